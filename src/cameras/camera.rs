@@ -0,0 +1,291 @@
+use crate::cameras::{Projection, ThinLens};
+use crate::primitives::{Canvas, Color, Matrix, Ray};
+use crate::sampling::{Rng, Supersampler};
+
+// ------------------------------------------------------
+/// Camera looking down `-z` in its own space. `transform` is the view
+/// transform from world to camera space; `projection` decides how image
+/// positions turn into rays.
+#[derive(Clone, Debug)]
+pub struct Camera {
+    hsize: usize,
+    vsize: usize,
+    projection: Projection,
+    transform: Matrix,
+    inverse: Matrix,
+    lens: ThinLens,
+}
+
+impl Camera {
+    /// Perspective camera; `field_of_view` is the angle, in radians, across
+    /// the longer side.
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
+        if hsize == 0 || vsize == 0 {
+            panic!("Invalid camera dimensions.");
+        }
+
+        Camera {
+            hsize,
+            vsize,
+            projection: Projection::Perspective { field_of_view },
+            transform: Matrix::identity(),
+            inverse: Matrix::identity(),
+            lens: ThinLens::default(),
+        }
+    }
+
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    pub fn with_transform(mut self, transform: Matrix) -> Self {
+        self.inverse = transform
+            .inverse()
+            .expect("Camera transform must be invertible.");
+        self.transform = transform;
+        self
+    }
+
+    pub fn with_lens(mut self, lens: ThinLens) -> Self {
+        self.lens = lens;
+        self
+    }
+
+    pub fn hsize(&self) -> usize {
+        self.hsize
+    }
+
+    pub fn vsize(&self) -> usize {
+        self.vsize
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn pixel_size(&self) -> f64 {
+        self.projection.pixel_size(self.hsize, self.vsize)
+    }
+
+    pub fn transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    pub fn lens(&self) -> ThinLens {
+        self.lens
+    }
+
+    /// Pinhole ray through the continuous image coordinates `(x, y)`, where
+    /// pixel `(i, j)` covers `[i, i + 1) x [j, j + 1)`. `None` where the
+    /// projection does not cover the image.
+    pub fn ray_for(&self, x: f64, y: f64) -> Option<Ray> {
+        self.projection
+            .ray(x, y, self.hsize, self.vsize)
+            .map(|ray| ray.transform(&self.inverse))
+    }
+
+    /// Pinhole ray through the center of pixel `(px, py)`.
+    pub fn ray_for_pixel(&self, px: usize, py: usize) -> Option<Ray> {
+        self.ray_for(px as f64 + 0.5, py as f64 + 0.5)
+    }
+
+    /// Ray through `(x, y)` leaving the lens at the unit square sample
+    /// `(lens_u, lens_v)`.
+    pub fn ray_through_lens(&self, x: f64, y: f64, lens_u: f64, lens_v: f64) -> Option<Ray> {
+        self.projection
+            .ray(x, y, self.hsize, self.vsize)
+            .map(|ray| {
+                self.lens
+                    .focus(&ray, lens_u, lens_v)
+                    .transform(&self.inverse)
+            })
+    }
+
+    /// Renders the image with `sampler`, averaging `lens_samples` rays per
+    /// image sample unless the lens is a pinhole. `trace` returns the color
+    /// seen along a world space ray; uncovered positions are black.
+    pub fn render(
+        &self,
+        sampler: &Supersampler,
+        lens_samples: usize,
+        trace: impl Fn(&Ray) -> Color,
+    ) -> Canvas {
+        let trace = |ray: Option<Ray>| ray.map_or(Color::black(), |ray| trace(&ray));
+
+        sampler.render(self.hsize, self.vsize, |x, y| {
+            if self.lens.is_pinhole() || lens_samples == 0 {
+                return trace(self.ray_for(x, y));
+            }
+
+            let mut rng = Rng::new(x.to_bits() ^ y.to_bits().rotate_left(32));
+            let sum = (0..lens_samples).fold(Color::black(), |acc, _| {
+                acc + trace(self.ray_through_lens(x, y, rng.next_f64(), rng.next_f64()))
+            });
+            sum * (1.0 / lens_samples as f64)
+        })
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{Point, Tuple, Vector};
+    use crate::sampling::SamplePattern;
+    use approx::assert_abs_diff_eq;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn camera_construction() {
+        let c = Camera::new(160, 120, PI / 2.0);
+        assert_eq!(c.hsize(), 160);
+        assert_eq!(c.vsize(), 120);
+        assert_eq!(
+            c.projection(),
+            Projection::Perspective {
+                field_of_view: PI / 2.0
+            }
+        );
+        assert_eq!(*c.transform(), Matrix::identity());
+        assert_eq!(c.lens(), ThinLens::pinhole());
+    }
+
+    #[test]
+    fn camera_pixel_size() {
+        assert_abs_diff_eq!(Camera::new(200, 125, PI / 2.0).pixel_size(), 0.01);
+        assert_abs_diff_eq!(Camera::new(125, 200, PI / 2.0).pixel_size(), 0.01);
+    }
+
+    #[test]
+    fn camera_ray_through_center() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(100, 50).unwrap();
+        assert_eq!(r.origin, Point::new(0.0, 0.0, 0.0));
+        assert_eq!(r.direction, Vector::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn camera_ray_through_corner() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(0, 0).unwrap();
+        assert_eq!(r.origin, Point::new(0.0, 0.0, 0.0));
+        assert_eq!(r.direction, Vector::new(0.66519, 0.33259, -0.66851));
+    }
+
+    #[test]
+    fn camera_ray_when_transformed() {
+        let (s, c) = (PI / 4.0).sin_cos();
+        #[rustfmt::skip]
+        let rotation_y = Matrix::new4().init([
+            c, 0.0, s, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            -s, 0.0, c, 0.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+        #[rustfmt::skip]
+        let translation = Matrix::new4().init([
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, -2.0,
+            0.0, 0.0, 1.0, 5.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+
+        let camera = Camera::new(201, 101, PI / 2.0).with_transform(rotation_y * translation);
+        let r = camera.ray_for_pixel(100, 50).unwrap();
+        let h = 2.0f64.sqrt() / 2.0;
+        assert_eq!(r.origin, Point::new(0.0, 2.0, -5.0));
+        assert_eq!(r.direction, Vector::new(h, 0.0, -h));
+    }
+
+    /// A wall at `z = -distance`, white where `x > 0` (the left of the image).
+    fn wall(distance: f64) -> impl Fn(&Ray) -> Color {
+        move |ray: &Ray| {
+            let t = (-distance - ray.origin.z()) / ray.direction.z();
+            if ray.position(t).x() > 0.0 {
+                Color::white()
+            } else {
+                Color::black()
+            }
+        }
+    }
+
+    #[test]
+    fn camera_render_focus() {
+        let camera = Camera::new(8, 1, PI / 2.0).with_lens(ThinLens::new(1.0, 4.0));
+        let sampler = Supersampler::new(SamplePattern::Regular(2));
+
+        // The edge between pixels 3 and 4 stays crisp on the focal plane.
+        let sharp = camera.render(&sampler, 16, wall(4.0));
+        assert_eq!(sharp[3][0], Color::white());
+        assert_eq!(sharp[4][0], Color::black());
+
+        // Behind the focal plane it bleeds into both neighbours, more so the
+        // further away it is.
+        let near = camera.render(&sampler, 64, wall(8.0));
+        let far = camera.render(&sampler, 64, wall(40.0));
+        assert!(near[3][0].r() < 1.0 && near[4][0].r() > 0.0);
+        assert!(far[3][0].r() < near[3][0].r());
+        assert!(far[4][0].r() > near[4][0].r());
+    }
+
+    #[test]
+    fn camera_render_pinhole_is_sharp() {
+        let camera = Camera::new(8, 1, PI / 2.0);
+        let canvas = camera.render(&Supersampler::default(), 16, wall(40.0));
+        for x in 0..8 {
+            let expected = if x < 4 {
+                Color::white()
+            } else {
+                Color::black()
+            };
+            assert_eq!(canvas[x][0], expected);
+        }
+    }
+
+    #[test]
+    fn camera_projections_share_the_canvas() {
+        let projections = [
+            Projection::Perspective {
+                field_of_view: PI / 3.0,
+            },
+            Projection::Orthographic { size: 6.0 },
+            Projection::Fisheye {
+                field_of_view: 2.0 * PI,
+            },
+            Projection::Equirectangular,
+        ];
+        for projection in projections {
+            let camera = Camera::new(12, 6, PI / 2.0).with_projection(projection);
+            let canvas = camera.render(&Supersampler::default(), 1, |ray| {
+                // Visualize the view direction.
+                let d = ray.direction;
+                Color::new(d.x().abs(), d.y().abs(), d.z().abs())
+            });
+            assert_eq!(canvas.width(), 12);
+            assert_eq!(canvas.height(), 6);
+        }
+    }
+
+    #[test]
+    fn camera_orthographic_view() {
+        let camera =
+            Camera::new(4, 4, PI / 2.0).with_projection(Projection::Orthographic { size: 8.0 });
+        let r = camera.ray_for_pixel(0, 3).unwrap();
+        assert_eq!(r.origin, Point::new(3.0, -3.0, 0.0));
+        assert_eq!(r.direction, Vector::new(0.0, 0.0, -1.0));
+        assert_eq!(camera.pixel_size(), 2.0);
+    }
+
+    #[test]
+    fn camera_fisheye_corners_are_uncovered() {
+        let camera = Camera::new(10, 10, PI).with_projection(Projection::Fisheye {
+            field_of_view: 2.0 * PI,
+        });
+        assert!(camera.ray_for_pixel(0, 0).is_none());
+        assert!(camera.ray_for_pixel(5, 5).is_some());
+        let canvas = camera.render(&Supersampler::default(), 1, |_| Color::white());
+        assert_eq!(canvas[0][0], Color::black());
+        assert_eq!(canvas[5][5], Color::white());
+    }
+}
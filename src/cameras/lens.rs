@@ -0,0 +1,182 @@
+use crate::primitives::{Ray, Tuple, Vector};
+use crate::sampling::{sample_disk, sample_polygon};
+
+// ------------------------------------------------------
+/// Outline of the aperture, which shapes out-of-focus highlights (bokeh).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ApertureShape {
+    #[default]
+    Disk,
+    /// Regular polygon formed by `blades` diaphragm blades, the first corner
+    /// at `rotation` radians.
+    Polygon { blades: usize, rotation: f64 },
+}
+
+// ------------------------------------------------------
+/// Thin-lens model: rays leave from a point on the aperture and pass through
+/// the point the pinhole ray reaches on the focal plane, so geometry at the
+/// focal distance stays sharp and everything else blurs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThinLens {
+    aperture: f64,
+    focal_distance: f64,
+    shape: ApertureShape,
+}
+
+impl ThinLens {
+    /// `aperture` is the lens radius in camera space units.
+    pub fn new(aperture: f64, focal_distance: f64) -> Self {
+        if aperture < 0.0 || focal_distance <= 0.0 {
+            panic!("Invalid thin lens parameters.");
+        }
+
+        ThinLens {
+            aperture,
+            focal_distance,
+            shape: ApertureShape::default(),
+        }
+    }
+
+    /// Zero aperture, every ray passes through the camera origin.
+    pub fn pinhole() -> Self {
+        ThinLens::new(0.0, 1.0)
+    }
+
+    pub fn with_shape(mut self, shape: ApertureShape) -> Self {
+        if let ApertureShape::Polygon { blades, .. } = shape {
+            if blades < 3 {
+                panic!("A polygon needs at least three blades.");
+            }
+        }
+        self.shape = shape;
+        self
+    }
+
+    pub fn aperture(&self) -> f64 {
+        self.aperture
+    }
+
+    pub fn focal_distance(&self) -> f64 {
+        self.focal_distance
+    }
+
+    pub fn shape(&self) -> ApertureShape {
+        self.shape
+    }
+
+    pub fn is_pinhole(&self) -> bool {
+        self.aperture == 0.0
+    }
+
+    /// Point on the aperture for the unit square sample `(u, v)`, in the
+    /// camera's xy plane.
+    pub fn sample(&self, u: f64, v: f64) -> (f64, f64) {
+        let (x, y) = match self.shape {
+            ApertureShape::Disk => sample_disk(u, v),
+            ApertureShape::Polygon { blades, rotation } => sample_polygon(blades, rotation, u, v),
+        };
+        (x * self.aperture, y * self.aperture)
+    }
+
+    /// Turns a camera-space ray looking down `-z` into the ray leaving the
+    /// lens at sample `(u, v)` towards the same focal plane point. Rays that
+    /// never reach the focal plane are returned unchanged.
+    pub fn focus(&self, ray: &Ray, u: f64, v: f64) -> Ray {
+        if self.is_pinhole() || ray.direction.z() >= 0.0 {
+            return *ray;
+        }
+
+        let focus = ray.position(self.focal_distance / -ray.direction.z());
+        let (x, y) = self.sample(u, v);
+        let origin = ray.origin + Vector::new(x, y, 0.0);
+        Ray::new(origin, (focus - origin).normalize())
+    }
+}
+
+impl Default for ThinLens {
+    fn default() -> Self {
+        ThinLens::pinhole()
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Point;
+    use crate::sampling::Rng;
+    use approx::assert_abs_diff_eq;
+
+    /// Where `ray` crosses the plane `z = -distance`.
+    fn hit_plane(ray: &Ray, distance: f64) -> Point {
+        ray.position((-distance - ray.origin.z()) / ray.direction.z())
+    }
+
+    fn pinhole_ray() -> Ray {
+        Ray::new(
+            Point::new(0.0, 0.0, 0.0),
+            Vector::new(0.1, -0.2, -1.0).normalize(),
+        )
+    }
+
+    #[test]
+    fn pinhole_lens_leaves_rays_untouched() {
+        let ray = pinhole_ray();
+        assert!(ThinLens::default().is_pinhole());
+        assert_eq!(ThinLens::pinhole().focus(&ray, 0.3, 0.9), ray);
+    }
+
+    #[test]
+    fn focal_plane_stays_sharp() {
+        let ray = pinhole_ray();
+        let expected = hit_plane(&ray, 5.0);
+        let mut rng = Rng::new(4);
+
+        for shape in [
+            ApertureShape::Disk,
+            ApertureShape::Polygon {
+                blades: 6,
+                rotation: 0.3,
+            },
+        ] {
+            let lens = ThinLens::new(0.5, 5.0).with_shape(shape);
+            for _ in 0..100 {
+                let res = lens.focus(&ray, rng.next_f64(), rng.next_f64());
+                assert_eq!(hit_plane(&res, 5.0), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn blur_grows_with_distance_from_focal_plane() {
+        let ray = pinhole_ray();
+        let lens = ThinLens::new(0.5, 5.0);
+
+        // Circle of confusion radius is aperture * |1 - d / f|; the disk
+        // sample at (1, 0.5) lies on the rim of the aperture.
+        for (distance, radius) in [(2.5, 0.25), (10.0, 0.5), (15.0, 1.0)] {
+            let res = lens.focus(&ray, 1.0, 0.5);
+            let offset = hit_plane(&res, distance) - hit_plane(&ray, distance);
+            assert_abs_diff_eq!(offset.magnitude(), radius, epsilon = 1e-9);
+        }
+
+        // Doubling the aperture doubles the blur.
+        let wide = ThinLens::new(1.0, 5.0);
+        let narrow_offset = hit_plane(&lens.focus(&ray, 0.9, 0.2), 12.0) - hit_plane(&ray, 12.0);
+        let wide_offset = hit_plane(&wide.focus(&ray, 0.9, 0.2), 12.0) - hit_plane(&ray, 12.0);
+        assert_eq!(wide_offset, narrow_offset * 2.0);
+    }
+
+    #[test]
+    fn polygon_aperture_bounds_samples() {
+        let lens = ThinLens::new(2.0, 1.0).with_shape(ApertureShape::Polygon {
+            blades: 5,
+            rotation: 0.0,
+        });
+        let mut rng = Rng::new(5);
+        for _ in 0..200 {
+            let (x, y) = lens.sample(rng.next_f64(), rng.next_f64());
+            assert!(x * x + y * y <= 4.0 + 1e-9);
+        }
+    }
+}
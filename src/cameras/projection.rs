@@ -0,0 +1,149 @@
+use std::f64::consts::PI;
+
+use crate::primitives::{Point, Ray, Tuple, Vector};
+
+// ------------------------------------------------------
+/// How image positions map to camera space rays. The camera looks down
+/// `-z` with `+y` up; angles and sizes are measured across the longer side
+/// of the image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// Pinhole projection onto a plane one unit in front of the camera.
+    Perspective { field_of_view: f64 },
+    /// Parallel rays from a `size` wide window through the camera origin.
+    Orthographic { size: f64 },
+    /// Equidistant fisheye: the angle from the view axis grows linearly with
+    /// the distance from the image center.
+    Fisheye { field_of_view: f64 },
+    /// Full 360 by 180 degree panorama, longitude across and latitude down
+    /// the image.
+    Equirectangular,
+}
+
+impl Projection {
+    /// Camera space ray through continuous image coordinates `(x, y)` of a
+    /// `hsize` by `vsize` image, or `None` if the projection does not cover
+    /// that position.
+    pub fn ray(&self, x: f64, y: f64, hsize: usize, vsize: usize) -> Option<Ray> {
+        let (w, h) = (hsize as f64, vsize as f64);
+        let half = w.max(h) / 2.0;
+        // Screen coordinates: +1 at the left and top ends of the longer side.
+        let sx = (w / 2.0 - x) / half;
+        let sy = (h / 2.0 - y) / half;
+        let origin = Point::new(0.0, 0.0, 0.0);
+
+        match *self {
+            Projection::Perspective { field_of_view } => {
+                let t = (field_of_view / 2.0).tan();
+                Some(Ray::new(
+                    origin,
+                    Vector::new(sx * t, sy * t, -1.0).normalize(),
+                ))
+            }
+            Projection::Orthographic { size } => Some(Ray::new(
+                Point::new(sx * size / 2.0, sy * size / 2.0, 0.0),
+                Vector::new(0.0, 0.0, -1.0),
+            )),
+            Projection::Fisheye { field_of_view } => {
+                let theta = (sx * sx + sy * sy).sqrt() * field_of_view / 2.0;
+                if theta > PI {
+                    return None;
+                }
+                let phi = sy.atan2(sx);
+                Some(Ray::new(
+                    origin,
+                    Vector::new(
+                        theta.sin() * phi.cos(),
+                        theta.sin() * phi.sin(),
+                        -theta.cos(),
+                    ),
+                ))
+            }
+            Projection::Equirectangular => {
+                let longitude = (x / w - 0.5) * 2.0 * PI;
+                let latitude = (0.5 - y / h) * PI;
+                Some(Ray::new(
+                    origin,
+                    Vector::new(
+                        -longitude.sin() * latitude.cos(),
+                        latitude.sin(),
+                        -longitude.cos() * latitude.cos(),
+                    ),
+                ))
+            }
+        }
+    }
+
+    /// Width of a pixel: on the image plane for perspective, in world units
+    /// for orthographic and in radians for the angular projections.
+    pub fn pixel_size(&self, hsize: usize, vsize: usize) -> f64 {
+        let longer = hsize.max(vsize) as f64;
+        match *self {
+            Projection::Perspective { field_of_view } => 2.0 * (field_of_view / 2.0).tan() / longer,
+            Projection::Orthographic { size } => size / longer,
+            Projection::Fisheye { field_of_view } => field_of_view / longer,
+            Projection::Equirectangular => 2.0 * PI / hsize as f64,
+        }
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn orthographic_rays_are_parallel() {
+        let p = Projection::Orthographic { size: 4.0 };
+        let center = p.ray(2.0, 1.0, 4, 2).unwrap();
+        assert_eq!(center.origin, Point::new(0.0, 0.0, 0.0));
+        assert_eq!(center.direction, Vector::new(0.0, 0.0, -1.0));
+
+        let corner = p.ray(0.0, 0.0, 4, 2).unwrap();
+        assert_eq!(corner.origin, Point::new(2.0, 1.0, 0.0));
+        assert_eq!(corner.direction, Vector::new(0.0, 0.0, -1.0));
+        assert_eq!(p.pixel_size(4, 2), 1.0);
+    }
+
+    #[test]
+    fn fisheye_angle_is_equidistant() {
+        let p = Projection::Fisheye { field_of_view: PI };
+        let axis = Vector::new(0.0, 0.0, -1.0);
+        assert_eq!(p.ray(50.0, 50.0, 100, 100).unwrap().direction, axis);
+
+        // Angle from the axis grows linearly with the distance from center.
+        for (x, angle) in [(25.0, PI / 4.0), (0.0, PI / 2.0), (100.0, PI / 2.0)] {
+            let dir = p.ray(x, 50.0, 100, 100).unwrap().direction;
+            assert_abs_diff_eq!(dir.dot(axis).acos(), angle, epsilon = 1e-9);
+        }
+        let diagonal = p.ray(0.0, 0.0, 100, 100).unwrap().direction;
+        assert_abs_diff_eq!(
+            diagonal.dot(axis).acos(),
+            PI / 2.0 * 2.0f64.sqrt(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn fisheye_beyond_half_turn_is_uncovered() {
+        let p = Projection::Fisheye {
+            field_of_view: 2.0 * PI,
+        };
+        assert!(p.ray(0.0, 50.0, 100, 100).is_some());
+        assert!(p.ray(0.0, 0.0, 100, 100).is_none());
+    }
+
+    #[test]
+    fn equirectangular_covers_the_sphere() {
+        let p = Projection::Equirectangular;
+        let dir = |x, y| p.ray(x, y, 360, 180).unwrap().direction;
+        assert_eq!(dir(180.0, 90.0), Vector::new(0.0, 0.0, -1.0));
+        assert_eq!(dir(90.0, 90.0), Vector::new(1.0, 0.0, 0.0));
+        assert_eq!(dir(270.0, 90.0), Vector::new(-1.0, 0.0, 0.0));
+        assert_eq!(dir(0.0, 90.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(dir(123.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(dir(42.0, 180.0), Vector::new(0.0, -1.0, 0.0));
+        assert_abs_diff_eq!(p.pixel_size(360, 180), PI / 180.0);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::cameras::Camera;
+use crate::primitives::{Canvas, Color, Matrix, Point, Ray, Tuple, Vector};
+use crate::sampling::Supersampler;
+
+// ------------------------------------------------------
+/// How a stereo pair is packed into one canvas.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StereoLayout {
+    /// Left view on the left half, right view on the right half.
+    #[default]
+    SideBySide,
+    /// Left view on top, right view below.
+    TopBottom,
+}
+
+// ------------------------------------------------------
+/// Two eyes `interocular` apart, centered on `camera` and toed in so their
+/// view axes cross `convergence` units in front of it. An infinite
+/// convergence gives parallel eyes.
+#[derive(Clone, Debug)]
+pub struct StereoRig {
+    camera: Camera,
+    interocular: f64,
+    convergence: f64,
+}
+
+impl StereoRig {
+    pub fn new(camera: Camera, interocular: f64, convergence: f64) -> Self {
+        if interocular < 0.0 || convergence <= 0.0 {
+            panic!("Invalid stereo rig parameters.");
+        }
+
+        StereoRig {
+            camera,
+            interocular,
+            convergence,
+        }
+    }
+
+    pub fn interocular(&self) -> f64 {
+        self.interocular
+    }
+
+    pub fn convergence(&self) -> f64 {
+        self.convergence
+    }
+
+    pub fn left_camera(&self) -> Camera {
+        self.eye(self.interocular / 2.0)
+    }
+
+    pub fn right_camera(&self) -> Camera {
+        self.eye(-self.interocular / 2.0)
+    }
+
+    /// Renders both views, left first.
+    pub fn render_views(
+        &self,
+        sampler: &Supersampler,
+        lens_samples: usize,
+        trace: impl Fn(&Ray) -> Color,
+    ) -> (Canvas, Canvas) {
+        (
+            self.left_camera().render(sampler, lens_samples, &trace),
+            self.right_camera().render(sampler, lens_samples, &trace),
+        )
+    }
+
+    /// Renders both views packed into one canvas.
+    pub fn render(
+        &self,
+        sampler: &Supersampler,
+        lens_samples: usize,
+        layout: StereoLayout,
+        trace: impl Fn(&Ray) -> Color,
+    ) -> Canvas {
+        let (left, right) = self.render_views(sampler, lens_samples, trace);
+        compose(&left, &right, layout)
+    }
+
+    /// Renders both views combined into a red/cyan anaglyph.
+    pub fn render_anaglyph(
+        &self,
+        sampler: &Supersampler,
+        lens_samples: usize,
+        trace: impl Fn(&Ray) -> Color,
+    ) -> Canvas {
+        let (left, right) = self.render_views(sampler, lens_samples, trace);
+        anaglyph(&left, &right)
+    }
+
+    /// Camera for the eye `offset` along the camera's x axis, which points
+    /// to the left of the image.
+    fn eye(&self, offset: f64) -> Camera {
+        let from = Point::new(offset, 0.0, 0.0);
+        let to = if self.convergence.is_finite() {
+            Point::new(0.0, 0.0, -self.convergence)
+        } else {
+            from + Vector::new(0.0, 0.0, -1.0)
+        };
+        let eye = view_transform(from, to, Vector::new(0.0, 1.0, 0.0));
+        self.camera
+            .clone()
+            .with_transform(eye * self.camera.transform().clone())
+    }
+}
+
+fn view_transform(from: Point, to: Point, up: Vector) -> Matrix {
+    let forward = (to - from).normalize();
+    let left = forward * up.normalize();
+    let true_up = left * forward;
+
+    #[rustfmt::skip]
+    let orientation = Matrix::new4().init([
+        left.x(), left.y(), left.z(), 0.0,
+        true_up.x(), true_up.y(), true_up.z(), 0.0,
+        -forward.x(), -forward.y(), -forward.z(), 0.0,
+        0.0, 0.0, 0.0, 1.0
+    ].to_vec());
+    #[rustfmt::skip]
+    let translation = Matrix::new4().init([
+        1.0, 0.0, 0.0, -from.x(),
+        0.0, 1.0, 0.0, -from.y(),
+        0.0, 0.0, 1.0, -from.z(),
+        0.0, 0.0, 0.0, 1.0
+    ].to_vec());
+    orientation * translation
+}
+
+// ------------------------------------------------------
+/// Packs two equally sized views into one canvas.
+pub fn compose(left: &Canvas, right: &Canvas, layout: StereoLayout) -> Canvas {
+    let (w, h) = (left.width(), left.height());
+    if right.width() != w || right.height() != h {
+        panic!("Stereo views must have the same dimensions.");
+    }
+
+    let (dx, dy) = match layout {
+        StereoLayout::SideBySide => (w, 0),
+        StereoLayout::TopBottom => (0, h),
+    };
+    let mut canvas = Canvas::new(w + dx, h + dy);
+    for y in 0..h {
+        for x in 0..w {
+            canvas[x][y] = left[x][y];
+            canvas[x + dx][y + dy] = right[x][y];
+        }
+    }
+    canvas
+}
+
+/// Red/cyan anaglyph: red from the left view, green and blue from the right.
+pub fn anaglyph(left: &Canvas, right: &Canvas) -> Canvas {
+    let (w, h) = (left.width(), left.height());
+    if right.width() != w || right.height() != h {
+        panic!("Stereo views must have the same dimensions.");
+    }
+
+    let cyan = Color::new(0.0, 1.0, 1.0);
+    let mut canvas = Canvas::new(w, h);
+    for y in 0..h {
+        for x in 0..w {
+            canvas[x][y] = left[x][y] * Color::red() + right[x][y] * cyan;
+        }
+    }
+    canvas
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn stereo_eyes_converge() {
+        let rig = StereoRig::new(Camera::new(11, 11, PI / 2.0), 0.2, 5.0);
+        let left = rig.left_camera().ray_for_pixel(5, 5).unwrap();
+        let right = rig.right_camera().ray_for_pixel(5, 5).unwrap();
+
+        assert_eq!(left.origin, Point::new(0.1, 0.0, 0.0));
+        assert_eq!(right.origin, Point::new(-0.1, 0.0, 0.0));
+        let t = (5.0f64 * 5.0 + 0.1 * 0.1).sqrt();
+        assert_eq!(left.position(t), Point::new(0.0, 0.0, -5.0));
+        assert_eq!(right.position(t), Point::new(0.0, 0.0, -5.0));
+    }
+
+    #[test]
+    fn stereo_parallel_eyes() {
+        let rig = StereoRig::new(Camera::new(11, 11, PI / 2.0), 0.065, f64::INFINITY);
+        for camera in [rig.left_camera(), rig.right_camera()] {
+            let ray = camera.ray_for_pixel(5, 5).unwrap();
+            assert_eq!(ray.direction, Vector::new(0.0, 0.0, -1.0));
+            assert_abs_diff_eq!(ray.origin.x().abs(), 0.0325);
+        }
+    }
+
+    #[test]
+    fn stereo_follows_camera_transform() {
+        #[rustfmt::skip]
+        let translation = Matrix::new4().init([
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, -2.0,
+            0.0, 0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+        let camera = Camera::new(11, 11, PI / 2.0).with_transform(translation);
+        let rig = StereoRig::new(camera, 0.2, f64::INFINITY);
+        let left = rig.left_camera().ray_for_pixel(5, 5).unwrap();
+        assert_eq!(left.origin, Point::new(0.1, 2.0, 0.0));
+    }
+
+    #[test]
+    fn stereo_layouts() {
+        let left = Canvas::new_with_bgcolor(3, 2, Color::red());
+        let right = Canvas::new_with_bgcolor(3, 2, Color::blue());
+
+        let sbs = compose(&left, &right, StereoLayout::SideBySide);
+        assert_eq!((sbs.width(), sbs.height()), (6, 2));
+        assert_eq!(sbs[2][1], Color::red());
+        assert_eq!(sbs[3][0], Color::blue());
+
+        let tb = compose(&left, &right, StereoLayout::TopBottom);
+        assert_eq!((tb.width(), tb.height()), (3, 4));
+        assert_eq!(tb[2][1], Color::red());
+        assert_eq!(tb[0][2], Color::blue());
+    }
+
+    #[test]
+    fn stereo_anaglyph_channels() {
+        let left = Canvas::new_with_bgcolor(2, 2, Color::new(0.8, 0.5, 0.2));
+        let right = Canvas::new_with_bgcolor(2, 2, Color::new(0.1, 0.6, 0.9));
+        let res = anaglyph(&left, &right);
+        assert_eq!(res[1][1], Color::new(0.8, 0.6, 0.9));
+    }
+
+    #[test]
+    fn stereo_render_views_differ() {
+        let rig = StereoRig::new(Camera::new(8, 1, PI / 2.0), 1.0, f64::INFINITY);
+        // A wall at z = -4, white left of the world origin.
+        let trace = |ray: &Ray| {
+            let t = (-4.0 - ray.origin.z()) / ray.direction.z();
+            if ray.position(t).x() > 0.0 {
+                Color::white()
+            } else {
+                Color::black()
+            }
+        };
+        let sampler = Supersampler::default();
+        let (left, right) = rig.render_views(&sampler, 1, trace);
+        // The left eye sits further left, so the edge moves right in its view
+        // and more of the white half shows.
+        let edge = |c: &Canvas| (0..8).filter(|&x| c[x][0] == Color::white()).count();
+        assert!(edge(&left) > edge(&right));
+
+        let packed = rig.render(&sampler, 1, StereoLayout::SideBySide, trace);
+        assert_eq!((packed.width(), packed.height()), (16, 1));
+        let combined = rig.render_anaglyph(&sampler, 1, trace);
+        assert_eq!((combined.width(), combined.height()), (8, 1));
+    }
+}
@@ -0,0 +1,87 @@
+use crate::primitives::{Color, Tuple, Vector};
+
+// ------------------------------------------------------
+/// What a ray sees when it leaves the scene without hitting anything.
+pub trait Background {
+    /// Radiance arriving from `direction`, which need not be normalized.
+    fn color_at(&self, direction: Vector) -> Color;
+}
+
+// ------------------------------------------------------
+/// The same color in every direction.
+#[derive(Clone, Copy, Debug)]
+pub struct Flat {
+    color: Color,
+}
+
+impl Flat {
+    pub fn new(color: Color) -> Self {
+        Flat { color }
+    }
+}
+
+impl Default for Flat {
+    fn default() -> Self {
+        Flat::new(Color::black())
+    }
+}
+
+impl Background for Flat {
+    fn color_at(&self, _direction: Vector) -> Color {
+        self.color
+    }
+}
+
+// ------------------------------------------------------
+/// Blends from `bottom` straight down to `top` straight up.
+#[derive(Clone, Copy, Debug)]
+pub struct VerticalGradient {
+    bottom: Color,
+    top: Color,
+}
+
+impl VerticalGradient {
+    pub fn new(bottom: Color, top: Color) -> Self {
+        VerticalGradient { bottom, top }
+    }
+}
+
+impl Background for VerticalGradient {
+    fn color_at(&self, direction: Vector) -> Color {
+        let t = 0.5 * (direction.normalize().y() + 1.0);
+        self.bottom * (1.0 - t) + self.top * t
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_background() {
+        let bg = Flat::new(Color::new(0.2, 0.3, 0.4));
+        assert_eq!(
+            bg.color_at(Vector::new(0.0, 0.0, 1.0)),
+            Color::new(0.2, 0.3, 0.4)
+        );
+        assert_eq!(
+            Flat::default().color_at(Vector::new(1.0, 0.0, 0.0)),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn vertical_gradient_background() {
+        let bg = VerticalGradient::new(Color::white(), Color::new(0.5, 0.7, 1.0));
+        assert_eq!(bg.color_at(Vector::new(0.0, -3.0, 0.0)), Color::white());
+        assert_eq!(
+            bg.color_at(Vector::new(0.0, 2.0, 0.0)),
+            Color::new(0.5, 0.7, 1.0)
+        );
+        assert_eq!(
+            bg.color_at(Vector::new(1.0, 0.0, 1.0)),
+            Color::new(0.75, 0.85, 1.0)
+        );
+    }
+}
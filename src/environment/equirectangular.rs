@@ -0,0 +1,87 @@
+use crate::environment::Background;
+use crate::primitives::{Color, Vector};
+use crate::textures::{ImageTexture, UvMapping};
+
+// ------------------------------------------------------
+/// A latitude/longitude environment image wrapped around the scene.
+///
+/// The canvas holds unclamped floating point radiance, so high dynamic
+/// range maps read with `Canvas::from_pfm` keep their bright spots;
+/// `intensity` scales the whole map.
+pub struct Equirectangular {
+    texture: ImageTexture,
+    intensity: f64,
+}
+
+impl Equirectangular {
+    pub fn new(texture: ImageTexture) -> Self {
+        Equirectangular {
+            texture,
+            intensity: 1.0,
+        }
+    }
+
+    pub fn with_intensity(self, intensity: f64) -> Self {
+        Equirectangular { intensity, ..self }
+    }
+}
+
+impl Background for Equirectangular {
+    fn color_at(&self, direction: Vector) -> Color {
+        let (u, v) = UvMapping::Spherical.map(direction.normalize().to_point());
+        self.texture.sample(u, v) * self.intensity
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{Canvas, Tuple};
+    use crate::textures::TextureFilter;
+
+    #[test]
+    fn equirectangular_lookup() {
+        // Top row sky, bottom row ground; columns split the horizon into
+        // the four quadrants u = 0..0.25..0.5..0.75..1.
+        let mut canvas = Canvas::new(4, 2);
+        for x in 0..4 {
+            canvas[x][0] = Color::new(0.0, 0.0, 1.0 + x as f64);
+            canvas[x][1] = Color::new(0.0, 1.0 + x as f64, 0.0);
+        }
+        let texture = ImageTexture::new(canvas).with_filter(TextureFilter::Nearest);
+        let bg = Equirectangular::new(texture).with_intensity(2.0);
+
+        // Looking up and slightly toward +x lands in the second column.
+        assert_eq!(
+            bg.color_at(Vector::new(1.0, 1.0, 0.2)),
+            Color::new(0.0, 0.0, 4.0)
+        );
+        // Looking down and toward -x lands in the fourth column.
+        assert_eq!(
+            bg.color_at(Vector::new(-1.0, -1.0, -0.2)),
+            Color::new(0.0, 8.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn equirectangular_from_pfm_keeps_the_sun() {
+        // A 2x1 probe: a sun a thousand times brighter than the sky.
+        let mut pfm = b"PF\n2 1\n-1.0\n".to_vec();
+        for sample in [1000.0f32, 1000.0, 900.0, 0.2, 0.3, 0.5] {
+            pfm.extend_from_slice(&sample.to_le_bytes());
+        }
+        let canvas = Canvas::from_pfm(&pfm).unwrap();
+        let texture = ImageTexture::new(canvas).with_filter(TextureFilter::Nearest);
+        let bg = Equirectangular::new(texture);
+
+        assert_eq!(
+            bg.color_at(Vector::new(1.0, 0.0, 0.2)),
+            Color::new(1000.0, 1000.0, 900.0)
+        );
+        assert_eq!(
+            bg.color_at(Vector::new(-1.0, 0.0, -0.2)),
+            Color::new(0.2, 0.3, 0.5)
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use crate::environment::Background;
+use crate::primitives::{Color, Tuple, Vector};
+use crate::textures::{cube_uv, ImageTexture};
+
+// ------------------------------------------------------
+/// Six images on the faces of a cube surrounding the scene, in
+/// `CubeFace::ALL` order.
+pub struct SkyBox {
+    faces: [ImageTexture; 6],
+}
+
+impl SkyBox {
+    pub fn new(faces: [ImageTexture; 6]) -> Self {
+        SkyBox { faces }
+    }
+}
+
+impl Background for SkyBox {
+    fn color_at(&self, direction: Vector) -> Color {
+        // Project the direction onto the cube [-1, 1]³.
+        let a = direction.abs();
+        let largest = a.x().max(a.y()).max(a.z());
+        let (face, u, v) = cube_uv((direction / largest).to_point());
+        self.faces[face.index()].sample(u, v)
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Canvas;
+    use crate::textures::{CubeFace, TextureFilter};
+
+    #[test]
+    fn sky_box_faces() {
+        let faces = CubeFace::ALL.map(|face| {
+            let c = face.index() as f64;
+            ImageTexture::new(Canvas::new_with_bgcolor(2, 2, Color::new(c, c, c)))
+        });
+        let bg = SkyBox::new(faces);
+
+        let cases = [
+            (Vector::new(-5.0, 1.0, 2.0), CubeFace::Left),
+            (Vector::new(0.1, 0.2, 3.0), CubeFace::Front),
+            (Vector::new(2.0, -1.0, 0.0), CubeFace::Right),
+            (Vector::new(0.0, 0.5, -0.6), CubeFace::Back),
+            (Vector::new(0.3, 9.0, 0.3), CubeFace::Up),
+            (Vector::new(0.0, -1.0, 0.0), CubeFace::Down),
+        ];
+        for (direction, face) in cases {
+            let c = face.index() as f64;
+            assert_eq!(bg.color_at(direction), Color::new(c, c, c));
+        }
+    }
+
+    #[test]
+    fn sky_box_samples_within_face() {
+        // Front face: red on the left half, blue on the right.
+        let mut front = Canvas::new(2, 1);
+        front[0][0] = Color::red();
+        front[1][0] = Color::blue();
+        let mut faces = CubeFace::ALL.map(|_| ImageTexture::new(Canvas::new(1, 1)));
+        faces[CubeFace::Front.index()] =
+            ImageTexture::new(front).with_filter(TextureFilter::Nearest);
+        let bg = SkyBox::new(faces);
+
+        assert_eq!(bg.color_at(Vector::new(-0.5, 0.0, 1.0)), Color::red());
+        assert_eq!(bg.color_at(Vector::new(0.5, 0.0, 1.0)), Color::blue());
+    }
+}
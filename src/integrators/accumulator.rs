@@ -0,0 +1,95 @@
+use crate::primitives::{Canvas, Color};
+
+// ------------------------------------------------------
+/// Running sum of render passes for progressive rendering; `canvas` gives
+/// the average so far and can be called after every pass.
+#[derive(Clone, Debug)]
+pub struct Accumulator {
+    width: usize,
+    height: usize,
+    sum: Vec<Color>,
+    passes: usize,
+}
+
+impl Accumulator {
+    pub fn new(width: usize, height: usize) -> Self {
+        if width == 0 || height == 0 {
+            panic!("Invalid accumulator dimensions.");
+        }
+
+        Accumulator {
+            width,
+            height,
+            sum: vec![Color::black(); width * height],
+            passes: 0,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn passes(&self) -> usize {
+        self.passes
+    }
+
+    pub fn add(&mut self, pass: &Canvas) {
+        if pass.width() != self.width || pass.height() != self.height {
+            panic!("Render pass does not match the accumulator dimensions.");
+        }
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = y * self.width + x;
+                self.sum[i] = self.sum[i] + pass[x][y];
+            }
+        }
+        self.passes += 1;
+    }
+
+    /// Average of all passes, black before the first one.
+    pub fn canvas(&self) -> Canvas {
+        let scale = 1.0 / self.passes.max(1) as f64;
+        let mut canvas = Canvas::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                canvas[x][y] = self.sum[y * self.width + x] * scale;
+            }
+        }
+        canvas
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulator_averages_passes() {
+        let mut acc = Accumulator::new(2, 3);
+        assert_eq!(acc.passes(), 0);
+        assert_eq!(acc.canvas()[1][2], Color::black());
+
+        acc.add(&Canvas::new_with_bgcolor(2, 3, Color::new(1.0, 0.0, 0.5)));
+        let mut second = Canvas::new_with_bgcolor(2, 3, Color::new(0.0, 1.0, 0.5));
+        second[1][2] = Color::new(3.0, 3.0, 3.0);
+        acc.add(&second);
+
+        let res = acc.canvas();
+        assert_eq!(acc.passes(), 2);
+        assert_eq!(res[0][0], Color::new(0.5, 0.5, 0.5));
+        assert_eq!(res[1][2], Color::new(2.0, 1.5, 1.75));
+    }
+
+    #[test]
+    #[should_panic]
+    fn accumulator_rejects_mismatched_pass() {
+        let mut acc = Accumulator::new(2, 3);
+        acc.add(&Canvas::new(3, 2));
+    }
+}
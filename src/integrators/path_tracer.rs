@@ -0,0 +1,1580 @@
+use std::cell::RefCell;
+use std::f64::consts::PI;
+use std::ops::{Add, Mul};
+
+use crate::cameras::Camera;
+use crate::environment::Background;
+use crate::lights::{Light, LightSample, MeshLight};
+use crate::materials::{Bsdf, BsdfSample, Emission, Lambertian, Subsurface};
+use crate::media::{HenyeyGreenstein, Medium, MediumSample};
+use crate::primitives::{Canvas, Color, Point, Ray, Vector, EPSILON};
+use crate::sampling::{orthonormal_basis, Heuristic, Rng, SamplePattern, Supersampler};
+#[cfg(feature = "spectral")]
+use crate::spectral::{Ior, SampledSpectrum, SampledWavelengths};
+
+/// Wavelength, in nanometres, at which RGB paths refract: the helium d line,
+/// where refractive indices are usually quoted.
+#[cfg(feature = "spectral")]
+const D_LINE: f64 = 587.56;
+
+// ------------------------------------------------------
+/// Where a ray hits a surface.
+#[derive(Clone, Copy)]
+pub struct SurfaceInteraction<'a> {
+    /// Distance along the ray.
+    pub t: f64,
+    pub point: Point,
+    /// Unit surface normal; it is flipped to face the ray when shading.
+    pub normal: Vector,
+    pub bsdf: &'a dyn Bsdf,
+    pub emission: Emission,
+}
+
+/// What the path tracer needs to know about the scene.
+pub trait Scene {
+    /// Closest hit in front of the ray origin.
+    fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction<'_>>;
+
+    /// Lights sampled explicitly at every bounce.
+    fn lights(&self) -> &[Box<dyn Light>];
+
+    /// Geometry of the emissive shapes `intersect` returns, sampled as
+    /// lights in proportion to their area. Each emits from the side its
+    /// normals face, with the emission of the interactions on it; emissive
+    /// shapes left out only add light where paths happen to hit them.
+    fn emitters(&self) -> &[MeshLight] {
+        &[]
+    }
+
+    /// Environment lighting paths that escape the scene; `None` is black.
+    fn background(&self) -> Option<&dyn Background> {
+        None
+    }
+
+    /// Participating media rays travel through; none for clear air.
+    fn media(&self) -> &[Box<dyn Medium>] {
+        &[]
+    }
+}
+
+// ------------------------------------------------------
+/// Survival probability for a path carrying `throughput`, or `None` when it
+/// should be terminated. Dividing the throughput by the returned value keeps
+/// the estimate unbiased.
+pub fn russian_roulette(throughput: Color, rng: &mut Rng) -> Option<f64> {
+    survival(Rgb::max(throughput), rng)
+}
+
+/// Russian roulette for a throughput whose largest component is `max`.
+fn survival(max: f64, rng: &mut Rng) -> Option<f64> {
+    let p = max.clamp(0.05, 1.0);
+    if rng.next_f64() < p {
+        Some(p)
+    } else {
+        None
+    }
+}
+
+// ------------------------------------------------------
+/// Unidirectional path tracer with Russian roulette. Direct light from
+/// lights with an area is estimated by sampling both the light and the
+/// BSDF and combining the two with multiple importance sampling.
+///
+/// Rays passing through participating media may scatter on the way; light
+/// reaching a scattering point is estimated by sampling the lights and the
+/// phase function in the same way, and shadow rays are attenuated by the
+/// media they cross.
+///
+/// Light entering a translucent `Subsurface` material leaves it elsewhere: a
+/// probe ray through a point drawn from the diffusion profile finds the
+/// exit on the same surface, which then reflects diffusely.
+///
+/// Emissive shapes listed in `Scene::emitters` are lights too: each vertex
+/// samples a point on them, weighted against a BSDF sample that hits them,
+/// and later bounces reaching them add nothing more.
+///
+/// Point-like delta light intensities are radiant intensity, falling off
+/// with the squared distance; directional lights deliver their intensity as
+/// irradiance. Area light intensities are emitted radiance.
+///
+/// With the `spectral` feature, paths pass through smooth `Dielectric`
+/// interfaces, refracting at the d line or, when traced spectrally, at the
+/// wavelengths they carry.
+#[derive(Clone, Copy, Debug)]
+pub struct PathTracer {
+    max_depth: usize,
+    roulette_depth: usize,
+    light_samples: usize,
+    bsdf_samples: usize,
+    heuristic: Heuristic,
+}
+
+impl PathTracer {
+    pub fn new(max_depth: usize) -> Self {
+        PathTracer {
+            max_depth,
+            roulette_depth: 3,
+            light_samples: 1,
+            bsdf_samples: 1,
+            heuristic: Heuristic::default(),
+        }
+    }
+
+    /// Bounces after which Russian roulette may end a path.
+    pub fn with_roulette_depth(mut self, depth: usize) -> Self {
+        self.roulette_depth = depth;
+        self
+    }
+
+    /// Samples drawn on each area light per bounce; zero leaves area lights
+    /// to BSDF sampling alone.
+    pub fn with_light_samples(mut self, samples: usize) -> Self {
+        self.light_samples = samples;
+        self
+    }
+
+    /// BSDF directions tested against each area light per bounce; zero
+    /// leaves area lights to light sampling alone.
+    pub fn with_bsdf_samples(mut self, samples: usize) -> Self {
+        self.bsdf_samples = samples;
+        self
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn roulette_depth(&self) -> usize {
+        self.roulette_depth
+    }
+
+    pub fn light_samples(&self) -> usize {
+        self.light_samples
+    }
+
+    pub fn bsdf_samples(&self) -> usize {
+        self.bsdf_samples
+    }
+
+    pub fn heuristic(&self) -> Heuristic {
+        self.heuristic
+    }
+
+    /// One-sample estimate of the radiance arriving along `ray`.
+    pub fn trace(&self, scene: &impl Scene, ray: &Ray, rng: &mut Rng) -> Color {
+        self.radiance(scene, ray, rng, &mut Rgb)
+    }
+
+    /// One-sample estimate of the radiance arriving along `ray`, carried at
+    /// wavelengths sampled for the path and converted back to a color with
+    /// the CIE matching functions. The scene's colors become smooth spectra
+    /// (`SampledSpectrum::from_rgb`), so light is filtered wavelength by
+    /// wavelength as it scatters; a path refracted by a dispersive interface
+    /// follows its hero wavelength alone.
+    #[cfg(feature = "spectral")]
+    pub fn trace_spectral(&self, scene: &impl Scene, ray: &Ray, rng: &mut Rng) -> Color {
+        let mut wavelengths = SampledWavelengths::sample(rng.next_f64());
+        let radiance = self.radiance(scene, ray, rng, &mut wavelengths);
+        radiance.to_color(&wavelengths)
+    }
+
+    fn radiance<C: Carrier>(
+        &self,
+        scene: &impl Scene,
+        ray: &Ray,
+        rng: &mut Rng,
+        carrier: &mut C,
+    ) -> C::Value {
+        let mut radiance = carrier.convert(Color::black());
+        let mut throughput = carrier.convert(Color::white());
+        let mut ray = *ray;
+        let emitters = Emitters::new(scene.emitters());
+        let exitant = Lambertian::new(Color::white());
+        // Whether light along `ray` went unsampled at the vertex it left:
+        // true for camera rays and rays through smooth interfaces.
+        let mut specular = true;
+
+        for depth in 0..self.max_depth {
+            let hit = scene.intersect(&ray);
+
+            // Lights with an area are seen directly by camera rays; later
+            // bounces reaching them were already counted as direct light.
+            let light = scene
+                .lights()
+                .iter()
+                .filter_map(|light| light.hit(&ray))
+                .min_by(|a, b| a.distance.total_cmp(&b.distance));
+            let t_light = light.map_or(f64::INFINITY, |light| {
+                light.distance / ray.direction.magnitude()
+            });
+            let t_max = hit.map_or(f64::INFINITY, |hit| hit.t).min(t_light);
+
+            if let Some((medium, sample)) = scatter(scene, &ray, t_max, rng) {
+                throughput = throughput * carrier.convert(sample.albedo);
+                let wo = -ray.direction.normalize();
+                let phase = Scatterer::Medium(medium.phase());
+                for light in scene.lights() {
+                    let direct = self.direct_light(
+                        scene,
+                        carrier,
+                        light.as_ref(),
+                        &phase,
+                        sample.point,
+                        wo,
+                        rng,
+                    );
+                    radiance = radiance + throughput * direct;
+                }
+                let emitted =
+                    self.emitter_light(scene, carrier, &emitters, &phase, sample.point, wo, rng);
+                radiance = radiance + throughput * emitted;
+
+                if depth + 1 >= self.roulette_depth {
+                    match survival(C::max(throughput), rng) {
+                        Some(p) => throughput = throughput * (1.0 / p),
+                        None => break,
+                    }
+                }
+                match phase.sample(wo, rng.next_f64(), rng.next_f64()) {
+                    Some(next) => ray = Ray::new(sample.point, next.direction),
+                    None => break,
+                }
+                specular = false;
+                continue;
+            }
+
+            if let Some(light) = light {
+                if hit.is_none_or(|hit| t_light < hit.t) {
+                    if specular {
+                        radiance = radiance + throughput * carrier.convert(light.radiance);
+                    }
+                    return radiance;
+                }
+            }
+
+            let hit = match hit {
+                Some(hit) => hit,
+                None => {
+                    let background = background(scene, ray.direction);
+                    return radiance + throughput * carrier.convert(background);
+                }
+            };
+            // Listed emitters reached by a bounce were counted as light at
+            // the vertex before.
+            if specular || !emitters.lists(&ray, hit.t) {
+                radiance = radiance + throughput * carrier.convert(hit.emission.radiance());
+            }
+
+            let mut wo = -ray.direction.normalize();
+            let mut normal = if hit.normal.dot(wo) < 0.0 {
+                -hit.normal
+            } else {
+                hit.normal
+            };
+            let mut point = hit.point;
+            let mut bsdf = hit.bsdf;
+
+            #[cfg(feature = "spectral")]
+            if let Some(dielectric) = hit.bsdf.dielectric() {
+                let lambda = carrier.wavelength(dielectric.ior());
+                let direction = dielectric.scatter(wo, hit.normal, lambda, rng.next_f64());
+                let side = if direction.dot(normal) > 0.0 {
+                    normal
+                } else {
+                    -normal
+                };
+                ray = Ray::new(point + side * EPSILON, direction);
+                specular = true;
+                continue;
+            }
+
+            // Light entering a translucent material leaves it elsewhere,
+            // diffusely.
+            if let Some(material) = hit.bsdf.subsurface().filter(|m| m.is_translucent()) {
+                let Some((exit, exit_normal, weight)) =
+                    probe(scene, material, hit.bsdf, hit.point, normal, rng)
+                else {
+                    break;
+                };
+                throughput = throughput * carrier.convert(weight);
+                point = exit;
+                normal = exit_normal;
+                wo = exit_normal;
+                bsdf = &exitant;
+            }
+            let origin = point + normal * EPSILON;
+
+            let surface = Scatterer::Surface { bsdf, normal };
+            for light in scene.lights() {
+                let direct =
+                    self.direct_light(scene, carrier, light.as_ref(), &surface, origin, wo, rng);
+                radiance = radiance + throughput * direct;
+            }
+            let emitted = self.emitter_light(scene, carrier, &emitters, &surface, origin, wo, rng);
+            radiance = radiance + throughput * emitted;
+
+            if depth + 1 >= self.roulette_depth {
+                match survival(C::max(throughput), rng) {
+                    Some(p) => throughput = throughput * (1.0 / p),
+                    None => break,
+                }
+            }
+
+            match bsdf.sample(wo, normal, rng.next_f64(), rng.next_f64()) {
+                Some(sample) => {
+                    throughput = throughput * carrier.convert(sample.weight);
+                    ray = Ray::new(origin, sample.direction);
+                }
+                None => break,
+            }
+            specular = false;
+        }
+
+        radiance
+    }
+
+    /// Light scattered towards `wo` straight from `light`.
+    #[allow(clippy::too_many_arguments)]
+    fn direct_light<C: Carrier>(
+        &self,
+        scene: &impl Scene,
+        carrier: &C,
+        light: &dyn Light,
+        scatterer: &Scatterer,
+        origin: Point,
+        wo: Vector,
+        rng: &mut Rng,
+    ) -> C::Value {
+        let mut res = carrier.convert(Color::black());
+
+        if light.is_delta() {
+            for sample in light.samples(origin) {
+                let f = scatterer.eval(wo, sample.direction);
+                if f.is_black() {
+                    continue;
+                }
+                let tr = transmittance(scene, origin, sample.direction, sample.distance, rng);
+                let falloff = if sample.distance.is_finite() {
+                    sample.distance * sample.distance
+                } else {
+                    1.0
+                };
+                let li = carrier.convert(f) * carrier.convert(sample.intensity);
+                res = res + li * (tr / falloff);
+            }
+            return res;
+        }
+
+        let (nl, nb) = (self.light_samples, self.bsdf_samples);
+        for _ in 0..nl {
+            let Some((sample, pdf)) = light.sample_li(origin, rng.next_f64(), rng.next_f64())
+            else {
+                continue;
+            };
+            let f = scatterer.eval(wo, sample.direction);
+            if f.is_black() {
+                continue;
+            }
+            let tr = transmittance(scene, origin, sample.direction, sample.distance, rng);
+            let weight = self
+                .heuristic
+                .weight(nl, pdf, nb, scatterer.pdf(wo, sample.direction));
+            let li = carrier.convert(f) * carrier.convert(sample.intensity);
+            res = res + li * (tr * weight / (pdf * nl as f64));
+        }
+
+        for _ in 0..nb {
+            let Some(sample) = scatterer.sample(wo, rng.next_f64(), rng.next_f64()) else {
+                continue;
+            };
+            let Some(hit) = light.hit(&Ray::new(origin, sample.direction)) else {
+                continue;
+            };
+            let tr = transmittance(scene, origin, sample.direction, hit.distance, rng);
+            let weight = self.heuristic.weight(nb, sample.pdf, nl, hit.pdf);
+            let li = carrier.convert(sample.weight) * carrier.convert(hit.radiance);
+            res = res + li * (tr * weight / nb as f64);
+        }
+
+        res
+    }
+
+    /// Light scattered towards `wo` straight from the scene's emissive
+    /// shapes, combining area samples on them with BSDF samples that hit
+    /// them.
+    #[allow(clippy::too_many_arguments)]
+    fn emitter_light<C: Carrier>(
+        &self,
+        scene: &impl Scene,
+        carrier: &C,
+        emitters: &Emitters,
+        scatterer: &Scatterer,
+        origin: Point,
+        wo: Vector,
+        rng: &mut Rng,
+    ) -> C::Value {
+        let mut res = carrier.convert(Color::black());
+        if emitters.is_empty() {
+            return res;
+        }
+
+        let (nl, nb) = (self.light_samples, self.bsdf_samples);
+        for _ in 0..nl {
+            let Some((sample, pdf)) = emitters.sample_li(origin, rng.next_f64(), rng.next_f64())
+            else {
+                continue;
+            };
+            let f = scatterer.eval(wo, sample.direction);
+            if f.is_black() {
+                continue;
+            }
+            // Stop short of the emitter, which blocks the shadow ray itself.
+            let distance = sample.distance - EPSILON;
+            let tr = transmittance(scene, origin, sample.direction, distance, rng);
+            let weight = self
+                .heuristic
+                .weight(nl, pdf, nb, scatterer.pdf(wo, sample.direction));
+            let li = carrier.convert(f) * carrier.convert(sample.intensity);
+            res = res + li * (tr * weight / (pdf * nl as f64));
+        }
+
+        for _ in 0..nb {
+            let Some(sample) = scatterer.sample(wo, rng.next_f64(), rng.next_f64()) else {
+                continue;
+            };
+            let ray = Ray::new(origin, sample.direction);
+            let Some(hit) = scene.intersect(&ray) else {
+                continue;
+            };
+            // Unlisted emissive shapes are counted when the path reaches
+            // them instead.
+            if hit.emission.is_black() || !emitters.lists(&ray, hit.t) {
+                continue;
+            }
+            let tr = transmittance(scene, origin, sample.direction, hit.t - EPSILON, rng);
+            let light_pdf = emitters.pdf(sample.direction, hit.t, hit.normal);
+            let weight = self.heuristic.weight(nb, sample.pdf, nl, light_pdf);
+            let li = carrier.convert(sample.weight) * carrier.convert(hit.emission.radiance());
+            res = res + li * (tr * weight / nb as f64);
+        }
+
+        res
+    }
+
+    /// Renders one pass with one jittered path per pixel; `pass` seeds the
+    /// random numbers so successive passes can be accumulated.
+    pub fn render_pass(&self, camera: &Camera, scene: &impl Scene, pass: u64) -> Canvas {
+        let rng = RefCell::new(Rng::new(pass.wrapping_mul(0x2545_F491_4F6C_DD1D)));
+        let sampler = Supersampler::new(SamplePattern::Jittered(1)).with_seed(pass);
+        camera.render(&sampler, 1, |ray| {
+            self.trace(scene, ray, &mut rng.borrow_mut())
+        })
+    }
+
+    /// Renders one pass like `render_pass`, tracing each path spectrally.
+    #[cfg(feature = "spectral")]
+    pub fn render_spectral_pass(&self, camera: &Camera, scene: &impl Scene, pass: u64) -> Canvas {
+        let rng = RefCell::new(Rng::new(pass.wrapping_mul(0x2545_F491_4F6C_DD1D)));
+        let sampler = Supersampler::new(SamplePattern::Jittered(1)).with_seed(pass);
+        camera.render(&sampler, 1, |ray| {
+            self.trace_spectral(scene, ray, &mut rng.borrow_mut())
+        })
+    }
+}
+
+/// What a path carries light as: RGB, or values at the wavelengths of a
+/// spectrally traced path, into which the scene's colors are converted.
+trait Carrier {
+    type Value: Copy
+        + Add<Output = Self::Value>
+        + Mul<Output = Self::Value>
+        + Mul<f64, Output = Self::Value>;
+
+    fn convert(&self, color: Color) -> Self::Value;
+
+    /// Largest component, which Russian roulette goes by.
+    fn max(value: Self::Value) -> f64;
+
+    /// Wavelength at which the path refracts through `ior`.
+    #[cfg(feature = "spectral")]
+    fn wavelength(&mut self, ior: Ior) -> f64;
+}
+
+/// Paths carrying RGB colors.
+struct Rgb;
+
+impl Carrier for Rgb {
+    type Value = Color;
+
+    fn convert(&self, color: Color) -> Color {
+        color
+    }
+
+    fn max(value: Color) -> f64 {
+        value.r().max(value.g()).max(value.b())
+    }
+
+    #[cfg(feature = "spectral")]
+    fn wavelength(&mut self, _ior: Ior) -> f64 {
+        D_LINE
+    }
+}
+
+#[cfg(feature = "spectral")]
+impl Carrier for SampledWavelengths {
+    type Value = SampledSpectrum;
+
+    fn convert(&self, color: Color) -> SampledSpectrum {
+        SampledSpectrum::from_rgb(color, self)
+    }
+
+    fn max(value: SampledSpectrum) -> f64 {
+        value.values().into_iter().fold(0.0, f64::max)
+    }
+
+    fn wavelength(&mut self, ior: Ior) -> f64 {
+        // Each wavelength refracts its own way, so only the hero goes on.
+        if ior.is_dispersive() {
+            self.terminate_secondary();
+        }
+        self.lambda(0)
+    }
+}
+
+/// Radiance arriving from `direction` when a path escapes the scene.
+fn background(scene: &impl Scene, direction: Vector) -> Color {
+    scene
+        .background()
+        .map_or(Color::black(), |background| background.color_at(direction))
+}
+
+/// The scene's emissive shapes as one light, sampled in proportion to area.
+struct Emitters<'a> {
+    shapes: &'a [MeshLight],
+    /// Running sum of the shape areas.
+    cdf: Vec<f64>,
+}
+
+impl<'a> Emitters<'a> {
+    fn new(shapes: &'a [MeshLight]) -> Self {
+        let cdf = shapes
+            .iter()
+            .scan(0.0, |sum, shape| {
+                *sum += shape.area();
+                Some(*sum)
+            })
+            .collect();
+        Emitters { shapes, cdf }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    fn area(&self) -> f64 {
+        self.cdf.last().copied().unwrap_or(0.0)
+    }
+
+    /// Whether `ray` meets one of the shapes at `t`.
+    fn lists(&self, ray: &Ray, t: f64) -> bool {
+        self.shapes
+            .iter()
+            .flat_map(MeshLight::emitters)
+            .filter_map(|emitter| emitter.intersect(ray))
+            .any(|(hit, _)| (hit - t).abs() < EPSILON)
+    }
+
+    /// Direction towards a point spread uniformly over all the shapes, with
+    /// its solid angle density.
+    fn sample_li(&self, point: Point, u: f64, v: f64) -> Option<(LightSample, f64)> {
+        let target = u * self.area();
+        let index = self
+            .cdf
+            .partition_point(|&sum| sum <= target)
+            .min(self.cdf.len() - 1);
+        let start = if index == 0 { 0.0 } else { self.cdf[index - 1] };
+        let shape = &self.shapes[index];
+        let u = ((target - start) / shape.area()).clamp(0.0, 1.0);
+
+        let (sample, pdf) = shape.sample_li(point, u, v)?;
+        Some((sample, pdf * shape.area() / self.area()))
+    }
+
+    /// Solid angle density with which `sample_li` picks the unit
+    /// `direction` reaching a shape `distance` away where its normal is
+    /// `normal`; zero from behind.
+    fn pdf(&self, direction: Vector, distance: f64, normal: Vector) -> f64 {
+        let cos = -direction.dot(normal);
+        if cos <= 0.0 {
+            return 0.0;
+        }
+        distance * distance / (self.area() * cos)
+    }
+}
+
+/// What light scatters off at a path vertex.
+enum Scatterer<'a> {
+    Surface { bsdf: &'a dyn Bsdf, normal: Vector },
+    Medium(HenyeyGreenstein),
+}
+
+impl Scatterer<'_> {
+    /// Scattering function times the cosine term for surfaces.
+    fn eval(&self, wo: Vector, wi: Vector) -> Color {
+        match self {
+            Scatterer::Surface { bsdf, normal } => {
+                let cos = wi.dot(*normal);
+                if cos <= 0.0 {
+                    return Color::black();
+                }
+                bsdf.eval(wo, wi, *normal) * cos
+            }
+            Scatterer::Medium(phase) => Color::white() * phase.eval(wo, wi),
+        }
+    }
+
+    fn pdf(&self, wo: Vector, wi: Vector) -> f64 {
+        match self {
+            Scatterer::Surface { bsdf, normal } => bsdf.pdf(wo, wi, *normal),
+            Scatterer::Medium(phase) => phase.eval(wo, wi),
+        }
+    }
+
+    fn sample(&self, wo: Vector, u: f64, v: f64) -> Option<BsdfSample> {
+        match self {
+            Scatterer::Surface { bsdf, normal } => bsdf.sample(wo, *normal, u, v),
+            Scatterer::Medium(phase) => {
+                let direction = phase.sample(wo, u, v);
+                Some(BsdfSample {
+                    direction,
+                    weight: Color::white(),
+                    pdf: phase.eval(wo, direction),
+                })
+            }
+        }
+    }
+}
+
+/// Where light entering `material` at `point` leaves it again, found by
+/// casting a probe ray along `normal` through a point sampled around `point`
+/// with the diffusion profile. Returns the exit point, its normal on the
+/// side of `normal` and the factor the throughput is multiplied by, or
+/// `None` when the probe misses the surface of `bsdf`.
+fn probe(
+    scene: &impl Scene,
+    material: &Subsurface,
+    bsdf: &dyn Bsdf,
+    point: Point,
+    normal: Vector,
+    rng: &mut Rng,
+) -> Option<(Point, Vector, Color)> {
+    let (r, pdf) = material.sample_radius(rng.next_f64(), rng.next_f64());
+    let r_max = material.max_radius();
+    if r >= r_max || pdf <= 0.0 {
+        return None;
+    }
+
+    // The probe spans the sphere of radius `r_max` around `point`.
+    let h = (r_max * r_max - r * r).sqrt();
+    let phi = 2.0 * PI * rng.next_f64();
+    let (tangent, bitangent) = orthonormal_basis(normal);
+    let start = point + (tangent * phi.cos() + bitangent * phi.sin()) * r + normal * h;
+    let hit = scene.intersect(&Ray::new(start, -normal))?;
+    if hit.t > 2.0 * h || !std::ptr::addr_eq(hit.bsdf, bsdf) {
+        return None;
+    }
+
+    let exit_normal = if hit.normal.dot(normal) < 0.0 {
+        -hit.normal
+    } else {
+        hit.normal
+    };
+    // The radius was sampled in the plane; the exit point's density on the
+    // surface is smaller by the cosine between the normals.
+    let cos = exit_normal.dot(normal);
+    if cos <= 0.0 {
+        return None;
+    }
+    let profile = material.profile((hit.point - point).magnitude());
+    Some((hit.point, exit_normal, profile * (1.0 / (pdf * cos))))
+}
+
+/// Nearest collision with any of the scene's media before `t_max`.
+fn scatter<'a>(
+    scene: &'a impl Scene,
+    ray: &Ray,
+    t_max: f64,
+    rng: &mut Rng,
+) -> Option<(&'a dyn Medium, MediumSample)> {
+    let mut res: Option<(&dyn Medium, MediumSample)> = None;
+    for medium in scene.media() {
+        let t_max = res.map_or(t_max, |(_, sample)| sample.t);
+        if let Some(sample) = medium.sample(ray, 0.0, t_max, rng) {
+            res = Some((medium.as_ref(), sample));
+        }
+    }
+    res
+}
+
+/// Fraction of the light travelling `distance` from `origin` along the unit
+/// `direction` that arrives: zero when blocked by a surface, otherwise
+/// attenuated by the media on the way.
+fn transmittance(
+    scene: &impl Scene,
+    origin: Point,
+    direction: Vector,
+    distance: f64,
+    rng: &mut Rng,
+) -> f64 {
+    let ray = Ray::new(origin, direction);
+    if scene.intersect(&ray).is_some_and(|hit| hit.t < distance) {
+        return 0.0;
+    }
+    scene
+        .media()
+        .iter()
+        .map(|medium| medium.transmittance(&ray, 0.0, distance, rng))
+        .product()
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::{Flat, VerticalGradient};
+    use crate::integrators::Accumulator;
+    use crate::lights::{AreaLight, Emitter, MeshLight, PointLight, SpotLight};
+    use crate::materials::{Distribution, Emission, Fresnel, Lambertian, MicrofacetReflection};
+    use crate::media::{Bounded, DensityGrid, Heterogeneous, Homogeneous};
+    use crate::primitives::Tuple;
+    #[cfg(feature = "spectral")]
+    use crate::{materials::Dielectric, spectral::Ior};
+    use std::f64::consts::PI;
+
+    /// Inside of a unit sphere; an optional floor at `y = -0.5`.
+    struct Room {
+        walls: Lambertian,
+        emission: Color,
+        floor: Option<Lambertian>,
+        lights: Vec<Box<dyn Light>>,
+    }
+
+    impl Scene for Room {
+        fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction<'_>> {
+            let o = ray.origin.to_vector();
+            let d = ray.direction;
+            let b = o.dot(d);
+            let c = o.dot(o) - 1.0;
+            let t = -b + (b * b - c).sqrt();
+            let mut hit = SurfaceInteraction {
+                t,
+                point: ray.position(t),
+                normal: -ray.position(t).to_vector(),
+                bsdf: &self.walls,
+                emission: self.emission.into(),
+            };
+
+            if let Some(floor) = &self.floor {
+                let t = (-0.5 - ray.origin.y()) / d.y();
+                if t > 0.0 && t < hit.t {
+                    hit = SurfaceInteraction {
+                        t,
+                        point: ray.position(t),
+                        normal: Vector::new(0.0, 1.0, 0.0),
+                        bsdf: floor,
+                        emission: Emission::default(),
+                    };
+                }
+            }
+            Some(hit)
+        }
+
+        fn lights(&self) -> &[Box<dyn Light>] {
+            &self.lights
+        }
+    }
+
+    /// Open sky above a floor at `y = 0`.
+    struct Sky {
+        sky: Box<dyn Background>,
+        floor: Box<dyn Bsdf>,
+        lights: Vec<Box<dyn Light>>,
+    }
+
+    impl Scene for Sky {
+        fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction<'_>> {
+            let t = -ray.origin.y() / ray.direction.y();
+            (t > 0.0).then(|| SurfaceInteraction {
+                t,
+                point: ray.position(t),
+                normal: Vector::new(0.0, 1.0, 0.0),
+                bsdf: self.floor.as_ref(),
+                emission: Emission::default(),
+            })
+        }
+
+        fn lights(&self) -> &[Box<dyn Light>] {
+            &self.lights
+        }
+
+        fn background(&self) -> Option<&dyn Background> {
+            Some(self.sky.as_ref())
+        }
+    }
+
+    fn mean_radiance(tracer: &PathTracer, scene: &impl Scene, ray: &Ray, n: usize) -> Color {
+        let mut rng = Rng::new(9);
+        let sum = (0..n).fold(Color::black(), |acc, _| {
+            acc + tracer.trace(scene, ray, &mut rng)
+        });
+        sum * (1.0 / n as f64)
+    }
+
+    #[test]
+    fn russian_roulette_is_unbiased() {
+        let mut rng = Rng::new(10);
+        let throughput = Color::new(0.3, 0.2, 0.1);
+        let n = 20_000;
+        let sum: f64 = (0..n)
+            .filter_map(|_| russian_roulette(throughput, &mut rng))
+            .map(|p| 1.0 / p)
+            .sum();
+        assert!((sum / n as f64 - 1.0).abs() < 0.05);
+
+        assert_eq!(russian_roulette(Color::white(), &mut rng), Some(1.0));
+    }
+
+    #[test]
+    fn path_tracer_escaping_ray_sees_background() {
+        let scene = Sky {
+            sky: Box::new(Flat::new(Color::new(0.2, 0.4, 0.8))),
+            floor: Box::new(Lambertian::new(Color::white())),
+            lights: vec![],
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+        let res = PathTracer::new(5).trace(&scene, &ray, &mut Rng::new(0));
+        assert_eq!(res, Color::new(0.2, 0.4, 0.8));
+    }
+
+    #[test]
+    fn path_tracer_bounced_rays_see_environment() {
+        // A half-white floor lit only by a sky going from black below to
+        // white above: the cosine-weighted mean of the gradient over the
+        // upper hemisphere is 5/6.
+        let scene = Sky {
+            sky: Box::new(VerticalGradient::new(Color::black(), Color::white())),
+            floor: Box::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+            lights: vec![],
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let res = mean_radiance(&PathTracer::new(2), &scene, &ray, 4000);
+        assert!((res.g() - 0.5 * 5.0 / 6.0).abs() < 0.02, "{:?}", res);
+    }
+
+    #[test]
+    fn path_tracer_furnace() {
+        // Every bounce adds the emission and keeps `albedo` of the throughput,
+        // so the radiance converges to emission / (1 - albedo).
+        let scene = Room {
+            walls: Lambertian::new(Color::new(0.5, 0.5, 0.5)),
+            emission: Color::new(0.5, 0.25, 0.1),
+            floor: None,
+            lights: vec![],
+        };
+        let ray = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+        let res = mean_radiance(&PathTracer::new(64), &scene, &ray, 5_000);
+        approx::assert_abs_diff_eq!(res, Color::new(1.0, 0.5, 0.2), epsilon = 0.05);
+    }
+
+    #[test]
+    fn path_tracer_next_event_estimation() {
+        let scene = Sky {
+            sky: Box::new(Flat::default()),
+            floor: Box::new(Lambertian::new(Color::white())),
+            lights: vec![Box::new(PointLight::new(
+                Point::new(0.0, 10.0, 10.0),
+                Color::white(),
+            ))],
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let res = PathTracer::new(5).trace(&scene, &ray, &mut Rng::new(0));
+        // White Lambertian: cos / PI times the intensity over distance².
+        let expected = (PI / 4.0).cos() / (PI * 200.0);
+        assert_eq!(res, Color::new(expected, expected, expected));
+    }
+
+    #[test]
+    fn path_tracer_dark_surfaces_receive_light() {
+        // Light reflected off a nearly black floor is dim but not zero.
+        let scene = Sky {
+            sky: Box::new(Flat::default()),
+            floor: Box::new(Lambertian::new(Color::new(1e-5, 1e-5, 1e-5))),
+            lights: vec![Box::new(PointLight::new(
+                Point::new(0.0, 10.0, 10.0),
+                Color::white(),
+            ))],
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let res = PathTracer::new(1).trace(&scene, &ray, &mut Rng::new(0));
+        let expected = 1e-5 * (PI / 4.0).cos() / (PI * 200.0);
+        approx::assert_relative_eq!(res.r(), expected, max_relative = 1e-3);
+    }
+
+    #[test]
+    fn path_tracer_light_is_shadowed() {
+        // A light outside the room never reaches its interior.
+        let scene = Room {
+            walls: Lambertian::new(Color::new(0.5, 0.5, 0.5)),
+            emission: Color::black(),
+            floor: None,
+            lights: vec![Box::new(PointLight::new(
+                Point::new(0.0, 5.0, 0.0),
+                Color::white(),
+            ))],
+        };
+        let ray = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let res = mean_radiance(&PathTracer::new(8), &scene, &ray, 100);
+        assert_eq!(res, Color::black());
+    }
+
+    #[test]
+    fn path_tracer_color_bleeding() {
+        // A white floor in a red room picks up red indirect light.
+        let scene = Room {
+            walls: Lambertian::new(Color::new(0.8, 0.1, 0.1)),
+            emission: Color::black(),
+            floor: Some(Lambertian::new(Color::white())),
+            lights: vec![Box::new(PointLight::new(
+                Point::new(0.0, 0.5, 0.0),
+                Color::white(),
+            ))],
+        };
+        let ray = Ray::new(Point::new(0.3, 0.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let direct = mean_radiance(&PathTracer::new(1), &scene, &ray, 1);
+        let global = mean_radiance(&PathTracer::new(8), &scene, &ray, 2_000);
+        assert_eq!(direct.r(), direct.g());
+        assert!(global.r() > direct.r() + 0.05);
+        assert!(global.r() > global.g() + 0.05);
+    }
+
+    #[test]
+    fn path_tracer_progressive_passes() {
+        let scene = Room {
+            walls: Lambertian::new(Color::new(0.5, 0.5, 0.5)),
+            emission: Color::new(0.5, 0.5, 0.5),
+            floor: None,
+            lights: vec![],
+        };
+        let camera = Camera::new(4, 4, PI / 2.0);
+        let tracer = PathTracer::new(32);
+        let mut acc = Accumulator::new(4, 4);
+        for pass in 0..64 {
+            acc.add(&tracer.render_pass(&camera, &scene, pass));
+        }
+        assert_eq!(acc.passes(), 64);
+
+        let res = acc.canvas();
+        let mean = (0..4)
+            .flat_map(|x| (0..4).map(move |y| (x, y)))
+            .fold(Color::black(), |a, (x, y)| a + res[x][y])
+            * (1.0 / 16.0);
+        approx::assert_abs_diff_eq!(mean, Color::white(), epsilon = 0.05);
+    }
+
+    fn small_light(size: f64, radiance: f64) -> Vec<Box<dyn Light>> {
+        vec![Box::new(AreaLight::new(
+            Point::new(-size / 2.0, 2.0, -size / 2.0),
+            Vector::new(size, 0.0, 0.0),
+            1,
+            Vector::new(0.0, 0.0, size),
+            1,
+            Color::new(radiance, radiance, radiance),
+        ))]
+    }
+
+    /// Mean and variance of the red channel over `n` single-path estimates.
+    fn statistics(tracer: &PathTracer, scene: &impl Scene, ray: &Ray, n: usize) -> (f64, f64) {
+        let mut rng = Rng::new(14);
+        let values: Vec<f64> = (0..n)
+            .map(|_| tracer.trace(scene, ray, &mut rng).r())
+            .collect();
+        let mean = values.iter().sum::<f64>() / n as f64;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64;
+        (mean, variance)
+    }
+
+    #[test]
+    fn path_tracer_strategy_counts() {
+        let tracer = PathTracer::new(4)
+            .with_light_samples(4)
+            .with_bsdf_samples(2)
+            .with_heuristic(Heuristic::Balance);
+        assert_eq!(tracer.light_samples(), 4);
+        assert_eq!(tracer.bsdf_samples(), 2);
+        assert_eq!(tracer.heuristic(), Heuristic::Balance);
+        assert_eq!(PathTracer::new(4).heuristic(), Heuristic::Power);
+    }
+
+    #[test]
+    fn path_tracer_area_light_strategies_agree() {
+        // Every combination of strategies estimates the same direct light.
+        let scene = Sky {
+            sky: Box::new(Flat::default()),
+            floor: Box::new(Lambertian::new(Color::white())),
+            lights: small_light(1.0, 10.0),
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+
+        let light_only = PathTracer::new(1).with_bsdf_samples(0);
+        let (expected, _) = statistics(&light_only, &scene, &ray, 20_000);
+        // Roughly radiance * solid angle * cos / PI for a light this size.
+        assert!((expected - 10.0 / (4.0 * PI)).abs() < 0.1);
+
+        let bsdf_only = PathTracer::new(1).with_light_samples(0);
+        let balance = PathTracer::new(1).with_heuristic(Heuristic::Balance);
+        let power = PathTracer::new(1)
+            .with_light_samples(2)
+            .with_bsdf_samples(3);
+        for tracer in [bsdf_only, balance, power] {
+            let (mean, _) = statistics(&tracer, &scene, &ray, 20_000);
+            assert!(
+                (mean - expected).abs() < 0.05 * expected,
+                "{:?}: {} vs {}",
+                tracer,
+                mean,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn path_tracer_mis_avoids_fireflies() {
+        let ray = Ray::new(
+            Point::new(-1.0, 1.0, 0.0),
+            Vector::new(1.0, -1.0, 0.0).normalize(),
+        );
+
+        // Small bright light over a diffuse floor: BSDF sampling rarely hits
+        // it, light sampling does well, MIS keeps most of that.
+        let diffuse = Sky {
+            sky: Box::new(Flat::default()),
+            floor: Box::new(Lambertian::new(Color::white())),
+            lights: small_light(0.1, 1000.0),
+        };
+        let (_, light_var) = statistics(
+            &PathTracer::new(1).with_bsdf_samples(0),
+            &diffuse,
+            &ray,
+            5_000,
+        );
+        let (_, bsdf_var) = statistics(
+            &PathTracer::new(1).with_light_samples(0),
+            &diffuse,
+            &ray,
+            5_000,
+        );
+        let (_, mis_var) = statistics(&PathTracer::new(1), &diffuse, &ray, 5_000);
+        assert!(mis_var < bsdf_var / 10.0);
+        assert!(mis_var < light_var * 2.0);
+
+        // Large light over a mirror-like floor: light samples rarely land in
+        // the narrow lobe, BSDF sampling does well, MIS keeps most of that.
+        let glossy = Sky {
+            sky: Box::new(Flat::default()),
+            floor: Box::new(MicrofacetReflection::new(
+                Distribution::Ggx { alpha: 0.02 },
+                Fresnel::NoOp,
+            )),
+            lights: vec![Box::new(AreaLight::new(
+                Point::new(-1.0, 2.0, -3.0),
+                Vector::new(6.0, 0.0, 0.0),
+                1,
+                Vector::new(0.0, 0.0, 6.0),
+                1,
+                Color::white(),
+            ))],
+        };
+        let (_, light_var) = statistics(
+            &PathTracer::new(1).with_bsdf_samples(0),
+            &glossy,
+            &ray,
+            5_000,
+        );
+        let (_, bsdf_var) = statistics(
+            &PathTracer::new(1).with_light_samples(0),
+            &glossy,
+            &ray,
+            5_000,
+        );
+        let (_, mis_var) = statistics(&PathTracer::new(1), &glossy, &ray, 5_000);
+        assert!(mis_var < light_var / 1000.0);
+        assert!(bsdf_var < light_var / 1000.0);
+    }
+
+    /// A white floor at `y = 0` under emissive `lamps`, which the scene
+    /// intersects and reports the first `listed` of as emitters.
+    struct Lamp {
+        floor: Lambertian,
+        dark: Lambertian,
+        lamps: Vec<MeshLight>,
+        listed: usize,
+    }
+
+    impl Lamp {
+        fn new(lamps: Vec<MeshLight>) -> Self {
+            Lamp {
+                floor: Lambertian::new(Color::white()),
+                dark: Lambertian::new(Color::black()),
+                listed: lamps.len(),
+                lamps,
+            }
+        }
+    }
+
+    impl Scene for Lamp {
+        fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction<'_>> {
+            let t = -ray.origin.y() / ray.direction.y();
+            let mut hit = (t > 0.0).then(|| SurfaceInteraction {
+                t,
+                point: ray.position(t),
+                normal: Vector::new(0.0, 1.0, 0.0),
+                bsdf: &self.floor,
+                emission: Emission::default(),
+            });
+
+            for lamp in &self.lamps {
+                for emitter in lamp.emitters() {
+                    let Some((t, normal)) = emitter.intersect(ray) else {
+                        continue;
+                    };
+                    if hit.is_none_or(|hit| t < hit.t) {
+                        hit = Some(SurfaceInteraction {
+                            t,
+                            point: ray.position(t),
+                            normal,
+                            bsdf: &self.dark,
+                            emission: lamp.emission(),
+                        });
+                    }
+                }
+            }
+            hit
+        }
+
+        fn lights(&self) -> &[Box<dyn Light>] {
+            &[]
+        }
+
+        fn emitters(&self) -> &[MeshLight] {
+            &self.lamps[..self.listed]
+        }
+    }
+
+    fn sphere_lamp(y: f64, radius: f64, emission: Emission) -> MeshLight {
+        MeshLight::new(
+            vec![Emitter::Sphere {
+                center: Point::new(0.0, y, 0.0),
+                radius,
+            }],
+            emission,
+        )
+    }
+
+    #[test]
+    fn path_tracer_camera_sees_emitters() {
+        let scene = Lamp::new(vec![sphere_lamp(
+            3.0,
+            1.0,
+            Emission::new(Color::new(1.0, 0.5, 0.25), 2.0),
+        )]);
+        let up = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+        let res = PathTracer::new(4).trace(&scene, &up, &mut Rng::new(0));
+        assert_eq!(res, Color::new(2.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn path_tracer_sphere_light() {
+        // A uniformly emitting sphere of radius R whose center is d above a
+        // white diffuse floor reflects L * (R / d)^2 straight up. A single
+        // bounce sees it only because the floor samples it as a light.
+        let scene = Lamp::new(vec![sphere_lamp(
+            2.0,
+            0.5,
+            Emission::new(Color::white(), 4.0),
+        )]);
+        let ray = Ray::new(Point::new(0.3, 1.0, 0.0), Vector::new(-0.3, -1.0, 0.0));
+        let (mean, _) = statistics(&PathTracer::new(1), &scene, &ray, 20_000);
+        assert!((mean - 0.25).abs() < 0.01, "{}", mean);
+    }
+
+    #[test]
+    fn path_tracer_emitters_match_unsampled_emission() {
+        // Listing the lamp as an emitter leaves the estimate unchanged but
+        // makes it far less noisy than waiting for bounces to hit it.
+        let listed = Lamp::new(vec![sphere_lamp(
+            2.0,
+            0.25,
+            Emission::new(Color::white(), 16.0),
+        )]);
+        let unlisted = Lamp {
+            listed: 0,
+            ..Lamp::new(listed.lamps.clone())
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let tracer = PathTracer::new(2);
+        let (mean, var) = statistics(&tracer, &listed, &ray, 20_000);
+        let (expected, unlisted_var) = statistics(&tracer, &unlisted, &ray, 200_000);
+        assert!((mean - 0.25).abs() < 0.01, "{}", mean);
+        assert!((expected - 0.25).abs() < 0.02, "{}", expected);
+        assert!(var < unlisted_var / 10.0, "{} {}", var, unlisted_var);
+    }
+
+    #[test]
+    fn path_tracer_partly_listed_emitters_count_every_lamp() {
+        // The second lamp is left out of the emitters, so its light comes
+        // from bounces that hit it, with nothing lost to the first lamp's
+        // sampling.
+        let lamps = vec![
+            sphere_lamp(2.0, 0.25, Emission::new(Color::white(), 16.0)),
+            MeshLight::new(
+                vec![Emitter::Sphere {
+                    center: Point::new(1.0, 2.0, 0.0),
+                    radius: 0.25,
+                }],
+                Emission::new(Color::white(), 16.0),
+            ),
+        ];
+        let partly = Lamp {
+            listed: 1,
+            ..Lamp::new(lamps.clone())
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let tracer = PathTracer::new(2);
+        let (mean, _) = statistics(&tracer, &partly, &ray, 200_000);
+        let (all, _) = statistics(&tracer, &Lamp::new(lamps), &ray, 20_000);
+        assert!(all > 0.3, "{}", all);
+        assert!((mean - all).abs() < 0.02, "{} {}", mean, all);
+    }
+
+    #[test]
+    fn path_tracer_emitter_strategies_agree() {
+        let scene = Lamp::new(vec![sphere_lamp(
+            2.0,
+            0.5,
+            Emission::new(Color::white(), 4.0),
+        )]);
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let light_only = PathTracer::new(1).with_bsdf_samples(0);
+        let bsdf_only = PathTracer::new(1).with_light_samples(0);
+        let (light_mean, _) = statistics(&light_only, &scene, &ray, 20_000);
+        let (bsdf_mean, _) = statistics(&bsdf_only, &scene, &ray, 100_000);
+        assert!((light_mean - 0.25).abs() < 0.01, "{}", light_mean);
+        assert!((bsdf_mean - 0.25).abs() < 0.02, "{}", bsdf_mean);
+    }
+
+    #[test]
+    fn path_tracer_triangle_mesh_light() {
+        // Two triangles forming the same 1x1 panel as an area light give the
+        // same lighting.
+        let panel = |y: f64| {
+            vec![
+                Emitter::Triangle {
+                    p0: Point::new(-0.5, y, -0.5),
+                    p1: Point::new(0.5, y, -0.5),
+                    p2: Point::new(0.5, y, 0.5),
+                },
+                Emitter::Triangle {
+                    p0: Point::new(-0.5, y, -0.5),
+                    p1: Point::new(0.5, y, 0.5),
+                    p2: Point::new(-0.5, y, 0.5),
+                },
+            ]
+        };
+        let mesh = Lamp::new(vec![MeshLight::new(
+            panel(2.0),
+            Emission::new(Color::white(), 10.0),
+        )]);
+        let area = Sky {
+            sky: Box::new(Flat::default()),
+            floor: Box::new(Lambertian::new(Color::white())),
+            lights: small_light(1.0, 10.0),
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let (expected, _) = statistics(&PathTracer::new(1), &area, &ray, 20_000);
+        let (mean, _) = statistics(&PathTracer::new(1), &mesh, &ray, 20_000);
+        assert!((mean - expected).abs() < 0.03 * expected);
+    }
+
+    #[test]
+    fn path_tracer_subsurface_profile_spreads_light() {
+        // A narrow spot lights the middle of a translucent floor. The longer
+        // the mean free path, the more of its light leaves the floor outside
+        // the spot; without any, the floor renders as a diffuse one.
+        let floor = |mean_free_path: f64| Sky {
+            sky: Box::new(Flat::default()),
+            floor: Box::new(Subsurface::new(
+                Color::new(0.8, 0.8, 0.8),
+                Color::white() * mean_free_path,
+            )),
+            lights: vec![Box::new(SpotLight::new(
+                Point::new(0.0, 1.0, 0.0),
+                Vector::new(0.0, -1.0, 0.0),
+                Color::white() * PI,
+                0.1,
+                0.15,
+            ))],
+        };
+        let down = Vector::new(0.0, -1.0, 0.0);
+        let inside = Ray::new(Point::new(0.0, 0.5, 0.0), down);
+        let outside = Ray::new(Point::new(0.6, 0.5, 0.0), down);
+        let tracer = PathTracer::new(1);
+
+        let (short_in, _) = statistics(&tracer, &floor(0.01), &inside, 5_000);
+        let (short_out, _) = statistics(&tracer, &floor(0.01), &outside, 5_000);
+        let (long_in, _) = statistics(&tracer, &floor(0.5), &inside, 5_000);
+        let (long_out, _) = statistics(&tracer, &floor(0.5), &outside, 20_000);
+        assert!(short_out < 5.0e-4, "{}", short_out);
+        assert!(long_out > 2.0e-3, "{}", long_out);
+        assert!(long_out > 10.0 * short_out);
+        assert!(short_in > 2.0 * long_in, "{} {}", short_in, long_in);
+
+        let diffuse = Sky {
+            floor: Box::new(Lambertian::new(Color::new(0.8, 0.8, 0.8))),
+            ..floor(0.0)
+        };
+        let (zero_in, _) = statistics(&tracer, &floor(0.0), &inside, 1_000);
+        let (expected, _) = statistics(&tracer, &diffuse, &inside, 1_000);
+        assert!((zero_in - expected).abs() < 1.0e-6);
+        assert!((expected - 0.8).abs() < 1.0e-4);
+    }
+
+    /// `scene` filled with `media`.
+    struct Foggy<S> {
+        scene: S,
+        media: Vec<Box<dyn Medium>>,
+    }
+
+    impl<S: Scene> Scene for Foggy<S> {
+        fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction<'_>> {
+            self.scene.intersect(ray)
+        }
+
+        fn lights(&self) -> &[Box<dyn Light>] {
+            self.scene.lights()
+        }
+
+        fn media(&self) -> &[Box<dyn Medium>] {
+            &self.media
+        }
+    }
+
+    /// A white floor under a light bright enough to give it unit radiance.
+    fn lit_floor() -> Sky {
+        Sky {
+            sky: Box::new(Flat::default()),
+            floor: Box::new(Lambertian::new(Color::white())),
+            lights: vec![Box::new(PointLight::new(
+                Point::new(0.0, 2.0, 0.0),
+                Color::white() * (4.0 * PI),
+            ))],
+        }
+    }
+
+    #[test]
+    fn path_tracer_fog_attenuates() {
+        // Absorbing fog dims the floor by the transmittance along the camera
+        // ray and the shadow ray, one and two units long.
+        let scene = Foggy {
+            scene: lit_floor(),
+            media: vec![Box::new(Homogeneous::new(0.5, Color::black(), 0.0))],
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let clear = PathTracer::new(1).trace(&lit_floor(), &ray, &mut Rng::new(0));
+        approx::assert_abs_diff_eq!(clear, Color::white(), epsilon = 1.0e-4);
+
+        let (mean, _) = statistics(&PathTracer::new(1), &scene, &ray, 20_000);
+        assert!((mean - (-1.5f64).exp()).abs() < 0.01, "{}", mean);
+    }
+
+    #[test]
+    fn path_tracer_fog_scatters_light() {
+        // A ray crossing two units of thin, isotropic fog half a unit below
+        // the light gathers sigma_s * I / (4 PI) times the integral of 1 / d²
+        // along it, less under 2% of attenuation.
+        let slab = Bounded::new(
+            Homogeneous::new(0.01, Color::white(), 0.0),
+            Point::new(-1.0, 1.0, -1.0),
+            Point::new(1.0, 2.0, 1.0),
+        );
+        let scene = Foggy {
+            scene: lit_floor(),
+            media: vec![Box::new(slab)],
+        };
+        let tracer = PathTracer::new(1);
+        let through = Ray::new(Point::new(-3.0, 1.5, 0.0), Vector::new(1.0, 0.0, 0.0));
+        let (mean, _) = statistics(&tracer, &scene, &through, 100_000);
+        let expected = 0.01 * 4.0 * 2.0f64.atan();
+        assert!((mean - expected).abs() < 0.08 * expected, "{}", mean);
+
+        // Rays passing beside the fog stay dark.
+        let beside = Ray::new(Point::new(-3.0, 0.5, 0.0), Vector::new(1.0, 0.0, 0.0));
+        assert_eq!(
+            mean_radiance(&tracer, &scene, &beside, 1000),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn path_tracer_shadows_through_smoke() {
+        // Smoke between the light and the floor darkens the floor more than
+        // the same amount of smoke off to the side.
+        let smoke = |x: f64| {
+            let grid = DensityGrid::from_fn(4, 4, 4, |p| p.y());
+            Box::new(Heterogeneous::new(
+                grid,
+                Point::new(x - 0.5, 1.0, -0.5),
+                Point::new(x + 0.5, 1.5, 0.5),
+                4.0,
+                Color::black(),
+                0.0,
+            )) as Box<dyn Medium>
+        };
+        let ray = Ray::new(Point::new(0.0, 0.5, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let tracer = PathTracer::new(1);
+        let above = Foggy {
+            scene: lit_floor(),
+            media: vec![smoke(0.0)],
+        };
+        let aside = Foggy {
+            scene: lit_floor(),
+            media: vec![smoke(3.0)],
+        };
+        let (shadowed, _) = statistics(&tracer, &above, &ray, 20_000);
+        let (lit, _) = statistics(&tracer, &aside, &ray, 1000);
+        assert!((lit - 1.0).abs() < 1.0e-4, "{}", lit);
+        // The mean density is a half, so the smoke is one optical depth thick.
+        assert!((shadowed - (-1.0f64).exp()).abs() < 0.02, "{}", shadowed);
+    }
+
+    /// Glass behind a face through `z = -1` tilted 45 degrees about the y
+    /// axis, over a floor at `z = -3` that glows between `x = strip.0` and
+    /// `x = strip.1`.
+    #[cfg(feature = "spectral")]
+    struct Prism {
+        glass: Dielectric,
+        floor: Lambertian,
+        strip: (f64, f64),
+    }
+
+    #[cfg(feature = "spectral")]
+    impl Scene for Prism {
+        fn intersect(&self, ray: &Ray) -> Option<SurfaceInteraction<'_>> {
+            let normal = Vector::new(1.0, 0.0, 1.0).normalize();
+            let behind = |p: Point| (p - Point::new(0.0, 0.0, -1.0)).dot(normal);
+
+            let t = -behind(ray.origin) / ray.direction.dot(normal);
+            let face = (t > 0.0).then(|| SurfaceInteraction {
+                t,
+                point: ray.position(t),
+                normal,
+                bsdf: &self.glass,
+                emission: Emission::default(),
+            });
+
+            let t = (-3.0 - ray.origin.z()) / ray.direction.z();
+            let point = ray.position(t);
+            let floor = (t > 0.0 && behind(point) < 0.0).then(|| {
+                let lit = point.x() > self.strip.0 && point.x() < self.strip.1;
+                SurfaceInteraction {
+                    t,
+                    point,
+                    normal: Vector::new(0.0, 0.0, 1.0),
+                    bsdf: &self.floor,
+                    emission: if lit {
+                        Emission::new(Color::white(), 1.0)
+                    } else {
+                        Emission::default()
+                    },
+                }
+            });
+
+            [face, floor]
+                .into_iter()
+                .flatten()
+                .min_by(|a, b| a.t.total_cmp(&b.t))
+        }
+
+        fn lights(&self) -> &[Box<dyn Light>] {
+            &[]
+        }
+    }
+
+    #[cfg(feature = "spectral")]
+    #[test]
+    fn path_tracer_glass_disperses_white_light() {
+        let render = |ior: Ior| {
+            let scene = Prism {
+                glass: Dielectric::new(ior),
+                floor: Lambertian::new(Color::black()),
+                strip: (-0.645, -0.595),
+            };
+            let camera = Camera::new(32, 1, 0.04);
+            let tracer = PathTracer::new(4);
+            let mut acc = Accumulator::new(32, 1);
+            for pass in 0..256 {
+                acc.add(&tracer.render_spectral_pass(&camera, &scene, pass));
+            }
+            acc.canvas()
+        };
+
+        // Blue light bends more, so the strip seen through BK7 glass is
+        // fringed blue on one side and red on the other.
+        let res = render(Ior::bk7());
+        let tint = |x: usize| res[x][0].b() - res[x][0].r();
+        let bluest = (0..32)
+            .max_by(|&a, &b| tint(a).total_cmp(&tint(b)))
+            .unwrap();
+        let reddest = (0..32)
+            .min_by(|&a, &b| tint(a).total_cmp(&tint(b)))
+            .unwrap();
+        assert!(tint(bluest) > 0.5 && tint(reddest) < -0.5);
+        // Both fringes lie outside the strip's white interior.
+        assert!(bluest < 8 && reddest > 21, "{} {}", bluest, reddest);
+
+        // Glass with a constant index leaves the strip white.
+        let res = render(Ior::Constant(1.5168));
+        for x in 0..32 {
+            let c = res[x][0];
+            assert!((c.b() - c.r()).abs() < 0.1, "{:?}", c);
+        }
+    }
+
+    #[cfg(feature = "spectral")]
+    #[test]
+    fn path_tracer_spectral_matches_rgb() {
+        // Under white light, an orange floor converted to a spectrum and
+        // back looks the same as traced in RGB.
+        let scene = Sky {
+            sky: Box::new(Flat::default()),
+            floor: Box::new(Lambertian::new(Color::new(0.8, 0.3, 0.1))),
+            lights: vec![Box::new(PointLight::new(
+                Point::new(0.0, 2.0, 0.0),
+                Color::new(4.0, 4.0, 4.0),
+            ))],
+        };
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        let tracer = PathTracer::new(2);
+        let mut rng = Rng::new(4);
+        let n = 20_000;
+        let sum = (0..n).fold(Color::black(), |acc, _| {
+            acc + tracer.trace_spectral(&scene, &ray, &mut rng)
+        });
+        let rgb = mean_radiance(&tracer, &scene, &ray, n);
+        approx::assert_abs_diff_eq!(sum * (1.0 / n as f64), rgb, epsilon = 0.01);
+    }
+}
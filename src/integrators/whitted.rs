@@ -0,0 +1,756 @@
+use crate::cameras::Camera;
+use crate::environment::Background;
+use crate::lights::{lighting, Light, Phong};
+use crate::optics::{fresnel_blend, refractive_indices, schlick};
+use crate::primitives::{Canvas, Color, Matrix, Point, Ray, Vector, EPSILON};
+use crate::sampling::Supersampler;
+
+// ------------------------------------------------------
+/// One crossing of a ray with the surface of an object.
+#[derive(Clone, Copy, Debug)]
+pub struct Intersection<'a> {
+    /// Distance along the ray; negative behind its origin.
+    pub t: f64,
+    /// Identifies the object crossed; every intersection with the same
+    /// object carries the same value.
+    pub object: usize,
+    /// Unit outward surface normal.
+    pub normal: Vector,
+    pub material: &'a Phong,
+    /// Inverse transform of the object, mapping world points into the
+    /// space its material's pattern is evaluated in.
+    pub world_to_object: &'a Matrix,
+}
+
+/// What the Whitted tracer needs to know about the scene.
+pub trait World {
+    /// Every intersection along the ray, sorted by `t`, including those
+    /// behind the origin.
+    fn intersect(&self, ray: &Ray) -> Vec<Intersection<'_>>;
+
+    /// Lights shading every hit.
+    fn lights(&self) -> &[Box<dyn Light>];
+
+    /// Environment seen by rays that miss every object, reflected and
+    /// refracted ones included; `None` is black.
+    fn background(&self) -> Option<&dyn Background> {
+        None
+    }
+}
+
+// ------------------------------------------------------
+/// Values shared by the shading of one intersection, precomputed once.
+#[derive(Clone, Copy, Debug)]
+pub struct Computations<'a> {
+    pub t: f64,
+    pub object: usize,
+    pub material: &'a Phong,
+    pub world_to_object: &'a Matrix,
+    pub point: Point,
+    /// `point` nudged along the normal so secondary rays leave the surface.
+    pub over_point: Point,
+    /// `point` nudged below the surface, where refracted rays start.
+    pub under_point: Point,
+    pub eyev: Vector,
+    /// Unit normal, flipped to face the eye.
+    pub normalv: Vector,
+    /// Whether the ray hit the surface from inside the object.
+    pub inside: bool,
+    /// Direction of the mirror reflection of the ray.
+    pub reflectv: Vector,
+    /// Refractive index on the side the ray comes from.
+    pub n1: f64,
+    /// Refractive index on the side the ray enters.
+    pub n2: f64,
+}
+
+impl<'a> Computations<'a> {
+    /// Prepares the shading of `intersections[hit]`, where `intersections`
+    /// are those of `ray` as returned by `World::intersect`.
+    pub fn prepare(intersections: &[Intersection<'a>], hit: usize, ray: &Ray) -> Self {
+        let i = &intersections[hit];
+        let point = ray.position(i.t);
+        let eyev = -ray.direction.normalize();
+        let inside = i.normal.dot(eyev) < 0.0;
+        let normalv = if inside { -i.normal } else { i.normal };
+
+        let objects: Vec<(usize, f64)> = intersections
+            .iter()
+            .map(|i| (i.object, i.material.refractive_index))
+            .collect();
+        let (n1, n2) = refractive_indices(&objects, hit, |&(_, ior)| ior);
+
+        Computations {
+            t: i.t,
+            object: i.object,
+            material: i.material,
+            world_to_object: i.world_to_object,
+            point,
+            over_point: point + normalv * EPSILON,
+            under_point: point - normalv * EPSILON,
+            eyev,
+            normalv,
+            inside,
+            reflectv: ray.direction.normalize().reflect(normalv),
+            n1,
+            n2,
+        }
+    }
+
+    /// Schlick reflectance at the hit.
+    pub fn schlick(&self) -> f64 {
+        schlick(self.eyev.dot(self.normalv), self.n1, self.n2)
+    }
+}
+
+// ------------------------------------------------------
+/// Whitted-style ray tracer: Phong shading with hard shadows, plus mirror
+/// reflection and refraction traced recursively until `max_depth` bounces.
+/// Surfaces both reflective and transparent blend the two with Schlick's
+/// approximation of the Fresnel reflectance.
+#[derive(Clone, Copy, Debug)]
+pub struct Whitted {
+    max_depth: usize,
+}
+
+impl Whitted {
+    pub fn new(max_depth: usize) -> Self {
+        Whitted { max_depth }
+    }
+
+    /// Secondary bounces traced before reflections and refractions turn
+    /// black.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Color seen along `ray`.
+    pub fn trace(&self, world: &impl World, ray: &Ray) -> Color {
+        self.color_at(world, ray, self.max_depth)
+    }
+
+    fn color_at(&self, world: &impl World, ray: &Ray, remaining: usize) -> Color {
+        let intersections = world.intersect(ray);
+        match intersections.iter().position(|i| i.t >= 0.0) {
+            Some(hit) => {
+                let comps = Computations::prepare(&intersections, hit, ray);
+                self.shade_hit(world, &comps, remaining)
+            }
+            None => world.background().map_or(Color::black(), |background| {
+                background.color_at(ray.direction)
+            }),
+        }
+    }
+
+    /// Surface color at a prepared hit, with `remaining` bounces left for
+    /// secondary rays.
+    pub fn shade_hit(&self, world: &impl World, comps: &Computations, remaining: usize) -> Color {
+        let surface = world.lights().iter().fold(Color::black(), |acc, light| {
+            acc + lighting(
+                comps.material,
+                comps.world_to_object,
+                light.as_ref(),
+                comps.over_point,
+                comps.eyev,
+                comps.normalv,
+                |sample| is_shadowed(world, comps.over_point, sample.direction, sample.distance),
+            )
+        });
+
+        let reflected = self.reflected_color(world, comps, remaining);
+        let refracted = self.refracted_color(world, comps, remaining);
+
+        let material = comps.material;
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            fresnel_blend(surface, reflected, refracted, comps.schlick())
+        } else {
+            surface + reflected + refracted
+        }
+    }
+
+    /// Light arriving from the mirror direction, scaled by the material's
+    /// reflectivity. Black once `remaining` reaches zero, so mirrors facing
+    /// each other terminate.
+    pub fn reflected_color(
+        &self,
+        world: &impl World,
+        comps: &Computations,
+        remaining: usize,
+    ) -> Color {
+        if remaining == 0 || comps.material.reflective == 0.0 {
+            return Color::black();
+        }
+
+        let ray = Ray::new(comps.over_point, comps.reflectv);
+        self.color_at(world, &ray, remaining - 1) * comps.material.reflective
+    }
+
+    /// Light arriving through the surface, scaled by the material's
+    /// transparency. Black under total internal reflection and once
+    /// `remaining` reaches zero.
+    pub fn refracted_color(
+        &self,
+        world: &impl World,
+        comps: &Computations,
+        remaining: usize,
+    ) -> Color {
+        if remaining == 0 || comps.material.transparency == 0.0 {
+            return Color::black();
+        }
+
+        let Some(direction) = (-comps.eyev).refract(comps.normalv, comps.n1 / comps.n2) else {
+            return Color::black();
+        };
+        let ray = Ray::new(comps.under_point, direction);
+        self.color_at(world, &ray, remaining - 1) * comps.material.transparency
+    }
+
+    pub fn render(&self, camera: &Camera, sampler: &Supersampler, world: &impl World) -> Canvas {
+        camera.render(sampler, 1, |ray| self.trace(world, ray))
+    }
+}
+
+impl Default for Whitted {
+    fn default() -> Self {
+        Whitted::new(5)
+    }
+}
+
+/// Whether something lies between `origin` and a light `distance` away.
+fn is_shadowed(world: &impl World, origin: Point, direction: Vector, distance: f64) -> bool {
+    world
+        .intersect(&Ray::new(origin, direction))
+        .iter()
+        .any(|i| i.t >= 0.0 && i.t < distance)
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::{Flat, VerticalGradient};
+    use crate::lights::PointLight;
+    use crate::patterns::Stripe;
+    use crate::primitives::Tuple;
+    use approx::assert_abs_diff_eq;
+
+    enum Shape {
+        Sphere { center: Point, radius: f64 },
+        Plane { height: f64, normal: Vector },
+    }
+
+    struct Object {
+        shape: Shape,
+        material: Phong,
+        world_to_object: Matrix,
+    }
+
+    impl Object {
+        fn sphere(center: Point, radius: f64, material: Phong) -> Self {
+            Object {
+                shape: Shape::Sphere { center, radius },
+                material,
+                world_to_object: Matrix::identity(),
+            }
+        }
+
+        /// Horizontal plane at `y = height` whose normal points up, or down
+        /// when `facing_down`.
+        fn plane(height: f64, facing_down: bool, material: Phong) -> Self {
+            let normal = Vector::new(0.0, if facing_down { -1.0 } else { 1.0 }, 0.0);
+            Object {
+                shape: Shape::Plane { height, normal },
+                material,
+                world_to_object: Matrix::identity(),
+            }
+        }
+
+        fn crossings(&self, ray: &Ray) -> Vec<(f64, Vector)> {
+            match self.shape {
+                Shape::Sphere { center, radius } => {
+                    let oc = ray.origin - center;
+                    let a = ray.direction.dot(ray.direction);
+                    let b = 2.0 * ray.direction.dot(oc);
+                    let c = oc.dot(oc) - radius * radius;
+                    let disc = b * b - 4.0 * a * c;
+                    if disc < 0.0 {
+                        return Vec::new();
+                    }
+                    [-1.0, 1.0]
+                        .iter()
+                        .map(|sign| {
+                            let t = (-b + sign * disc.sqrt()) / (2.0 * a);
+                            (t, (ray.position(t) - center) * (1.0 / radius))
+                        })
+                        .collect()
+                }
+                Shape::Plane { height, normal } => {
+                    if ray.direction.y().abs() < EPSILON {
+                        return Vec::new();
+                    }
+                    vec![((height - ray.origin.y()) / ray.direction.y(), normal)]
+                }
+            }
+        }
+    }
+
+    struct TestWorld {
+        objects: Vec<Object>,
+        lights: Vec<Box<dyn Light>>,
+        background: Option<Box<dyn Background>>,
+    }
+
+    impl TestWorld {
+        /// Two concentric spheres lit from the upper left.
+        fn default_world() -> Self {
+            let outer = Phong {
+                color: Color::new(0.8, 1.0, 0.6),
+                diffuse: 0.7,
+                specular: 0.2,
+                ..Phong::default()
+            };
+            TestWorld {
+                objects: vec![
+                    Object::sphere(Point::default(), 1.0, outer),
+                    Object::sphere(Point::default(), 0.5, Phong::default()),
+                ],
+                lights: vec![Box::new(PointLight::new(
+                    Point::new(-10.0, 10.0, -10.0),
+                    Color::white(),
+                ))],
+                background: None,
+            }
+        }
+
+        fn with(mut self, object: Object) -> Self {
+            self.objects.push(object);
+            self
+        }
+    }
+
+    impl World for TestWorld {
+        fn intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+            let mut res: Vec<Intersection> = self
+                .objects
+                .iter()
+                .enumerate()
+                .flat_map(|(object, o)| {
+                    o.crossings(ray)
+                        .into_iter()
+                        .map(move |(t, normal)| Intersection {
+                            t,
+                            object,
+                            normal,
+                            material: &o.material,
+                            world_to_object: &o.world_to_object,
+                        })
+                })
+                .collect();
+            res.sort_by(|a, b| a.t.total_cmp(&b.t));
+            res
+        }
+
+        fn lights(&self) -> &[Box<dyn Light>] {
+            &self.lights
+        }
+
+        fn background(&self) -> Option<&dyn Background> {
+            self.background.as_deref()
+        }
+    }
+
+    fn mirror(reflective: f64) -> Phong {
+        Phong {
+            reflective,
+            ..Phong::default()
+        }
+    }
+
+    fn glass(refractive_index: f64) -> Phong {
+        Phong {
+            transparency: 1.0,
+            refractive_index,
+            ..Phong::default()
+        }
+    }
+
+    fn diagonal_ray() -> Ray {
+        let s = 2.0f64.sqrt() / 2.0;
+        Ray::new(Point::new(0.0, 0.0, -3.0), Vector::new(0.0, -s, s))
+    }
+
+    #[test]
+    fn default_material_is_not_reflective() {
+        assert_eq!(Phong::default().reflective, 0.0);
+    }
+
+    #[test]
+    fn prepare_computes_reflection_vector() {
+        let world = TestWorld {
+            objects: vec![Object::plane(0.0, false, Phong::default())],
+            lights: Vec::new(),
+            background: None,
+        };
+        let s = 2.0f64.sqrt() / 2.0;
+        let ray = Ray::new(Point::new(0.0, 1.0, -1.0), Vector::new(0.0, -s, s));
+        let xs = world.intersect(&ray);
+        let comps = Computations::prepare(&xs, 0, &ray);
+        assert_eq!(comps.reflectv, Vector::new(0.0, s, s));
+    }
+
+    #[test]
+    fn prepare_hit_inside() {
+        let world = TestWorld {
+            objects: vec![Object::sphere(Point::default(), 1.0, Phong::default())],
+            lights: Vec::new(),
+            background: None,
+        };
+        let ray = Ray::new(Point::default(), Vector::new(0.0, 0.0, 1.0));
+        let xs = world.intersect(&ray);
+        let comps = Computations::prepare(&xs, 1, &ray);
+        assert!(comps.inside);
+        assert_eq!(comps.point, Point::new(0.0, 0.0, 1.0));
+        assert_eq!(comps.normalv, Vector::new(0.0, 0.0, -1.0));
+        assert!(comps.over_point.z() < comps.point.z());
+    }
+
+    #[test]
+    fn reflected_color_of_nonreflective_material() {
+        let world = TestWorld::default_world();
+        let ray = Ray::new(Point::default(), Vector::new(0.0, 0.0, 1.0));
+        let xs = world.intersect(&ray);
+        let comps = Computations::prepare(&xs, 2, &ray);
+        let tracer = Whitted::default();
+        assert_eq!(
+            tracer.reflected_color(&world, &comps, tracer.max_depth()),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn reflected_color_of_reflective_material() {
+        let world = TestWorld::default_world().with(Object::plane(-1.0, false, mirror(0.5)));
+        let ray = diagonal_ray();
+        let xs = world.intersect(&ray);
+        let hit = xs.iter().position(|i| i.object == 2).unwrap();
+        let comps = Computations::prepare(&xs, hit, &ray);
+        assert_abs_diff_eq!(comps.t, 2.0f64.sqrt(), epsilon = EPSILON);
+
+        let tracer = Whitted::default();
+        assert_abs_diff_eq!(
+            tracer.reflected_color(&world, &comps, tracer.max_depth()),
+            Color::new(0.19032, 0.2379, 0.14274),
+            epsilon = 1.0e-4
+        );
+        assert_abs_diff_eq!(
+            tracer.shade_hit(&world, &comps, tracer.max_depth()),
+            Color::new(0.87677, 0.92436, 0.82918),
+            epsilon = 1.0e-4
+        );
+    }
+
+    #[test]
+    fn reflected_color_at_maximum_depth() {
+        let world = TestWorld::default_world().with(Object::plane(-1.0, false, mirror(0.5)));
+        let ray = diagonal_ray();
+        let xs = world.intersect(&ray);
+        let hit = xs.iter().position(|i| i.object == 2).unwrap();
+        let comps = Computations::prepare(&xs, hit, &ray);
+        assert_eq!(
+            Whitted::default().reflected_color(&world, &comps, 0),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn mutually_reflective_surfaces_terminate() {
+        let world = TestWorld {
+            objects: vec![
+                Object::plane(-1.0, false, mirror(1.0)),
+                Object::plane(1.0, true, mirror(1.0)),
+            ],
+            lights: vec![Box::new(PointLight::new(Point::default(), Color::white()))],
+            background: None,
+        };
+        let ray = Ray::new(Point::default(), Vector::new(0.0, 1.0, 0.0));
+
+        // Each bounce adds the same ambient and diffuse term, so the depth
+        // limit is exactly what bounds the result.
+        let shallow = Whitted::new(1).trace(&world, &ray);
+        let deep = Whitted::new(8).trace(&world, &ray);
+        assert!(deep.r().is_finite());
+        assert!(deep.r() > shallow.r());
+    }
+
+    #[test]
+    fn default_material_is_opaque_vacuum() {
+        let phong = Phong::default();
+        assert_eq!(phong.transparency, 0.0);
+        assert_eq!(phong.refractive_index, 1.0);
+    }
+
+    #[test]
+    fn prepare_finds_n1_and_n2() {
+        let world = TestWorld {
+            objects: vec![
+                Object::sphere(Point::default(), 2.0, glass(1.5)),
+                Object::sphere(Point::new(0.0, 0.0, -0.25), 1.0, glass(2.0)),
+                Object::sphere(Point::new(0.0, 0.0, 0.25), 1.0, glass(2.5)),
+            ],
+            lights: Vec::new(),
+            background: None,
+        };
+        let ray = Ray::new(Point::new(0.0, 0.0, -4.0), Vector::new(0.0, 0.0, 1.0));
+        let xs = world.intersect(&ray);
+        let expected = [
+            (1.0, 1.5),
+            (1.5, 2.0),
+            (2.0, 2.5),
+            (2.5, 2.5),
+            (2.5, 1.5),
+            (1.5, 1.0),
+        ];
+        assert_eq!(xs.len(), expected.len());
+        for (hit, (n1, n2)) in expected.iter().enumerate() {
+            let comps = Computations::prepare(&xs, hit, &ray);
+            assert_eq!((comps.n1, comps.n2), (*n1, *n2));
+        }
+    }
+
+    #[test]
+    fn prepare_computes_under_point() {
+        let world = TestWorld {
+            objects: vec![Object::sphere(Point::new(0.0, 0.0, 1.0), 1.0, glass(1.5))],
+            lights: Vec::new(),
+            background: None,
+        };
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        let xs = world.intersect(&ray);
+        let comps = Computations::prepare(&xs, 0, &ray);
+        assert!(comps.under_point.z() > 0.0);
+        assert!(comps.point.z() < comps.under_point.z());
+    }
+
+    #[test]
+    fn refracted_color_of_opaque_material() {
+        let world = TestWorld::default_world();
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        let xs = world.intersect(&ray);
+        let comps = Computations::prepare(&xs, 0, &ray);
+        assert_eq!(
+            Whitted::default().refracted_color(&world, &comps, 5),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn refracted_color_at_maximum_depth() {
+        let mut world = TestWorld::default_world();
+        world.objects[0].material = glass(1.5);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        let xs = world.intersect(&ray);
+        let comps = Computations::prepare(&xs, 0, &ray);
+        assert_eq!(
+            Whitted::default().refracted_color(&world, &comps, 0),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn refracted_color_under_total_internal_reflection() {
+        let mut world = TestWorld::default_world();
+        world.objects[0].material = glass(1.5);
+        let s = 2.0f64.sqrt() / 2.0;
+        let ray = Ray::new(Point::new(0.0, 0.0, s), Vector::new(0.0, 1.0, 0.0));
+        let xs = world.intersect(&ray);
+        assert_eq!(xs.len(), 2);
+        let comps = Computations::prepare(&xs, 1, &ray);
+        assert_eq!(
+            Whitted::default().refracted_color(&world, &comps, 5),
+            Color::black()
+        );
+    }
+
+    fn floor_and_ball(floor: Phong) -> TestWorld {
+        let ball = Phong {
+            color: Color::red(),
+            ambient: 0.5,
+            ..Phong::default()
+        };
+        TestWorld::default_world()
+            .with(Object::plane(-1.0, false, floor))
+            .with(Object::sphere(Point::new(0.0, -3.5, -0.5), 1.0, ball))
+    }
+
+    #[test]
+    fn shade_hit_with_transparent_material() {
+        let world = floor_and_ball(Phong {
+            transparency: 0.5,
+            refractive_index: 1.5,
+            ..Phong::default()
+        });
+        let ray = diagonal_ray();
+        let xs = world.intersect(&ray);
+        let hit = xs.iter().position(|i| i.object == 2).unwrap();
+        let comps = Computations::prepare(&xs, hit, &ray);
+        assert_abs_diff_eq!(
+            Whitted::default().shade_hit(&world, &comps, 5),
+            Color::new(0.93642, 0.68642, 0.68642),
+            epsilon = 1.0e-4
+        );
+    }
+
+    #[test]
+    fn shade_hit_blends_reflection_and_refraction_with_schlick() {
+        let world = floor_and_ball(Phong {
+            reflective: 0.5,
+            transparency: 0.5,
+            refractive_index: 1.5,
+            ..Phong::default()
+        });
+        let ray = diagonal_ray();
+        let xs = world.intersect(&ray);
+        let hit = xs.iter().position(|i| i.object == 2).unwrap();
+        let comps = Computations::prepare(&xs, hit, &ray);
+        assert_abs_diff_eq!(
+            Whitted::default().shade_hit(&world, &comps, 5),
+            Color::new(0.93391, 0.69643, 0.69243),
+            epsilon = 1.0e-4
+        );
+    }
+
+    #[test]
+    fn schlick_at_perpendicular_and_grazing_angles() {
+        let world = TestWorld {
+            objects: vec![Object::sphere(Point::default(), 1.0, glass(1.5))],
+            lights: Vec::new(),
+            background: None,
+        };
+        let ray = Ray::new(Point::default(), Vector::new(0.0, 1.0, 0.0));
+        let xs = world.intersect(&ray);
+        let comps = Computations::prepare(&xs, 1, &ray);
+        assert_abs_diff_eq!(comps.schlick(), 0.04, epsilon = EPSILON);
+
+        let ray = Ray::new(Point::new(0.0, 0.99, -2.0), Vector::new(0.0, 0.0, 1.0));
+        let xs = world.intersect(&ray);
+        let comps = Computations::prepare(&xs, 0, &ray);
+        assert_abs_diff_eq!(comps.schlick(), 0.48873, epsilon = 1.0e-4);
+    }
+
+    #[test]
+    fn patterned_floor_is_shaded_per_hit_point() {
+        let floor = Phong {
+            pattern: Some(Stripe::new(Color::white(), Color::black()).into()),
+            ambient: 1.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Phong::default()
+        };
+        let world = TestWorld {
+            objects: vec![Object::plane(0.0, false, floor)],
+            lights: vec![Box::new(PointLight::new(
+                Point::new(0.0, 10.0, 0.0),
+                Color::white(),
+            ))],
+            background: None,
+        };
+        let down = Vector::new(0.0, -1.0, 0.0);
+        let tracer = Whitted::default();
+        assert_eq!(
+            tracer.trace(&world, &Ray::new(Point::new(0.5, 1.0, 0.0), down)),
+            Color::white()
+        );
+        assert_eq!(
+            tracer.trace(&world, &Ray::new(Point::new(1.5, 1.0, 0.0), down)),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn ray_missing_everything_is_black() {
+        let world = TestWorld::default_world();
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(Whitted::default().trace(&world, &ray), Color::black());
+    }
+
+    #[test]
+    fn ray_missing_everything_sees_background() {
+        let mut world = TestWorld::default_world();
+        world.background = Some(Box::new(VerticalGradient::new(
+            Color::black(),
+            Color::white(),
+        )));
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(Whitted::default().trace(&world, &ray), Color::white());
+
+        world.background = Some(Box::new(Flat::new(Color::new(0.2, 0.4, 0.8))));
+        assert_eq!(
+            Whitted::default().trace(&world, &ray),
+            Color::new(0.2, 0.4, 0.8)
+        );
+    }
+
+    #[test]
+    fn reflected_rays_see_background() {
+        let floor = Phong {
+            color: Color::black(),
+            specular: 0.0,
+            reflective: 1.0,
+            ..Phong::default()
+        };
+        let world = TestWorld {
+            objects: vec![Object::plane(0.0, false, floor)],
+            lights: Vec::new(),
+            background: Some(Box::new(VerticalGradient::new(
+                Color::black(),
+                Color::white(),
+            ))),
+        };
+        let s = 2.0f64.sqrt() / 2.0;
+        let ray = Ray::new(Point::new(0.0, 1.0, -1.0), Vector::new(0.0, -s, s));
+
+        // The mirror direction points 45 degrees up into the gradient.
+        let expected = Color::white() * (0.5 * (s + 1.0));
+        assert_eq!(Whitted::default().trace(&world, &ray), expected);
+        assert_eq!(Whitted::new(0).trace(&world, &ray), Color::black());
+    }
+
+    #[test]
+    fn refracted_rays_see_background() {
+        let pane = Phong {
+            color: Color::black(),
+            specular: 0.0,
+            ..glass(1.0)
+        };
+        let world = TestWorld {
+            objects: vec![Object::sphere(Point::default(), 1.0, pane)],
+            lights: Vec::new(),
+            background: Some(Box::new(Flat::new(Color::new(0.2, 0.4, 0.8)))),
+        };
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            Whitted::default().trace(&world, &ray),
+            Color::new(0.2, 0.4, 0.8)
+        );
+    }
+
+    #[test]
+    fn shadowed_hit_keeps_only_ambient() {
+        let world = TestWorld {
+            objects: vec![
+                Object::sphere(Point::default(), 1.0, Phong::default()),
+                Object::sphere(Point::new(0.0, 0.0, 10.0), 1.0, Phong::default()),
+            ],
+            lights: vec![Box::new(PointLight::new(
+                Point::new(0.0, 0.0, -10.0),
+                Color::white(),
+            ))],
+            background: None,
+        };
+        let ray = Ray::new(Point::new(0.0, 0.0, 5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            Whitted::default().trace(&world, &ray),
+            Color::new(0.1, 0.1, 0.1)
+        );
+    }
+}
@@ -1,15 +1,173 @@
 pub mod primitives {
+    pub use blackbody::planck;
     pub use canvas::Canvas;
     pub use color::Color;
+    pub use color_parse::ColorParseError;
+    pub use color_space::{cie_xyz, Hsl, Hsv, Lab, Oklab, Xyz};
+    pub use float::{Float, EPSILON};
     pub use matrix::Matrix;
+    pub use pfm::PfmError;
     pub use point::Point;
+    pub use ppm::PpmError;
+    pub use ray::Ray;
+    #[cfg(feature = "simd")]
+    pub use ray_packet::RayPacket;
     pub use tuple::Tuple;
     pub use vector::Vector;
 
+    mod blackbody;
     mod canvas;
     mod color;
+    mod color_parse;
+    mod color_space;
+    mod float;
     mod matrix;
+    mod pfm;
     mod point;
+    mod ppm;
+    mod ray;
+    #[cfg(feature = "simd")]
+    mod ray_packet;
+    mod simd;
     mod tuple;
     mod vector;
 }
+pub mod materials {
+    pub use bsdf::{Bsdf, BsdfSample, Lambertian};
+    #[cfg(feature = "spectral")]
+    pub use dielectric::Dielectric;
+    pub use emission::Emission;
+    pub use microfacet::{Distribution, Fresnel, MicrofacetReflection};
+    pub use principled::Principled;
+    pub use subsurface::Subsurface;
+
+    mod bsdf;
+    #[cfg(feature = "spectral")]
+    mod dielectric;
+    mod emission;
+    mod microfacet;
+    mod principled;
+    mod subsurface;
+}
+pub mod media {
+    pub use grid::{DensityGrid, Heterogeneous};
+    pub(crate) use medium::clip;
+    pub use medium::{Bounded, Homogeneous, Medium, MediumSample};
+    pub use phase::HenyeyGreenstein;
+
+    mod grid;
+    mod medium;
+    mod phase;
+}
+pub mod optics {
+    pub use containers::refractive_indices;
+    pub use fresnel::{
+        fresnel_blend, fresnel_conductor, fresnel_dielectric, schlick, schlick_color,
+    };
+
+    mod containers;
+    mod fresnel;
+}
+pub mod patterns {
+    pub use blend::Blend;
+    pub use checkers::Checkers;
+    pub use gradient::Gradient;
+    pub use noise::perlin;
+    pub use pattern::{Pattern, PatternTransform};
+    pub use perturbed::Perturbed;
+    pub use radial_gradient::RadialGradient;
+    pub use ring::Ring;
+    pub use solid::Solid;
+    pub use stripe::Stripe;
+
+    mod blend;
+    mod checkers;
+    mod gradient;
+    mod noise;
+    mod pattern;
+    mod perturbed;
+    mod radial_gradient;
+    mod ring;
+    mod solid;
+    mod stripe;
+}
+#[cfg(feature = "spectral")]
+pub mod spectral {
+    pub use crate::primitives::cie_xyz;
+    pub(crate) use cie::{cie_y_integral, integrate};
+    pub use cie::{xyz_to_color, LAMBDA_MAX, LAMBDA_MIN};
+    pub use ior::Ior;
+    pub use sampled::{SampledSpectrum, SampledWavelengths, WAVELENGTH_SAMPLES};
+    pub use spectrum::Spectrum;
+
+    mod cie;
+    mod ior;
+    mod sampled;
+    mod spectrum;
+}
+pub mod textures {
+    pub use image_texture::{ImageTexture, TextureFilter, WrapMode};
+    pub use texture_map::{CubeMap, TextureMap};
+    pub use uv_mapping::{cube_uv, CubeFace, UvMapping};
+
+    mod image_texture;
+    mod texture_map;
+    mod uv_mapping;
+}
+pub mod environment {
+    pub use background::{Background, Flat, VerticalGradient};
+    pub use equirectangular::Equirectangular;
+    pub use sky_box::SkyBox;
+
+    mod background;
+    mod equirectangular;
+    mod sky_box;
+}
+pub mod cameras {
+    pub use camera::Camera;
+    pub use lens::{ApertureShape, ThinLens};
+    pub use projection::Projection;
+    pub use stereo::{anaglyph, compose, StereoLayout, StereoRig};
+
+    mod camera;
+    mod lens;
+    mod projection;
+    mod stereo;
+}
+pub mod integrators {
+    pub use accumulator::Accumulator;
+    pub use path_tracer::{russian_roulette, PathTracer, Scene, SurfaceInteraction};
+    pub use whitted::{Computations, Intersection, Whitted, World};
+
+    mod accumulator;
+    mod path_tracer;
+    mod whitted;
+}
+pub mod lights {
+    pub use area::AreaLight;
+    pub use directional::DirectionalLight;
+    pub use light::{Light, LightHit, LightSample, PointLight};
+    pub use lighting::{lighting, Phong};
+    pub use mesh_light::{Emitter, MeshLight};
+    pub use spot::SpotLight;
+
+    mod area;
+    mod directional;
+    mod light;
+    mod lighting;
+    mod mesh_light;
+    mod spot;
+}
+pub mod sampling {
+    pub use filter::Filter;
+    pub use mis::{balance_heuristic, power_heuristic, Heuristic};
+    pub use rng::Rng;
+    pub use supersampler::{SamplePattern, Supersampler};
+    pub use warp::{cosine_hemisphere, orthonormal_basis, sample_disk, sample_polygon};
+
+    mod filter;
+    mod mis;
+    mod rng;
+    mod supersampler;
+    mod warp;
+}
@@ -0,0 +1,288 @@
+use crate::lights::{Light, LightHit, LightSample};
+use crate::primitives::{Color, Point, Ray, Tuple, Vector};
+use crate::sampling::Rng;
+
+// ------------------------------------------------------
+/// Rectangular light spanned by `uvec` and `vvec` from `corner`, split into
+/// `usteps * vsteps` cells. Each cell contributes one sample, jittered within
+/// the cell, so partially occluded lights cast soft shadows.
+#[derive(Clone, Copy, Debug)]
+pub struct AreaLight {
+    corner: Point,
+    uvec: Vector,
+    usteps: usize,
+    vvec: Vector,
+    vsteps: usize,
+    intensity: Color,
+    jitter: bool,
+    seed: u64,
+}
+
+impl AreaLight {
+    pub fn new(
+        corner: Point,
+        uvec: Vector,
+        usteps: usize,
+        vvec: Vector,
+        vsteps: usize,
+        intensity: Color,
+    ) -> Self {
+        if usteps == 0 || vsteps == 0 {
+            panic!("Area light needs at least one cell in each direction.");
+        }
+
+        AreaLight {
+            corner,
+            uvec,
+            usteps,
+            vvec,
+            vsteps,
+            intensity,
+            jitter: true,
+            seed: 0,
+        }
+    }
+
+    /// Without jitter every sample sits at its cell center, which bands
+    /// shadows but is handy for reproducible comparisons.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.usteps * self.vsteps
+    }
+
+    /// Position within cell `(u, v)`, offset by `(ju, jv)` in `[0, 1)`.
+    pub fn point_on_light(&self, u: usize, v: usize, ju: f64, jv: f64) -> Point {
+        self.corner
+            + self.uvec * ((u as f64 + ju) / self.usteps as f64)
+            + self.vvec * ((v as f64 + jv) / self.vsteps as f64)
+    }
+
+    pub fn area(&self) -> f64 {
+        (self.uvec * self.vvec).magnitude()
+    }
+
+    /// Unit normal of the light's plane; the light emits from both sides.
+    pub fn normal(&self) -> Vector {
+        (self.uvec * self.vvec).normalize()
+    }
+
+    /// Solid angle density of uniformly sampling the light's area, seen
+    /// along `direction` from `distance` away.
+    fn solid_angle_pdf(&self, direction: Vector, distance: f64) -> f64 {
+        let cos = direction.dot(self.normal()).abs();
+        if cos == 0.0 {
+            return 0.0;
+        }
+        distance * distance / (self.area() * cos)
+    }
+
+    /// Jitter depends only on the seed and the shaded point, so renders are
+    /// reproducible while neighbouring points still see different samples.
+    fn rng_for(&self, point: Point) -> Rng {
+        let hash = [point.x(), point.y(), point.z()]
+            .iter()
+            .fold(self.seed, |h, c| {
+                (h ^ c.to_bits()).wrapping_mul(0x0100_0000_01B3)
+            });
+        Rng::new(hash)
+    }
+}
+
+impl Light for AreaLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, point: Point) -> Vec<LightSample> {
+        let mut rng = self.rng_for(point);
+        let intensity = self.intensity * (1.0 / self.sample_count() as f64);
+
+        let mut samples = Vec::with_capacity(self.sample_count());
+        for v in 0..self.vsteps {
+            for u in 0..self.usteps {
+                let (ju, jv) = if self.jitter {
+                    (rng.next_f64(), rng.next_f64())
+                } else {
+                    (0.5, 0.5)
+                };
+                let to_light = self.point_on_light(u, v, ju, jv) - point;
+                samples.push(LightSample {
+                    direction: to_light.normalize(),
+                    distance: to_light.magnitude(),
+                    intensity,
+                });
+            }
+        }
+        samples
+    }
+
+    fn is_delta(&self) -> bool {
+        false
+    }
+
+    /// Uniform point on the light; `intensity` is its emitted radiance.
+    fn sample_li(&self, point: Point, u: f64, v: f64) -> Option<(LightSample, f64)> {
+        let to_light = self.corner + self.uvec * u + self.vvec * v - point;
+        let distance = to_light.magnitude();
+        let direction = to_light.normalize();
+        let pdf = self.solid_angle_pdf(direction, distance);
+        (pdf > 0.0).then_some((
+            LightSample {
+                direction,
+                distance,
+                intensity: self.intensity,
+            },
+            pdf,
+        ))
+    }
+
+    fn hit(&self, ray: &Ray) -> Option<LightHit> {
+        let cross = self.uvec * self.vvec;
+        let denom = cross.dot(ray.direction);
+        if denom.abs() < 1.0e-12 {
+            return None;
+        }
+
+        let t = cross.dot(self.corner - ray.origin) / denom;
+        if t <= 0.0 {
+            return None;
+        }
+
+        // Coordinates of the hit in the (uvec, vvec) frame.
+        let offset = ray.position(t) - self.corner;
+        let area2 = cross.dot(cross);
+        let a = (offset * self.vvec).dot(cross) / area2;
+        let b = (self.uvec * offset).dot(cross) / area2;
+        if !(0.0..=1.0).contains(&a) || !(0.0..=1.0).contains(&b) {
+            return None;
+        }
+
+        let distance = t * ray.direction.magnitude();
+        Some(LightHit {
+            distance,
+            radiance: self.intensity,
+            pdf: self.solid_angle_pdf(ray.direction.normalize(), distance),
+        })
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn light() -> AreaLight {
+        AreaLight::new(
+            Point::new(0.0, 0.0, 0.0),
+            Vector::new(2.0, 0.0, 0.0),
+            4,
+            Vector::new(0.0, 0.0, 1.0),
+            2,
+            Color::white(),
+        )
+    }
+
+    #[test]
+    fn area_light_cell_centers() {
+        let light = light().with_jitter(false);
+        assert_eq!(light.sample_count(), 8);
+        assert_eq!(
+            light.point_on_light(0, 0, 0.5, 0.5),
+            Point::new(0.25, 0.0, 0.25)
+        );
+        assert_eq!(
+            light.point_on_light(3, 1, 0.5, 0.5),
+            Point::new(1.75, 0.0, 0.75)
+        );
+
+        let p = Point::new(1.0, -2.0, 0.5);
+        let samples = light.samples(p);
+        assert_eq!(samples.len(), 8);
+        assert_eq!(
+            p + samples[0].direction * samples[0].distance,
+            Point::new(0.25, 0.0, 0.25)
+        );
+    }
+
+    #[test]
+    fn area_light_samples_sum_to_intensity() {
+        let samples = light().samples(Point::new(0.0, -3.0, 0.0));
+        let total = samples
+            .iter()
+            .fold(Color::black(), |acc, s| acc + s.intensity);
+        assert_eq!(total, Color::white());
+    }
+
+    #[test]
+    fn area_light_jitter_stays_in_cell() {
+        let light = light();
+        let p = Point::new(0.3, -1.0, 0.7);
+        let samples = light.samples(p);
+        for (i, s) in samples.iter().enumerate() {
+            let hit = p + s.direction * s.distance;
+            let (u, v) = (i % 4, i / 4);
+            assert!(hit.x() >= u as f64 * 0.5 - 1e-9 && hit.x() <= (u + 1) as f64 * 0.5 + 1e-9);
+            assert!(hit.z() >= v as f64 * 0.5 - 1e-9 && hit.z() <= (v + 1) as f64 * 0.5 + 1e-9);
+        }
+
+        // Deterministic for the same point, different for another.
+        assert_eq!(light.samples(p), samples);
+        assert_ne!(light.samples(Point::new(0.3, -1.0, 0.8))[0], samples[0]);
+    }
+
+    #[test]
+    fn area_light_hit_matches_sample() {
+        let light = light();
+        assert!(!light.is_delta());
+        assert_eq!(light.area(), 2.0);
+        assert_eq!(light.normal(), Vector::new(0.0, -1.0, 0.0));
+
+        let p = Point::new(0.5, -2.0, 0.5);
+        let (sample, pdf) = light.sample_li(p, 0.25, 0.75).unwrap();
+        assert_eq!(
+            p + sample.direction * sample.distance,
+            Point::new(0.5, 0.0, 0.75)
+        );
+        assert_eq!(sample.intensity, Color::white());
+
+        let hit = light.hit(&Ray::new(p, sample.direction)).unwrap();
+        approx::assert_abs_diff_eq!(hit.distance, sample.distance, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(hit.pdf, pdf, epsilon = 1e-9);
+        assert_eq!(hit.radiance, Color::white());
+
+        // Head on from two units away: pdf = r^2 / (area * cos).
+        let (_, pdf) = light
+            .sample_li(Point::new(1.0, -2.0, 0.5), 0.5, 0.5)
+            .unwrap();
+        assert_eq!(pdf, 2.0);
+    }
+
+    #[test]
+    fn area_light_misses() {
+        let light = light();
+        let p = Point::new(0.5, -2.0, 0.5);
+        assert!(light
+            .hit(&Ray::new(p, Vector::new(0.0, -1.0, 0.0)))
+            .is_none());
+        assert!(light
+            .hit(&Ray::new(p, Vector::new(1.0, 0.0, 0.0)))
+            .is_none());
+        assert!(light
+            .hit(&Ray::new(p, Vector::new(2.0, 1.0, 0.0).normalize()))
+            .is_none());
+        // Emits from both sides.
+        let above = Point::new(0.5, 2.0, 0.5);
+        assert!(light
+            .hit(&Ray::new(above, Vector::new(0.0, -1.0, 0.0)))
+            .is_some());
+    }
+}
@@ -0,0 +1,60 @@
+use crate::lights::{Light, LightSample};
+use crate::primitives::{Color, Point, Vector};
+
+// ------------------------------------------------------
+/// Light arriving from infinitely far away along parallel rays, like the sun.
+#[derive(Clone, Copy, Debug)]
+pub struct DirectionalLight {
+    direction: Vector,
+    intensity: Color,
+}
+
+impl DirectionalLight {
+    /// `direction` is the way the light travels, e.g. straight down for a
+    /// sun at its zenith.
+    pub fn new(direction: Vector, intensity: Color) -> Self {
+        DirectionalLight {
+            direction: direction.normalize(),
+            intensity,
+        }
+    }
+
+    pub fn direction(&self) -> Vector {
+        self.direction
+    }
+}
+
+impl Light for DirectionalLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, _point: Point) -> Vec<LightSample> {
+        vec![LightSample {
+            direction: -self.direction,
+            distance: f64::INFINITY,
+            intensity: self.intensity,
+        }]
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Tuple;
+
+    #[test]
+    fn directional_light_is_position_independent() {
+        let light = DirectionalLight::new(Vector::new(0.0, -2.0, 0.0), Color::white());
+        assert_eq!(light.direction(), Vector::new(0.0, -1.0, 0.0));
+
+        for p in [Point::new(0.0, 0.0, 0.0), Point::new(100.0, -5.0, 3.0)] {
+            let samples = light.samples(p);
+            assert_eq!(samples.len(), 1);
+            assert_eq!(samples[0].direction, Vector::new(0.0, 1.0, 0.0));
+            assert_eq!(samples[0].distance, f64::INFINITY);
+            assert_eq!(samples[0].intensity, Color::white());
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::primitives::{Color, Point, Ray, Vector};
+
+// ------------------------------------------------------
+/// One direction from which a light reaches a surface point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightSample {
+    /// Normalized direction from the surface point towards the light.
+    pub direction: Vector,
+    /// Distance to the sampled position, `f64::INFINITY` for lights at infinity.
+    pub distance: f64,
+    /// Intensity carried by this sample; the samples of a light sum to its
+    /// contribution at the point.
+    pub intensity: Color,
+}
+
+/// Where a ray reaches a light with an area.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightHit {
+    pub distance: f64,
+    /// Radiance emitted back along the ray.
+    pub radiance: Color,
+    /// Solid angle density with which `Light::sample_li` picks this
+    /// direction from the ray origin.
+    pub pdf: f64,
+}
+
+/// Anything that illuminates the scene.
+pub trait Light {
+    /// Nominal intensity, used for the ambient term.
+    fn intensity(&self) -> Color;
+
+    /// Samples of the light as seen from `point`. Shadow rays are cast along
+    /// each sample's direction up to its distance.
+    fn samples(&self, point: Point) -> Vec<LightSample>;
+
+    /// Whether the light has no area, so rays can never hit it and it can
+    /// only be sampled explicitly.
+    fn is_delta(&self) -> bool {
+        true
+    }
+
+    /// One random sample for physically based integrators, with the solid
+    /// angle density it was drawn with. For lights with an area the sample's
+    /// intensity is the emitted radiance; delta lights return their single
+    /// sample with a density of one.
+    fn sample_li(&self, point: Point, _u: f64, _v: f64) -> Option<(LightSample, f64)> {
+        self.samples(point).into_iter().next().map(|s| (s, 1.0))
+    }
+
+    /// Where `ray` hits the light; always `None` for delta lights.
+    fn hit(&self, _ray: &Ray) -> Option<LightHit> {
+        None
+    }
+}
+
+// ------------------------------------------------------
+/// Infinitely small light radiating equally in every direction.
+#[derive(Clone, Copy, Debug)]
+pub struct PointLight {
+    position: Point,
+    intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Point, intensity: Color) -> Self {
+        PointLight {
+            position,
+            intensity,
+        }
+    }
+
+    pub fn position(&self) -> Point {
+        self.position
+    }
+}
+
+impl Light for PointLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, point: Point) -> Vec<LightSample> {
+        let to_light = self.position - point;
+        vec![LightSample {
+            direction: to_light.normalize(),
+            distance: to_light.magnitude(),
+            intensity: self.intensity,
+        }]
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Tuple;
+
+    #[test]
+    fn point_light_sample() {
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        assert_eq!(light.position(), Point::new(0.0, 0.0, -10.0));
+        assert_eq!(light.intensity(), Color::white());
+
+        let samples = light.samples(Point::new(0.0, 0.0, 0.0));
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].direction, Vector::new(0.0, 0.0, -1.0));
+        assert_eq!(samples[0].distance, 10.0);
+        assert_eq!(samples[0].intensity, Color::white());
+    }
+}
@@ -0,0 +1,401 @@
+use crate::lights::{Light, LightSample};
+use crate::patterns::Pattern;
+use crate::primitives::{Color, Matrix, Point, Vector};
+
+// ------------------------------------------------------
+/// Phong reflection parameters of a surface.
+pub struct Phong {
+    pub color: Color,
+    /// Paints the surface instead of `color` when set.
+    pub pattern: Option<Box<dyn Pattern>>,
+    pub ambient: f64,
+    pub diffuse: f64,
+    pub specular: f64,
+    pub shininess: f64,
+    /// Fraction of the light arriving from the mirror direction that is
+    /// reflected; `0.0` is a matte surface and `1.0` a perfect mirror.
+    pub reflective: f64,
+    /// Fraction of the light coming through the surface from behind; `0.0`
+    /// is opaque.
+    pub transparency: f64,
+    /// Refractive index of the object's interior; `1.0` is a vacuum.
+    pub refractive_index: f64,
+}
+
+impl Default for Phong {
+    fn default() -> Self {
+        Phong {
+            color: Color::white(),
+            pattern: None,
+            ambient: 0.1,
+            diffuse: 0.9,
+            specular: 0.9,
+            shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+        }
+    }
+}
+
+impl Phong {
+    /// Surface color at `point`, in world space, on an object whose inverse
+    /// transform is `world_to_object`.
+    pub fn color_at(&self, world_to_object: &Matrix, point: Point) -> Color {
+        match &self.pattern {
+            Some(pattern) => pattern.pattern_at_shape(world_to_object, point),
+            None => self.color,
+        }
+    }
+}
+
+impl std::fmt::Debug for Phong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Phong")
+            .field("color", &self.color)
+            .field("pattern", &self.pattern.as_ref().map(|_| ".."))
+            .field("ambient", &self.ambient)
+            .field("diffuse", &self.diffuse)
+            .field("specular", &self.specular)
+            .field("shininess", &self.shininess)
+            .field("reflective", &self.reflective)
+            .field("transparency", &self.transparency)
+            .field("refractive_index", &self.refractive_index)
+            .finish()
+    }
+}
+
+// ------------------------------------------------------
+/// Phong shading of `point` lit by `light`. Diffuse and specular terms are
+/// summed over the light's samples, skipping those for which `occluded`
+/// returns true, so area lights give fractional (soft) shadows. A pattern on
+/// the material is evaluated in the space of the object, whose inverse
+/// transform is `world_to_object`.
+pub fn lighting(
+    phong: &Phong,
+    world_to_object: &Matrix,
+    light: &dyn Light,
+    point: Point,
+    eyev: Vector,
+    normalv: Vector,
+    occluded: impl Fn(&LightSample) -> bool,
+) -> Color {
+    let color = phong.color_at(world_to_object, point);
+    let ambient = color * light.intensity() * phong.ambient;
+
+    light
+        .samples(point)
+        .iter()
+        .filter(|sample| !occluded(sample))
+        .fold(ambient, |acc, sample| {
+            let light_dot_normal = sample.direction.dot(normalv);
+            if light_dot_normal < 0.0 {
+                return acc;
+            }
+
+            let diffuse = color * sample.intensity * (phong.diffuse * light_dot_normal);
+            let reflect_dot_eye = (-sample.direction).reflect(normalv).dot(eyev);
+            let specular = if reflect_dot_eye <= 0.0 {
+                Color::black()
+            } else {
+                sample.intensity * (phong.specular * reflect_dot_eye.powf(phong.shininess))
+            };
+            acc + diffuse + specular
+        })
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lights::{AreaLight, DirectionalLight, PointLight, SpotLight};
+    use crate::patterns::Stripe;
+    use crate::primitives::Tuple;
+
+    fn unlit(_: &LightSample) -> bool {
+        false
+    }
+
+    #[test]
+    fn lighting_eye_between_light_and_surface() {
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let res = lighting(
+            &Phong::default(),
+            &Matrix::identity(),
+            &light,
+            Point::default(),
+            eyev,
+            normalv,
+            unlit,
+        );
+        assert_eq!(res, Color::new(1.9, 1.9, 1.9));
+    }
+
+    #[test]
+    fn lighting_eye_offset_45() {
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        let s = 2.0f64.sqrt() / 2.0;
+        let eyev = Vector::new(0.0, s, -s);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let res = lighting(
+            &Phong::default(),
+            &Matrix::identity(),
+            &light,
+            Point::default(),
+            eyev,
+            normalv,
+            unlit,
+        );
+        assert_eq!(res, Color::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn lighting_light_offset_45() {
+        let light = PointLight::new(Point::new(0.0, 10.0, -10.0), Color::white());
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let res = lighting(
+            &Phong::default(),
+            &Matrix::identity(),
+            &light,
+            Point::default(),
+            eyev,
+            normalv,
+            unlit,
+        );
+        assert_eq!(res, Color::new(0.7364, 0.7364, 0.7364));
+    }
+
+    #[test]
+    fn lighting_eye_in_reflection_path() {
+        let light = PointLight::new(Point::new(0.0, 10.0, -10.0), Color::white());
+        let s = 2.0f64.sqrt() / 2.0;
+        let eyev = Vector::new(0.0, -s, -s);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let res = lighting(
+            &Phong::default(),
+            &Matrix::identity(),
+            &light,
+            Point::default(),
+            eyev,
+            normalv,
+            unlit,
+        );
+        assert_eq!(res, Color::new(1.6364, 1.6364, 1.6364));
+    }
+
+    #[test]
+    fn lighting_light_behind_surface() {
+        let light = PointLight::new(Point::new(0.0, 0.0, 10.0), Color::white());
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let res = lighting(
+            &Phong::default(),
+            &Matrix::identity(),
+            &light,
+            Point::default(),
+            eyev,
+            normalv,
+            unlit,
+        );
+        assert_eq!(res, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_in_shadow() {
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let res = lighting(
+            &Phong::default(),
+            &Matrix::identity(),
+            &light,
+            Point::default(),
+            eyev,
+            normalv,
+            |_| true,
+        );
+        assert_eq!(res, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_accepts_any_light() {
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let lights: Vec<Box<dyn Light>> = vec![
+            Box::new(PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white())),
+            Box::new(DirectionalLight::new(
+                Vector::new(0.0, 0.0, 1.0),
+                Color::white(),
+            )),
+            Box::new(SpotLight::new(
+                Point::new(0.0, 0.0, -10.0),
+                Vector::new(0.0, 0.0, 1.0),
+                Color::white(),
+                0.1,
+                0.2,
+            )),
+        ];
+        for light in &lights {
+            let res = lighting(
+                &Phong::default(),
+                &Matrix::identity(),
+                light.as_ref(),
+                Point::default(),
+                eyev,
+                normalv,
+                unlit,
+            );
+            assert_eq!(res, Color::new(1.9, 1.9, 1.9));
+        }
+    }
+
+    #[test]
+    fn lighting_area_light_soft_shadow() {
+        let light = AreaLight::new(
+            Point::new(-0.5, -0.5, -5.0),
+            Vector::new(1.0, 0.0, 0.0),
+            2,
+            Vector::new(0.0, 1.0, 0.0),
+            2,
+            Color::white(),
+        )
+        .with_jitter(false);
+        let phong = Phong {
+            ambient: 0.1,
+            diffuse: 0.9,
+            specular: 0.0,
+            ..Phong::default()
+        };
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let identity = Matrix::identity();
+
+        let lit = lighting(
+            &phong,
+            &identity,
+            &light,
+            Point::default(),
+            eyev,
+            normalv,
+            unlit,
+        );
+        let dark = lighting(
+            &phong,
+            &identity,
+            &light,
+            Point::default(),
+            eyev,
+            normalv,
+            |_| true,
+        );
+        // Occlude the left half of the light.
+        let half = lighting(
+            &phong,
+            &identity,
+            &light,
+            Point::default(),
+            eyev,
+            normalv,
+            |s| s.direction.x() < 0.0,
+        );
+
+        assert_eq!(dark, Color::new(0.1, 0.1, 0.1));
+        assert!(lit.r() > 0.95 && lit.r() <= 1.0);
+        assert_eq!(half, (lit + dark) * 0.5);
+    }
+
+    fn striped() -> Phong {
+        Phong {
+            pattern: Some(Stripe::new(Color::white(), Color::black()).into()),
+            ambient: 1.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Phong::default()
+        }
+    }
+
+    #[test]
+    fn lighting_with_pattern() {
+        let phong = striped();
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let identity = Matrix::identity();
+
+        let c1 = lighting(
+            &phong,
+            &identity,
+            &light,
+            Point::new(0.9, 0.0, 0.0),
+            eyev,
+            normalv,
+            unlit,
+        );
+        let c2 = lighting(
+            &phong,
+            &identity,
+            &light,
+            Point::new(1.1, 0.0, 0.0),
+            eyev,
+            normalv,
+            unlit,
+        );
+        assert_eq!(c1, Color::white());
+        assert_eq!(c2, Color::black());
+    }
+
+    #[test]
+    fn lighting_evaluates_pattern_in_object_space() {
+        let phong = striped();
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let normalv = Vector::new(0.0, 0.0, -1.0);
+        let point = Point::new(1.5, 0.0, 0.0);
+
+        // The object is scaled up by 2, so its inverse halves world points.
+        #[rustfmt::skip]
+        let world_to_object = Matrix::new4().init([
+            0.5, 0.0, 0.0, 0.0,
+            0.0, 0.5, 0.0, 0.0,
+            0.0, 0.0, 0.5, 0.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+
+        let world = lighting(
+            &phong,
+            &Matrix::identity(),
+            &light,
+            point,
+            eyev,
+            normalv,
+            unlit,
+        );
+        let object = lighting(
+            &phong,
+            &world_to_object,
+            &light,
+            point,
+            eyev,
+            normalv,
+            unlit,
+        );
+        assert_eq!(world, Color::black());
+        assert_eq!(object, Color::white());
+    }
+
+    #[test]
+    fn material_without_pattern_uses_its_color() {
+        let phong = Phong {
+            color: Color::red(),
+            ..Phong::default()
+        };
+        assert_eq!(
+            phong.color_at(&Matrix::identity(), Point::new(1.5, 0.0, 0.0)),
+            Color::red()
+        );
+        assert!(Phong::default().pattern.is_none());
+    }
+}
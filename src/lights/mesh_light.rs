@@ -0,0 +1,377 @@
+use std::f64::consts::PI;
+
+use crate::lights::{Light, LightHit, LightSample};
+use crate::materials::Emission;
+use crate::primitives::{Color, Point, Ray, Tuple, Vector};
+
+// ------------------------------------------------------
+/// Piece of emissive geometry. Triangles emit from their front face, the
+/// side `(p1 - p0) * (p2 - p0)` points to; spheres emit outwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Emitter {
+    Triangle { p0: Point, p1: Point, p2: Point },
+    Sphere { center: Point, radius: f64 },
+}
+
+impl Emitter {
+    pub fn area(&self) -> f64 {
+        match *self {
+            Emitter::Triangle { p0, p1, p2 } => ((p1 - p0) * (p2 - p0)).magnitude() / 2.0,
+            Emitter::Sphere { radius, .. } => 4.0 * PI * radius * radius,
+        }
+    }
+
+    /// Point uniformly distributed over the surface, with its normal.
+    pub fn sample_point(&self, u: f64, v: f64) -> (Point, Vector) {
+        match *self {
+            Emitter::Triangle { p0, p1, p2 } => {
+                let su = u.sqrt();
+                let (b1, b2) = (su * (1.0 - v), su * v);
+                let p = p0 + (p1 - p0) * b1 + (p2 - p0) * b2;
+                (p, ((p1 - p0) * (p2 - p0)).normalize())
+            }
+            Emitter::Sphere { center, radius } => {
+                let z = 1.0 - 2.0 * u;
+                let r = (1.0 - z * z).max(0.0).sqrt();
+                let phi = 2.0 * PI * v;
+                let normal = Vector::new(r * phi.cos(), r * phi.sin(), z);
+                (center + normal * radius, normal)
+            }
+        }
+    }
+
+    /// Closest intersection in front of the ray origin, with the normal
+    /// there.
+    pub fn intersect(&self, ray: &Ray) -> Option<(f64, Vector)> {
+        match *self {
+            Emitter::Triangle { p0, p1, p2 } => {
+                // Moller-Trumbore.
+                let (e1, e2) = (p1 - p0, p2 - p0);
+                let p = ray.direction * e2;
+                let det = e1.dot(p);
+                if det.abs() < 1.0e-12 {
+                    return None;
+                }
+                let s = ray.origin - p0;
+                let u = s.dot(p) / det;
+                let q = s * e1;
+                let v = ray.direction.dot(q) / det;
+                if u < 0.0 || v < 0.0 || u + v > 1.0 {
+                    return None;
+                }
+                let t = e2.dot(q) / det;
+                (t > 0.0).then(|| (t, (e1 * e2).normalize()))
+            }
+            Emitter::Sphere { center, radius } => {
+                let oc = ray.origin - center;
+                let a = ray.direction.dot(ray.direction);
+                let b = oc.dot(ray.direction);
+                let c = oc.dot(oc) - radius * radius;
+                let disc = b * b - a * c;
+                if disc < 0.0 {
+                    return None;
+                }
+                let sqrt = disc.sqrt();
+                let t = [(-b - sqrt) / a, (-b + sqrt) / a]
+                    .into_iter()
+                    .find(|&t| t > 0.0)?;
+                Some((t, (ray.position(t) - center) / radius))
+            }
+        }
+    }
+
+    fn centroid(&self) -> Point {
+        match *self {
+            Emitter::Triangle { p0, p1, p2 } => Point::new(
+                (p0.x() + p1.x() + p2.x()) / 3.0,
+                (p0.y() + p1.y() + p2.y()) / 3.0,
+                (p0.z() + p1.z() + p2.z()) / 3.0,
+            ),
+            Emitter::Sphere { center, .. } => center,
+        }
+    }
+}
+
+// ------------------------------------------------------
+/// Emissive geometry acting as one light, such as a mesh of triangles for a
+/// fixture. Samples are spread over the emitters in proportion to their
+/// area, so the whole surface is sampled uniformly.
+///
+/// A fixture that is part of the scene, visible to camera and bounce rays,
+/// is listed in `Scene::emitters`; one in `Scene::lights` only lights the
+/// scene and is seen by camera rays.
+#[derive(Clone, Debug)]
+pub struct MeshLight {
+    emitters: Vec<Emitter>,
+    emission: Emission,
+    /// Running sum of the emitter areas.
+    cdf: Vec<f64>,
+}
+
+impl MeshLight {
+    pub fn new(emitters: Vec<Emitter>, emission: impl Into<Emission>) -> Self {
+        let cdf: Vec<f64> = emitters
+            .iter()
+            .scan(0.0, |sum, e| {
+                *sum += e.area();
+                Some(*sum)
+            })
+            .collect();
+        if cdf.last().is_none_or(|&area| area <= 0.0) {
+            panic!("Mesh light needs a positive area.");
+        }
+
+        MeshLight {
+            emitters,
+            emission: emission.into(),
+            cdf,
+        }
+    }
+
+    pub fn emitters(&self) -> &[Emitter] {
+        &self.emitters
+    }
+
+    pub fn emission(&self) -> Emission {
+        self.emission
+    }
+
+    pub fn area(&self) -> f64 {
+        self.cdf[self.cdf.len() - 1]
+    }
+
+    /// Emitter picked for `u`, and `u` rescaled to `[0, 1)` within it.
+    fn pick(&self, u: f64) -> (usize, f64) {
+        let target = u * self.area();
+        let index = self
+            .cdf
+            .partition_point(|&sum| sum <= target)
+            .min(self.cdf.len() - 1);
+        let start = if index == 0 { 0.0 } else { self.cdf[index - 1] };
+        let width = self.cdf[index] - start;
+        (index, ((target - start) / width).clamp(0.0, 1.0))
+    }
+
+    /// Solid angle density of uniform area sampling seen along `direction`
+    /// from `distance` away, where the surface has normal `normal`; `None`
+    /// when looking at the back.
+    fn solid_angle_pdf(&self, direction: Vector, distance: f64, normal: Vector) -> Option<f64> {
+        let cos = -direction.dot(normal);
+        (cos > 0.0).then(|| distance * distance / (self.area() * cos))
+    }
+}
+
+impl Light for MeshLight {
+    fn intensity(&self) -> Color {
+        self.emission.radiance()
+    }
+
+    /// One sample towards the centroid of each emitter, weighted by its share
+    /// of the area.
+    fn samples(&self, point: Point) -> Vec<LightSample> {
+        self.emitters
+            .iter()
+            .map(|e| {
+                let to_light = e.centroid() - point;
+                LightSample {
+                    direction: to_light.normalize(),
+                    distance: to_light.magnitude(),
+                    intensity: self.emission.radiance() * (e.area() / self.area()),
+                }
+            })
+            .collect()
+    }
+
+    fn is_delta(&self) -> bool {
+        false
+    }
+
+    fn sample_li(&self, point: Point, u: f64, v: f64) -> Option<(LightSample, f64)> {
+        let (index, u) = self.pick(u);
+        let (position, normal) = self.emitters[index].sample_point(u, v);
+        let to_light = position - point;
+        let distance = to_light.magnitude();
+        let direction = to_light.normalize();
+        let pdf = self.solid_angle_pdf(direction, distance, normal)?;
+        Some((
+            LightSample {
+                direction,
+                distance,
+                intensity: self.emission.radiance(),
+            },
+            pdf,
+        ))
+    }
+
+    fn hit(&self, ray: &Ray) -> Option<LightHit> {
+        let (t, normal) = self
+            .emitters
+            .iter()
+            .filter_map(|e| e.intersect(ray))
+            .min_by(|a, b| a.0.total_cmp(&b.0))?;
+
+        let distance = t * ray.direction.magnitude();
+        let direction = ray.direction.normalize();
+        Some(match self.solid_angle_pdf(direction, distance, normal) {
+            Some(pdf) => LightHit {
+                distance,
+                radiance: self.emission.radiance(),
+                pdf,
+            },
+            // The back of an emitter still blocks the view.
+            None => LightHit {
+                distance,
+                radiance: Color::black(),
+                pdf: 0.0,
+            },
+        })
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampling::Rng;
+    use approx::assert_abs_diff_eq;
+
+    fn triangle(size: f64, y: f64) -> Emitter {
+        // Wound so it faces down.
+        Emitter::Triangle {
+            p0: Point::new(0.0, y, 0.0),
+            p1: Point::new(size, y, 0.0),
+            p2: Point::new(0.0, y, size),
+        }
+    }
+
+    #[test]
+    fn emitter_areas() {
+        assert_eq!(triangle(2.0, 0.0).area(), 2.0);
+        let sphere = Emitter::Sphere {
+            center: Point::new(0.0, 0.0, 0.0),
+            radius: 2.0,
+        };
+        assert_abs_diff_eq!(sphere.area(), 16.0 * PI);
+    }
+
+    #[test]
+    fn emitter_samples_lie_on_surface() {
+        let mut rng = Rng::new(15);
+        let tri = triangle(2.0, 3.0);
+        let sphere = Emitter::Sphere {
+            center: Point::new(1.0, 2.0, 3.0),
+            radius: 0.5,
+        };
+        for _ in 0..100 {
+            let (p, n) = tri.sample_point(rng.next_f64(), rng.next_f64());
+            assert_abs_diff_eq!(p.y(), 3.0);
+            assert!(p.x() >= -1e-12 && p.z() >= -1e-12 && p.x() + p.z() <= 2.0 + 1e-12);
+            assert_eq!(n, Vector::new(0.0, -1.0, 0.0));
+
+            let (p, n) = sphere.sample_point(rng.next_f64(), rng.next_f64());
+            assert_abs_diff_eq!(
+                (p - Point::new(1.0, 2.0, 3.0)).magnitude(),
+                0.5,
+                epsilon = 1e-12
+            );
+            assert_eq!(p, Point::new(1.0, 2.0, 3.0) + n * 0.5);
+        }
+    }
+
+    #[test]
+    fn emitter_intersections() {
+        let down = Vector::new(0.0, -1.0, 0.0);
+        let tri = triangle(2.0, 3.0);
+        let (t, n) = tri
+            .intersect(&Ray::new(Point::new(0.5, 5.0, 0.5), down))
+            .unwrap();
+        assert_eq!(t, 2.0);
+        assert_eq!(n, down);
+        assert!(tri
+            .intersect(&Ray::new(Point::new(1.5, 5.0, 1.5), down))
+            .is_none());
+        assert!(tri
+            .intersect(&Ray::new(Point::new(0.5, 1.0, 0.5), down))
+            .is_none());
+
+        let sphere = Emitter::Sphere {
+            center: Point::new(0.0, 0.0, 0.0),
+            radius: 1.0,
+        };
+        let (t, n) = sphere
+            .intersect(&Ray::new(Point::new(0.0, 5.0, 0.0), down))
+            .unwrap();
+        assert_eq!(t, 4.0);
+        assert_eq!(n, Vector::new(0.0, 1.0, 0.0));
+        // From inside, the far side.
+        let (t, _) = sphere
+            .intersect(&Ray::new(Point::new(0.0, 0.0, 0.0), down))
+            .unwrap();
+        assert_eq!(t, 1.0);
+    }
+
+    #[test]
+    fn mesh_light_samples_proportional_to_area() {
+        let light = MeshLight::new(
+            vec![triangle(1.0, 2.0), triangle(3.0, 2.0)],
+            Emission::new(Color::white(), 2.0),
+        );
+        assert_eq!(light.area(), 5.0);
+        assert_eq!(light.intensity(), Color::new(2.0, 2.0, 2.0));
+
+        let mut rng = Rng::new(16);
+        let n = 10_000;
+        let point = Point::new(0.2, 0.0, 0.2);
+        let small = (0..n)
+            .filter_map(|_| light.sample_li(point, rng.next_f64(), rng.next_f64()))
+            .filter(|(s, _)| {
+                let p = point + s.direction * s.distance;
+                p.x() + p.z() <= 1.0
+            })
+            .count();
+        // The small triangle holds 0.5 of the 5 units of area, and overlaps
+        // a ninth of the large one's.
+        let expected = (0.5 + 4.5 / 9.0) / 5.0;
+        assert!((small as f64 / n as f64 - expected).abs() < 0.02);
+    }
+
+    #[test]
+    fn mesh_light_hit_matches_sample() {
+        let light = MeshLight::new(
+            vec![
+                triangle(1.0, 2.0),
+                Emitter::Sphere {
+                    center: Point::new(3.0, 2.0, 0.0),
+                    radius: 0.5,
+                },
+            ],
+            Color::white(),
+        );
+        let mut rng = Rng::new(17);
+        let point = Point::new(1.0, 0.0, 0.5);
+        let mut checked = 0;
+        for _ in 0..100 {
+            if let Some((sample, pdf)) = light.sample_li(point, rng.next_f64(), rng.next_f64()) {
+                let hit = light.hit(&Ray::new(point, sample.direction)).unwrap();
+                // Samples on the back of the sphere are rejected.
+                if (hit.distance - sample.distance).abs() < 1e-9 {
+                    assert_abs_diff_eq!(hit.pdf, pdf, epsilon = 1e-9);
+                    assert_eq!(hit.radiance, Color::white());
+                    checked += 1;
+                }
+            }
+        }
+        assert!(checked > 30);
+    }
+
+    #[test]
+    fn mesh_light_back_face_is_dark() {
+        let light = MeshLight::new(vec![triangle(1.0, 2.0)], Color::white());
+        let above = Point::new(0.2, 4.0, 0.2);
+        assert!(light.sample_li(above, 0.3, 0.3).is_none());
+        let hit = light
+            .hit(&Ray::new(above, Vector::new(0.0, -1.0, 0.0)))
+            .unwrap();
+        assert_eq!(hit.radiance, Color::black());
+    }
+}
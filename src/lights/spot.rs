@@ -0,0 +1,119 @@
+use crate::lights::{Light, LightSample};
+use crate::primitives::{Color, Point, Vector};
+
+// ------------------------------------------------------
+/// Point light restricted to a cone. Full intensity inside the inner angle,
+/// fading smoothly to nothing at the outer angle.
+#[derive(Clone, Copy, Debug)]
+pub struct SpotLight {
+    position: Point,
+    direction: Vector,
+    intensity: Color,
+    cos_inner: f64,
+    cos_outer: f64,
+}
+
+impl SpotLight {
+    /// Cone half-angles are in radians; `inner` is clamped to `outer`.
+    pub fn new(
+        position: Point,
+        direction: Vector,
+        intensity: Color,
+        inner: f64,
+        outer: f64,
+    ) -> Self {
+        SpotLight {
+            position,
+            direction: direction.normalize(),
+            intensity,
+            cos_inner: inner.min(outer).cos(),
+            cos_outer: outer.cos(),
+        }
+    }
+
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    pub fn direction(&self) -> Vector {
+        self.direction
+    }
+
+    /// Fraction of the intensity reaching `point`, in `[0, 1]`.
+    pub fn falloff(&self, point: Point) -> f64 {
+        let cos = (point - self.position).normalize().dot(self.direction);
+        if cos >= self.cos_inner {
+            return 1.0;
+        }
+        if cos <= self.cos_outer {
+            return 0.0;
+        }
+        let t = (cos - self.cos_outer) / (self.cos_inner - self.cos_outer);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Light for SpotLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, point: Point) -> Vec<LightSample> {
+        let to_light = self.position - point;
+        vec![LightSample {
+            direction: to_light.normalize(),
+            distance: to_light.magnitude(),
+            intensity: self.intensity * self.falloff(point),
+        }]
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Tuple;
+    use std::f64::consts::PI;
+
+    fn spot() -> SpotLight {
+        SpotLight::new(
+            Point::new(0.0, 10.0, 0.0),
+            Vector::new(0.0, -1.0, 0.0),
+            Color::white(),
+            PI / 8.0,
+            PI / 4.0,
+        )
+    }
+
+    #[test]
+    fn spot_light_inside_inner_cone() {
+        let light = spot();
+        assert_eq!(light.falloff(Point::new(0.0, 0.0, 0.0)), 1.0);
+        let samples = light.samples(Point::new(1.0, 0.0, 0.0));
+        assert_eq!(samples[0].intensity, Color::white());
+        assert_eq!(samples[0].distance, 101.0f64.sqrt());
+    }
+
+    #[test]
+    fn spot_light_outside_outer_cone() {
+        let light = spot();
+        assert_eq!(light.falloff(Point::new(20.0, 0.0, 0.0)), 0.0);
+        assert_eq!(light.falloff(Point::new(0.0, 20.0, 0.0)), 0.0);
+        let samples = light.samples(Point::new(20.0, 0.0, 0.0));
+        assert_eq!(samples[0].intensity, Color::black());
+    }
+
+    #[test]
+    fn spot_light_fades_between_cones() {
+        let light = spot();
+        let mut last = 1.0;
+        for i in 1..10 {
+            // Angles from just past the inner cone to just before the outer one.
+            let angle = PI / 8.0 + (PI / 8.0) * i as f64 / 10.0;
+            let f = light.falloff(Point::new(10.0 * angle.tan(), 0.0, 0.0));
+            assert!(f > 0.0 && f < 1.0);
+            assert!(f < last);
+            last = f;
+        }
+    }
+}
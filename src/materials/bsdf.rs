@@ -0,0 +1,156 @@
+use std::f64::consts::FRAC_1_PI;
+
+#[cfg(feature = "spectral")]
+use crate::materials::Dielectric;
+use crate::materials::Subsurface;
+use crate::primitives::{Color, Vector};
+use crate::sampling::cosine_hemisphere;
+
+// ------------------------------------------------------
+/// Direction drawn from a BSDF.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BsdfSample {
+    /// Unit direction towards the light.
+    pub direction: Vector,
+    /// `f * cos / pdf`, the factor the path throughput is multiplied by.
+    pub weight: Color,
+    pub pdf: f64,
+}
+
+/// Scattering at a surface point. `wo` points towards the viewer, `wi`
+/// towards the light, both away from the surface and normalized; `normal`
+/// is the unit shading normal on the viewer's side.
+pub trait Bsdf {
+    /// Fraction of the light arriving from `wi` scattered towards `wo`.
+    fn eval(&self, wo: Vector, wi: Vector, normal: Vector) -> Color;
+
+    /// Solid angle density with which `sample` picks `wi`.
+    fn pdf(&self, wo: Vector, wi: Vector, normal: Vector) -> f64;
+
+    /// Draws a direction for the unit square sample `(u, v)`, or `None` if
+    /// the sample does not leave through the upper hemisphere.
+    fn sample(&self, wo: Vector, normal: Vector, u: f64, v: f64) -> Option<BsdfSample>;
+
+    /// Subsurface scattering below the surface, for integrators that trace
+    /// where the light leaves it.
+    fn subsurface(&self) -> Option<&Subsurface> {
+        None
+    }
+
+    /// Smooth dielectric interface, for integrators that refract through it
+    /// at the wavelength of each path.
+    #[cfg(feature = "spectral")]
+    fn dielectric(&self) -> Option<&Dielectric> {
+        None
+    }
+}
+
+// ------------------------------------------------------
+/// Ideal diffuse reflection.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lambertian {
+    albedo: Color,
+}
+
+impl Lambertian {
+    pub fn new(albedo: Color) -> Self {
+        Lambertian { albedo }
+    }
+
+    pub fn albedo(&self) -> Color {
+        self.albedo
+    }
+}
+
+impl Bsdf for Lambertian {
+    fn eval(&self, wo: Vector, wi: Vector, normal: Vector) -> Color {
+        if wo.dot(normal) <= 0.0 || wi.dot(normal) <= 0.0 {
+            return Color::black();
+        }
+        self.albedo * FRAC_1_PI
+    }
+
+    fn pdf(&self, wo: Vector, wi: Vector, normal: Vector) -> f64 {
+        if wo.dot(normal) <= 0.0 {
+            return 0.0;
+        }
+        wi.dot(normal).max(0.0) * FRAC_1_PI
+    }
+
+    fn sample(&self, wo: Vector, normal: Vector, u: f64, v: f64) -> Option<BsdfSample> {
+        if wo.dot(normal) <= 0.0 {
+            return None;
+        }
+
+        let direction = cosine_hemisphere(normal, u, v);
+        Some(BsdfSample {
+            direction,
+            weight: self.albedo,
+            pdf: self.pdf(wo, direction, normal),
+        })
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::primitives::Tuple;
+    use crate::sampling::Rng;
+
+    pub(crate) fn normal() -> Vector {
+        Vector::new(0.0, 0.0, 1.0)
+    }
+
+    /// Outgoing direction `cos` away from the normal.
+    pub(crate) fn outgoing(cos: f64) -> Vector {
+        Vector::new((1.0 - cos * cos).sqrt(), 0.0, cos)
+    }
+
+    /// Monte Carlo estimate of the directional albedo, the fraction of
+    /// uniform incoming light reflected towards `wo` (white furnace test).
+    pub(crate) fn albedo(bsdf: &impl Bsdf, wo: Vector, n: usize) -> Color {
+        let mut rng = Rng::new(11);
+        let sum = (0..n).fold(Color::black(), |acc, _| {
+            match bsdf.sample(wo, normal(), rng.next_f64(), rng.next_f64()) {
+                Some(s) => acc + s.weight,
+                None => acc,
+            }
+        });
+        sum * (1.0 / n as f64)
+    }
+
+    /// Checks that sampled weights and densities agree with `eval` and
+    /// `pdf`.
+    pub(crate) fn assert_consistent(bsdf: &impl Bsdf, wo: Vector) {
+        let mut rng = Rng::new(12);
+        for _ in 0..200 {
+            if let Some(s) = bsdf.sample(wo, normal(), rng.next_f64(), rng.next_f64()) {
+                let pdf = bsdf.pdf(wo, s.direction, normal());
+                approx::assert_relative_eq!(s.pdf, pdf, max_relative = 1e-6);
+                let expected =
+                    bsdf.eval(wo, s.direction, normal()) * (s.direction.dot(normal()) / pdf);
+                approx::assert_relative_eq!(s.weight, expected, max_relative = 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn lambertian_eval() {
+        let bsdf = Lambertian::new(Color::new(0.5, 0.25, 1.0));
+        let wo = outgoing(0.8);
+        let res = bsdf.eval(wo, outgoing(0.3), normal());
+        assert_eq!(res, Color::new(0.5, 0.25, 1.0) * FRAC_1_PI);
+        assert_eq!(bsdf.eval(wo, -outgoing(0.3), normal()), Color::black());
+        assert_consistent(&bsdf, wo);
+    }
+
+    #[test]
+    fn lambertian_white_furnace() {
+        let bsdf = Lambertian::new(Color::white());
+        for cos in [1.0, 0.5, 0.1] {
+            assert_eq!(albedo(&bsdf, outgoing(cos), 1_000), Color::white());
+        }
+        assert!(bsdf.sample(-outgoing(0.5), normal(), 0.3, 0.3).is_none());
+    }
+}
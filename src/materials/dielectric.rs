@@ -0,0 +1,108 @@
+use crate::materials::{Bsdf, BsdfSample};
+use crate::optics::fresnel_dielectric;
+use crate::primitives::{Color, Vector};
+use crate::spectral::Ior;
+
+// ------------------------------------------------------
+/// Smooth interface of a clear dielectric such as glass, whose refractive
+/// index may vary with the wavelength. Light leaves it only along the ideal
+/// reflected and refracted directions, which integrators follow with
+/// `scatter`; as a `Bsdf` it is black.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dielectric {
+    ior: Ior,
+}
+
+impl Dielectric {
+    pub fn new(ior: Ior) -> Self {
+        Dielectric { ior }
+    }
+
+    pub fn ior(&self) -> Ior {
+        self.ior
+    }
+
+    /// Direction light of wavelength `lambda` arriving from `wo` leaves in:
+    /// reflected when `u` falls below the Fresnel reflectance, refracted
+    /// otherwise. `normal` is the unit normal on the outside of the
+    /// interface, whichever side `wo` is on.
+    pub fn scatter(&self, wo: Vector, normal: Vector, lambda: f64, u: f64) -> Vector {
+        let n = self.ior.at(lambda);
+        let (normal, n1, n2) = if wo.dot(normal) >= 0.0 {
+            (normal, 1.0, n)
+        } else {
+            (-normal, n, 1.0)
+        };
+
+        let incident = -wo;
+        match incident.refract(normal, n1 / n2) {
+            Some(refracted) if u >= fresnel_dielectric(wo.dot(normal), n1, n2) => refracted,
+            _ => incident.reflect(normal),
+        }
+    }
+}
+
+impl Bsdf for Dielectric {
+    fn eval(&self, _wo: Vector, _wi: Vector, _normal: Vector) -> Color {
+        Color::black()
+    }
+
+    fn pdf(&self, _wo: Vector, _wi: Vector, _normal: Vector) -> f64 {
+        0.0
+    }
+
+    fn sample(&self, _wo: Vector, _normal: Vector, _u: f64, _v: f64) -> Option<BsdfSample> {
+        None
+    }
+
+    fn dielectric(&self) -> Option<&Dielectric> {
+        Some(self)
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::bsdf::tests::{normal, outgoing};
+    use crate::primitives::Tuple;
+
+    #[test]
+    fn dielectric_refracts_by_snells_law() {
+        let glass = Dielectric::new(Ior::Constant(1.5));
+        let wo = outgoing(0.5);
+        let wi = glass.scatter(wo, normal(), 550.0, 1.0);
+
+        assert!(wi.dot(normal()) < 0.0);
+        approx::assert_abs_diff_eq!(wi.magnitude(), 1.0, epsilon = 1e-9);
+        let sin_i = (wo.x() * wo.x() + wo.y() * wo.y()).sqrt();
+        let sin_t = (wi.x() * wi.x() + wi.y() * wi.y()).sqrt();
+        approx::assert_abs_diff_eq!(sin_i, 1.5 * sin_t, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn dielectric_reflects_with_fresnel_probability() {
+        let glass = Dielectric::new(Ior::Constant(1.5));
+        let wo = outgoing(1.0);
+        assert_eq!(glass.scatter(wo, normal(), 550.0, 0.03), wo);
+        assert!(glass.scatter(wo, normal(), 550.0, 0.05).dot(normal()) < 0.0);
+
+        // Grazing light inside the glass is totally reflected.
+        let inside = -outgoing(0.1);
+        let wi = glass.scatter(inside, normal(), 550.0, 0.99);
+        assert_eq!(wi, Vector::new(-inside.x(), -inside.y(), inside.z()));
+    }
+
+    #[test]
+    fn dielectric_disperses_by_wavelength() {
+        let glass = Dielectric::new(Ior::bk7());
+        let wo = outgoing(0.5);
+        let blue = glass.scatter(wo, normal(), 450.0, 1.0);
+        let red = glass.scatter(wo, normal(), 650.0, 1.0);
+
+        // Blue light bends more, closer to the inward normal.
+        assert!(blue.dot(-normal()) > red.dot(-normal()));
+        assert_eq!(Bsdf::eval(&glass, wo, blue, normal()), Color::black());
+        assert!(glass.sample(wo, normal(), 0.5, 0.5).is_none());
+    }
+}
@@ -0,0 +1,71 @@
+use crate::primitives::Color;
+
+// ------------------------------------------------------
+/// Light given off by a surface: a color scaled by a strength, so fixtures
+/// can be brightened without changing their tint.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Emission {
+    color: Color,
+    strength: f64,
+}
+
+impl Emission {
+    pub fn new(color: Color, strength: f64) -> Self {
+        Emission {
+            color,
+            strength: strength.max(0.0),
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn strength(&self) -> f64 {
+        self.strength
+    }
+
+    /// Emitted radiance.
+    pub fn radiance(&self) -> Color {
+        self.color * self.strength
+    }
+
+    pub fn is_black(&self) -> bool {
+        self.radiance().is_black()
+    }
+}
+
+impl Default for Emission {
+    fn default() -> Self {
+        Emission::new(Color::black(), 0.0)
+    }
+}
+
+impl From<Color> for Emission {
+    fn from(color: Color) -> Self {
+        Emission::new(color, 1.0)
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emission_radiance() {
+        let e = Emission::new(Color::new(1.0, 0.5, 0.25), 4.0);
+        assert_eq!(e.color(), Color::new(1.0, 0.5, 0.25));
+        assert_eq!(e.strength(), 4.0);
+        assert_eq!(e.radiance(), Color::new(4.0, 2.0, 1.0));
+        assert!(!e.is_black());
+    }
+
+    #[test]
+    fn emission_default_is_black() {
+        assert!(Emission::default().is_black());
+        assert!(Emission::new(Color::white(), -1.0).is_black());
+        assert_eq!(Emission::from(Color::red()).radiance(), Color::red());
+        assert!(!Emission::new(Color::white(), 1e-7).is_black());
+    }
+}
@@ -0,0 +1,268 @@
+use std::f64::consts::PI;
+
+use crate::materials::{Bsdf, BsdfSample};
+use crate::optics::{fresnel_conductor, fresnel_dielectric, schlick_color};
+use crate::primitives::{Color, Vector};
+use crate::sampling::orthonormal_basis;
+
+// ------------------------------------------------------
+/// Distribution of microfacet normals; `alpha` is the roughness parameter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Distribution {
+    /// GGX / Trowbridge-Reitz, with long tails.
+    Ggx {
+        alpha: f64,
+    },
+    Beckmann {
+        alpha: f64,
+    },
+}
+
+impl Distribution {
+    pub fn alpha(&self) -> f64 {
+        match *self {
+            Distribution::Ggx { alpha } | Distribution::Beckmann { alpha } => alpha,
+        }
+    }
+
+    /// Density of microfacet normals at `cos_h` from the macro normal.
+    pub fn d(&self, cos_h: f64) -> f64 {
+        if cos_h <= 0.0 {
+            return 0.0;
+        }
+
+        let a2 = self.alpha() * self.alpha();
+        let cos2 = cos_h * cos_h;
+        let tan2 = (1.0 - cos2) / cos2;
+        match self {
+            Distribution::Ggx { .. } => a2 / (PI * cos2 * cos2 * (a2 + tan2).powi(2)),
+            Distribution::Beckmann { .. } => (-tan2 / a2).exp() / (PI * a2 * cos2 * cos2),
+        }
+    }
+
+    /// Smith's auxiliary function for a direction `cos` from the normal.
+    pub fn lambda(&self, cos: f64) -> f64 {
+        let cos2 = cos * cos;
+        let tan2 = (1.0 - cos2).max(0.0) / cos2;
+        if tan2 == 0.0 {
+            return 0.0;
+        }
+
+        let alpha = self.alpha();
+        match self {
+            Distribution::Ggx { .. } => ((1.0 + alpha * alpha * tan2).sqrt() - 1.0) / 2.0,
+            Distribution::Beckmann { .. } => {
+                let a = 1.0 / (alpha * tan2.sqrt());
+                if a >= 1.6 {
+                    0.0
+                } else {
+                    (1.0 - 1.259 * a + 0.396 * a * a) / (3.535 * a + 2.181 * a * a)
+                }
+            }
+        }
+    }
+
+    /// Height-correlated masking-shadowing term.
+    pub fn g(&self, cos_o: f64, cos_i: f64) -> f64 {
+        1.0 / (1.0 + self.lambda(cos_o) + self.lambda(cos_i))
+    }
+
+    /// Cosine of a microfacet normal drawn with density `d(cos) * cos`.
+    pub fn sample_cos(&self, u: f64) -> f64 {
+        let a2 = self.alpha() * self.alpha();
+        let tan2 = match self {
+            Distribution::Ggx { .. } => a2 * u / (1.0 - u).max(1.0e-12),
+            Distribution::Beckmann { .. } => -a2 * (1.0 - u).max(1.0e-12).ln(),
+        };
+        1.0 / (1.0 + tan2).sqrt()
+    }
+}
+
+// ------------------------------------------------------
+/// Reflectance of the microfacets themselves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fresnel {
+    /// Everything is reflected.
+    NoOp,
+    /// Dielectric with relative index of refraction `eta`.
+    Dielectric { eta: f64 },
+    /// Conductor with complex index `eta + i k` per channel.
+    Conductor { eta: Color, k: Color },
+    /// Schlick's approximation from the reflectance at normal incidence.
+    Schlick { f0: Color },
+}
+
+impl Fresnel {
+    pub fn eval(&self, cos: f64) -> Color {
+        match *self {
+            Fresnel::NoOp => Color::white(),
+            Fresnel::Dielectric { eta } => {
+                let f = fresnel_dielectric(cos, 1.0, eta);
+                Color::new(f, f, f)
+            }
+            Fresnel::Conductor { eta, k } => fresnel_conductor(cos, eta, k),
+            Fresnel::Schlick { f0 } => schlick_color(cos, f0),
+        }
+    }
+}
+
+// ------------------------------------------------------
+/// Torrance-Sparrow glossy reflection off a rough surface.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MicrofacetReflection {
+    distribution: Distribution,
+    fresnel: Fresnel,
+}
+
+impl MicrofacetReflection {
+    pub fn new(distribution: Distribution, fresnel: Fresnel) -> Self {
+        MicrofacetReflection {
+            distribution,
+            fresnel,
+        }
+    }
+
+    pub fn distribution(&self) -> Distribution {
+        self.distribution
+    }
+
+    pub fn fresnel(&self) -> Fresnel {
+        self.fresnel
+    }
+}
+
+impl Bsdf for MicrofacetReflection {
+    fn eval(&self, wo: Vector, wi: Vector, normal: Vector) -> Color {
+        let cos_o = wo.dot(normal);
+        let cos_i = wi.dot(normal);
+        if cos_o <= 0.0 || cos_i <= 0.0 {
+            return Color::black();
+        }
+
+        let h = (wo + wi).normalize();
+        let d = self.distribution.d(h.dot(normal));
+        let g = self.distribution.g(cos_o, cos_i);
+        self.fresnel.eval(wi.dot(h)) * (d * g / (4.0 * cos_o * cos_i))
+    }
+
+    fn pdf(&self, wo: Vector, wi: Vector, normal: Vector) -> f64 {
+        if wo.dot(normal) <= 0.0 || wi.dot(normal) <= 0.0 {
+            return 0.0;
+        }
+
+        let h = (wo + wi).normalize();
+        let cos_h = h.dot(normal);
+        self.distribution.d(cos_h) * cos_h / (4.0 * wo.dot(h))
+    }
+
+    fn sample(&self, wo: Vector, normal: Vector, u: f64, v: f64) -> Option<BsdfSample> {
+        if wo.dot(normal) <= 0.0 {
+            return None;
+        }
+
+        let cos_h = self.distribution.sample_cos(u);
+        let sin_h = (1.0 - cos_h * cos_h).max(0.0).sqrt();
+        let phi = 2.0 * PI * v;
+        let (tangent, bitangent) = orthonormal_basis(normal);
+        let h = tangent * (sin_h * phi.cos()) + bitangent * (sin_h * phi.sin()) + normal * cos_h;
+
+        let direction = (-wo).reflect(h);
+        let cos_i = direction.dot(normal);
+        if cos_i <= 0.0 {
+            return None;
+        }
+
+        let pdf = self.pdf(wo, direction, normal);
+        Some(BsdfSample {
+            direction,
+            weight: self.eval(wo, direction, normal) * (cos_i / pdf),
+            pdf,
+        })
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::bsdf::tests::{albedo, assert_consistent, outgoing};
+    use crate::sampling::Rng;
+    use approx::assert_abs_diff_eq;
+
+    fn distributions(alpha: f64) -> [Distribution; 2] {
+        [
+            Distribution::Ggx { alpha },
+            Distribution::Beckmann { alpha },
+        ]
+    }
+
+    #[test]
+    fn distribution_is_normalized() {
+        // The projected microfacet area equals the macro surface area:
+        // integral of D(h) cos(h) over the hemisphere is one.
+        for alpha in [0.1, 0.5, 1.0] {
+            for dist in distributions(alpha) {
+                let n = 200_000;
+                let mut rng = Rng::new(13);
+                let sum: f64 = (0..n)
+                    .map(|_| {
+                        let cos = rng.next_f64();
+                        dist.d(cos) * cos * 2.0 * PI
+                    })
+                    .sum();
+                assert_abs_diff_eq!(sum / n as f64, 1.0, epsilon = 0.05);
+            }
+        }
+    }
+
+    #[test]
+    fn distribution_masking() {
+        for dist in distributions(0.4) {
+            assert_eq!(dist.g(1.0, 1.0), 1.0);
+            assert!(dist.g(0.2, 1.0) < dist.g(0.6, 1.0));
+            assert!(dist.g(0.2, 0.2) > 0.0);
+        }
+    }
+
+    #[test]
+    fn microfacet_sampling_is_consistent() {
+        for dist in distributions(0.3) {
+            let bsdf = MicrofacetReflection::new(
+                dist,
+                Fresnel::Conductor {
+                    eta: Color::new(0.2, 0.9, 1.1),
+                    k: Color::new(3.9, 2.4, 2.2),
+                },
+            );
+            for cos in [1.0, 0.6, 0.2] {
+                assert_consistent(&bsdf, outgoing(cos));
+            }
+        }
+    }
+
+    #[test]
+    fn microfacet_white_furnace() {
+        for alpha in [0.05, 0.3, 0.8] {
+            for dist in distributions(alpha) {
+                let bsdf = MicrofacetReflection::new(dist, Fresnel::NoOp);
+                for cos in [1.0, 0.5, 0.2] {
+                    let res = albedo(&bsdf, outgoing(cos), 20_000);
+                    // Single scattering loses energy on rough surfaces but
+                    // never creates any.
+                    assert!(res.r() <= 1.01, "{:?} {} {:?}", dist, cos, res);
+                    if alpha < 0.1 && cos > 0.4 {
+                        assert!(res.r() > 0.95, "{:?} {} {:?}", dist, cos, res);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn microfacet_fresnel_scales_reflection() {
+        let dist = Distribution::Ggx { alpha: 0.05 };
+        let glass = MicrofacetReflection::new(dist, Fresnel::Dielectric { eta: 1.5 });
+        let res = albedo(&glass, outgoing(1.0), 5_000);
+        assert_abs_diff_eq!(res.r(), 0.04, epsilon = 0.005);
+    }
+}
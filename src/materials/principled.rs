@@ -0,0 +1,177 @@
+use std::f64::consts::FRAC_1_PI;
+
+use crate::materials::{Bsdf, BsdfSample, Distribution, Fresnel, MicrofacetReflection};
+use crate::optics::schlick;
+use crate::primitives::{Color, Vector};
+use crate::sampling::cosine_hemisphere;
+
+/// Reflectance of common dielectrics (index 1.5) at normal incidence.
+const DIELECTRIC_F0: f64 = 0.04;
+
+// ------------------------------------------------------
+/// Metallic-roughness material as authored in most content tools: a GGX
+/// specular lobe over a diffuse base. Metals tint the specular lobe with the
+/// base color and have no diffuse part.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Principled {
+    base_color: Color,
+    metallic: f64,
+    roughness: f64,
+}
+
+impl Principled {
+    /// `metallic` and `roughness` are clamped to `[0, 1]`.
+    pub fn new(base_color: Color, metallic: f64, roughness: f64) -> Self {
+        Principled {
+            base_color,
+            metallic: metallic.clamp(0.0, 1.0),
+            roughness: roughness.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn base_color(&self) -> Color {
+        self.base_color
+    }
+
+    pub fn metallic(&self) -> f64 {
+        self.metallic
+    }
+
+    pub fn roughness(&self) -> f64 {
+        self.roughness
+    }
+
+    fn specular(&self) -> MicrofacetReflection {
+        let dielectric = Color::new(DIELECTRIC_F0, DIELECTRIC_F0, DIELECTRIC_F0);
+        let f0 = dielectric * (1.0 - self.metallic) + self.base_color * self.metallic;
+        // Perceptually linear roughness; keep a sliver of it so the lobe
+        // stays finite.
+        let alpha = (self.roughness * self.roughness).max(1.0e-3);
+        MicrofacetReflection::new(Distribution::Ggx { alpha }, Fresnel::Schlick { f0 })
+    }
+
+    /// Light that is not reflected by the dielectric coating at either end
+    /// reaches and leaves the diffuse base.
+    fn diffuse(&self, cos_o: f64, cos_i: f64) -> Color {
+        let n2 = (1.0 + DIELECTRIC_F0.sqrt()) / (1.0 - DIELECTRIC_F0.sqrt());
+        let transmitted = (1.0 - schlick(cos_o, 1.0, n2)) * (1.0 - schlick(cos_i, 1.0, n2));
+        self.base_color * ((1.0 - self.metallic) * transmitted * FRAC_1_PI)
+    }
+
+    /// Probability of sampling the specular lobe.
+    fn specular_probability(&self) -> f64 {
+        0.5 + 0.5 * self.metallic
+    }
+}
+
+impl Bsdf for Principled {
+    fn eval(&self, wo: Vector, wi: Vector, normal: Vector) -> Color {
+        let cos_o = wo.dot(normal);
+        let cos_i = wi.dot(normal);
+        if cos_o <= 0.0 || cos_i <= 0.0 {
+            return Color::black();
+        }
+        self.specular().eval(wo, wi, normal) + self.diffuse(cos_o, cos_i)
+    }
+
+    fn pdf(&self, wo: Vector, wi: Vector, normal: Vector) -> f64 {
+        if wo.dot(normal) <= 0.0 {
+            return 0.0;
+        }
+
+        let p = self.specular_probability();
+        let diffuse = wi.dot(normal).max(0.0) * FRAC_1_PI;
+        p * self.specular().pdf(wo, wi, normal) + (1.0 - p) * diffuse
+    }
+
+    fn sample(&self, wo: Vector, normal: Vector, u: f64, v: f64) -> Option<BsdfSample> {
+        if wo.dot(normal) <= 0.0 {
+            return None;
+        }
+
+        let p = self.specular_probability();
+        let direction = if u < p {
+            self.specular().sample(wo, normal, u / p, v)?.direction
+        } else {
+            cosine_hemisphere(normal, (u - p) / (1.0 - p), v)
+        };
+
+        let pdf = self.pdf(wo, direction, normal);
+        let cos_i = direction.dot(normal);
+        if pdf <= 0.0 || cos_i <= 0.0 {
+            return None;
+        }
+        Some(BsdfSample {
+            direction,
+            weight: self.eval(wo, direction, normal) * (cos_i / pdf),
+            pdf,
+        })
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::bsdf::tests::{albedo, assert_consistent, outgoing};
+    use crate::materials::Lambertian;
+
+    #[test]
+    fn principled_clamps_parameters() {
+        let m = Principled::new(Color::white(), 1.5, -0.2);
+        assert_eq!(m.metallic(), 1.0);
+        assert_eq!(m.roughness(), 0.0);
+        assert_eq!(m.base_color(), Color::white());
+    }
+
+    #[test]
+    fn principled_sampling_is_consistent() {
+        for (metallic, roughness) in [(0.0, 0.5), (1.0, 0.3), (0.4, 0.8)] {
+            let m = Principled::new(Color::new(0.8, 0.5, 0.2), metallic, roughness);
+            for cos in [1.0, 0.5, 0.15] {
+                assert_consistent(&m, outgoing(cos));
+            }
+        }
+    }
+
+    #[test]
+    fn principled_white_furnace() {
+        for metallic in [0.0, 0.5, 1.0] {
+            for roughness in [0.1, 0.5, 1.0] {
+                let m = Principled::new(Color::white(), metallic, roughness);
+                for cos in [1.0, 0.5, 0.2] {
+                    let res = albedo(&m, outgoing(cos), 5_000);
+                    assert!(
+                        res.r() <= 1.01,
+                        "metallic {} roughness {} cos {}: {:?}",
+                        metallic,
+                        roughness,
+                        cos,
+                        res
+                    );
+                    // Single scattering drops the light that rough facets
+                    // bounce into each other.
+                    assert!(res.r() > 0.25);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn principled_rough_dielectric_is_nearly_lambertian() {
+        let base = Color::new(0.6, 0.3, 0.1);
+        let m = Principled::new(base, 0.0, 1.0);
+        let res = albedo(&m, outgoing(1.0), 20_000);
+        let lambert = albedo(&Lambertian::new(base), outgoing(1.0), 1_000);
+        approx::assert_abs_diff_eq!(res, lambert, epsilon = 0.1);
+    }
+
+    #[test]
+    fn principled_metal_is_tinted() {
+        let gold = Color::new(1.0, 0.78, 0.34);
+        let m = Principled::new(gold, 1.0, 0.2);
+        let res = albedo(&m, outgoing(1.0), 20_000);
+        assert!(res.r() > res.g() && res.g() > res.b());
+        approx::assert_abs_diff_eq!(res, gold, epsilon = 0.08);
+    }
+}
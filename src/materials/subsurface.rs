@@ -0,0 +1,295 @@
+use std::f64::consts::PI;
+
+use crate::materials::{Bsdf, BsdfSample, Lambertian};
+use crate::primitives::{Color, Vector};
+
+// ------------------------------------------------------
+/// Translucent material such as skin, wax, marble or milk, modelled by the
+/// classical diffusion dipole (Jensen et al. 2001).
+///
+/// Light entering the surface reappears at some distance `r` from where it
+/// entered, with the density given by `profile`. Integrators that can probe
+/// the surface around a point, such as `PathTracer`, sample that distance
+/// with `sample_radius`; as a `Bsdf` the material gives the profile's total,
+/// the light eventually leaving the surface, as a diffuse reflection.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Subsurface {
+    /// Reduced single scattering albedo per channel.
+    alpha: [f64; 3],
+    mean_free_path: [f64; 3],
+    eta: f64,
+    diffuse: Lambertian,
+}
+
+impl Subsurface {
+    /// Material reflecting `albedo` in total, with light travelling
+    /// `mean_free_path` on average between interactions below the surface.
+    /// A zero mean free path gives a plain diffuse material.
+    pub fn new(albedo: Color, mean_free_path: Color) -> Self {
+        Subsurface::with_albedo(albedo, mean_free_path, 1.3)
+    }
+
+    /// Material with absorption `sigma_a` and reduced scattering `sigma_s`
+    /// coefficients per unit distance, as measured for real materials.
+    pub fn from_coefficients(sigma_a: Color, sigma_s: Color) -> Self {
+        let channels = [
+            (sigma_a.r(), sigma_s.r()),
+            (sigma_a.g(), sigma_s.g()),
+            (sigma_a.b(), sigma_s.b()),
+        ];
+        if channels
+            .iter()
+            .any(|&(a, s)| a < 0.0 || s < 0.0 || a + s <= 0.0)
+        {
+            panic!("Invalid subsurface coefficients.");
+        }
+
+        let eta = 1.3;
+        let alpha = channels.map(|(a, s)| s / (a + s));
+        let mean_free_path = channels.map(|(a, s)| 1.0 / (a + s));
+        Subsurface {
+            alpha,
+            mean_free_path,
+            eta,
+            diffuse: Lambertian::new(total(alpha, eta)),
+        }
+    }
+
+    /// Relative refractive index of the material, 1.3 by default; the
+    /// albedo is kept.
+    pub fn with_eta(self, eta: f64) -> Self {
+        if eta <= 0.0 {
+            panic!("Invalid refractive index.");
+        }
+        Subsurface::with_albedo(self.albedo(), self.mean_free_path(), eta)
+    }
+
+    fn with_albedo(albedo: Color, mean_free_path: Color, eta: f64) -> Self {
+        let albedo = [albedo.r(), albedo.g(), albedo.b()];
+        let mean_free_path = [mean_free_path.r(), mean_free_path.g(), mean_free_path.b()];
+        if albedo.iter().any(|a| !(0.0..=1.0).contains(a)) {
+            panic!("Invalid subsurface albedo.");
+        }
+        if mean_free_path.iter().any(|&l| l.is_nan() || l < 0.0) {
+            panic!("Invalid mean free path.");
+        }
+
+        let boundary = boundary(eta);
+        let alpha = albedo.map(|albedo| {
+            // The total reflectance grows with the reduced albedo.
+            let (mut lo, mut hi) = (0.0, 1.0);
+            for _ in 0..60 {
+                let mid = 0.5 * (lo + hi);
+                if total_reflectance(mid, boundary) < albedo {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            0.5 * (lo + hi)
+        });
+        Subsurface {
+            alpha,
+            mean_free_path,
+            eta,
+            diffuse: Lambertian::new(total(alpha, eta)),
+        }
+    }
+
+    /// Total diffuse reflectance.
+    pub fn albedo(&self) -> Color {
+        self.diffuse.albedo()
+    }
+
+    pub fn mean_free_path(&self) -> Color {
+        let [r, g, b] = self.mean_free_path;
+        Color::new(r, g, b)
+    }
+
+    pub fn eta(&self) -> f64 {
+        self.eta
+    }
+
+    /// Whether light travels below the surface in every channel. Materials
+    /// with a zero mean free path in some channel are shaded as their
+    /// diffuse reflection.
+    pub fn is_translucent(&self) -> bool {
+        self.mean_free_path.iter().all(|&l| l > 0.0)
+    }
+
+    /// Distance from the entry point beyond which `sample_radius` picks
+    /// less than a thousandth of its samples in every channel.
+    pub fn max_radius(&self) -> f64 {
+        (0..3)
+            .filter(|&i| self.mean_free_path[i] > 0.0)
+            .map(|i| 1000.0f64.ln() / sigma_tr(self.alpha[i], self.mean_free_path[i]))
+            .fold(0.0, f64::max)
+    }
+
+    /// Radiant exitance per unit area at distance `r` from where unit flux
+    /// entered. Integrates to `albedo` over the plane; channels with a zero
+    /// mean free path keep all their light at `r = 0`.
+    pub fn profile(&self, r: f64) -> Color {
+        let boundary = boundary(self.eta);
+        let channel = |i: usize| {
+            let (alpha, mfp) = (self.alpha[i], self.mean_free_path[i]);
+            if mfp == 0.0 {
+                return 0.0;
+            }
+            let sigma_tr = sigma_tr(alpha, mfp);
+            let z_r = mfp;
+            let z_v = z_r * (1.0 + 4.0 / 3.0 * boundary);
+            let pole = |z: f64| {
+                let d = (r * r + z * z).sqrt();
+                z * (sigma_tr * d + 1.0) * (-sigma_tr * d).exp() / (d * d * d)
+            };
+            alpha / (4.0 * PI) * (pole(z_r) + pole(z_v))
+        };
+        Color::new(channel(0), channel(1), channel(2))
+    }
+
+    /// Distance from the entry point for the unit square sample `(u, v)`,
+    /// with its density per unit area. `u` picks the channel whose falloff
+    /// is followed, `v` the distance along it; channels with a zero mean
+    /// free path always give `r = 0`.
+    pub fn sample_radius(&self, u: f64, v: f64) -> (f64, f64) {
+        let channel = ((u * 3.0) as usize).min(2);
+        let sigma_tr = sigma_tr(self.alpha[channel], self.mean_free_path[channel]);
+        let r = -(1.0 - v).ln() / sigma_tr;
+        (r, self.pdf_radius(r))
+    }
+
+    /// Density per unit area with which `sample_radius` picks `r`.
+    pub fn pdf_radius(&self, r: f64) -> f64 {
+        (0..3)
+            .filter(|&i| self.mean_free_path[i] > 0.0)
+            .map(|i| {
+                let sigma_tr = sigma_tr(self.alpha[i], self.mean_free_path[i]);
+                sigma_tr * (-sigma_tr * r).exp() / (2.0 * PI * r)
+            })
+            .sum::<f64>()
+            / 3.0
+    }
+}
+
+impl Bsdf for Subsurface {
+    fn eval(&self, wo: Vector, wi: Vector, normal: Vector) -> Color {
+        self.diffuse.eval(wo, wi, normal)
+    }
+
+    fn pdf(&self, wo: Vector, wi: Vector, normal: Vector) -> f64 {
+        self.diffuse.pdf(wo, wi, normal)
+    }
+
+    fn sample(&self, wo: Vector, normal: Vector, u: f64, v: f64) -> Option<BsdfSample> {
+        self.diffuse.sample(wo, normal, u, v)
+    }
+
+    fn subsurface(&self) -> Option<&Subsurface> {
+        Some(self)
+    }
+}
+
+/// Internal reflection parameter `A` for the relative index `eta`.
+fn boundary(eta: f64) -> f64 {
+    let fdr = -1.440 / (eta * eta) + 0.710 / eta + 0.668 + 0.0636 * eta;
+    (1.0 + fdr) / (1.0 - fdr)
+}
+
+/// Effective transport coefficient.
+fn sigma_tr(alpha: f64, mean_free_path: f64) -> f64 {
+    // Keep a trace of absorption so the profile stays finite.
+    (3.0 * (1.0 - alpha).max(1.0e-9)).sqrt() / mean_free_path
+}
+
+/// Integral of the dipole profile over the plane.
+fn total_reflectance(alpha: f64, boundary: f64) -> f64 {
+    let s = (3.0 * (1.0 - alpha)).sqrt();
+    0.5 * alpha * (1.0 + (-4.0 / 3.0 * boundary * s).exp()) * (-s).exp()
+}
+
+fn total(alpha: [f64; 3], eta: f64) -> Color {
+    let [r, g, b] = alpha.map(|alpha| total_reflectance(alpha, boundary(eta)));
+    Color::new(r, g, b)
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::bsdf::tests::{albedo, assert_consistent, outgoing};
+    use crate::sampling::Rng;
+    use approx::assert_abs_diff_eq;
+
+    fn skin() -> Subsurface {
+        // Measured by Jensen et al., per millimetre.
+        Subsurface::from_coefficients(Color::new(0.032, 0.17, 0.48), Color::new(0.74, 0.88, 1.01))
+    }
+
+    /// Monte Carlo integral of the profile over the plane.
+    fn integrate(material: &Subsurface, n: usize) -> Color {
+        let mut rng = Rng::new(9);
+        let sum = (0..n).fold(Color::black(), |acc, _| {
+            let (r, pdf) = material.sample_radius(rng.next_f64(), rng.next_f64());
+            acc + material.profile(r) * (1.0 / pdf)
+        });
+        sum * (1.0 / n as f64)
+    }
+
+    #[test]
+    fn subsurface_matches_albedo() {
+        let albedo = Color::new(0.8, 0.5, 0.2);
+        let material = Subsurface::new(albedo, Color::new(1.0, 0.5, 0.25));
+        assert_abs_diff_eq!(material.albedo(), albedo, epsilon = 1.0e-9);
+        assert_eq!(material.mean_free_path(), Color::new(1.0, 0.5, 0.25));
+
+        let res = integrate(&material, 50_000);
+        assert_abs_diff_eq!(res, albedo, epsilon = 0.02);
+    }
+
+    #[test]
+    fn subsurface_energy_is_bounded() {
+        let materials = [
+            skin(),
+            Subsurface::new(Color::new(0.99, 0.95, 0.9), Color::new(4.0, 2.0, 1.0)),
+            Subsurface::new(Color::white(), Color::white()).with_eta(1.5),
+        ];
+        for material in materials {
+            let albedo = material.albedo();
+            assert!(albedo.r() <= 1.0 && albedo.g() <= 1.0 && albedo.b() <= 1.0);
+
+            let res = integrate(&material, 20_000);
+            assert!(
+                res.r() < 1.05 && res.g() < 1.05 && res.b() < 1.05,
+                "{:?}",
+                res
+            );
+            for cos in [1.0, 0.5, 0.1] {
+                let reflected = self::albedo(&material, outgoing(cos), 1_000);
+                assert_abs_diff_eq!(reflected, albedo, epsilon = 1.0e-9);
+            }
+            assert_consistent(&material, outgoing(0.6));
+        }
+    }
+
+    #[test]
+    fn subsurface_skin_scatters_red_furthest() {
+        let material = skin();
+        let near = material.profile(0.1);
+        let far = material.profile(2.0);
+        assert!(far.r() / near.r() > far.g() / near.g());
+        assert!(far.g() / near.g() > far.b() / near.b());
+    }
+
+    #[test]
+    #[should_panic]
+    fn subsurface_invalid_albedo() {
+        Subsurface::new(Color::new(1.2, 0.5, 0.5), Color::white());
+    }
+
+    #[test]
+    #[should_panic]
+    fn subsurface_invalid_mean_free_path() {
+        Subsurface::new(Color::white(), Color::new(1.0, -1.0, 1.0));
+    }
+}
@@ -0,0 +1,315 @@
+use crate::media::{clip, HenyeyGreenstein, Medium, MediumSample};
+use crate::primitives::{Color, Point, Ray, Tuple};
+use crate::sampling::Rng;
+
+// ------------------------------------------------------
+/// Densities on the vertices of a regular grid spanning the unit cube,
+/// stored with `x` varying fastest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DensityGrid {
+    nx: usize,
+    ny: usize,
+    nz: usize,
+    values: Vec<f64>,
+    max: f64,
+}
+
+impl DensityGrid {
+    pub fn new(nx: usize, ny: usize, nz: usize, values: Vec<f64>) -> Self {
+        if nx == 0 || ny == 0 || nz == 0 || values.len() != nx * ny * nz {
+            panic!("Invalid grid dimensions.");
+        }
+        if values.iter().any(|&value| value.is_nan() || value < 0.0) {
+            panic!("Invalid grid densities.");
+        }
+
+        let max = values.iter().copied().fold(0.0, f64::max);
+        DensityGrid {
+            nx,
+            ny,
+            nz,
+            values,
+            max,
+        }
+    }
+
+    /// Grid sampling `density` at its vertices.
+    pub fn from_fn(nx: usize, ny: usize, nz: usize, density: impl Fn(Point) -> f64) -> Self {
+        let coord = |i: usize, n: usize| {
+            if n > 1 {
+                i as f64 / (n - 1) as f64
+            } else {
+                0.5
+            }
+        };
+        let mut values = Vec::with_capacity(nx * ny * nz);
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    values.push(density(Point::new(
+                        coord(x, nx),
+                        coord(y, ny),
+                        coord(z, nz),
+                    )));
+                }
+            }
+        }
+        DensityGrid::new(nx, ny, nz, values)
+    }
+
+    /// Largest density in the grid.
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Trilinearly interpolated density at `point` in the unit cube;
+    /// coordinates outside are clamped.
+    pub fn density(&self, point: Point) -> f64 {
+        let axis = |p: f64, n: usize| {
+            let x = p.clamp(0.0, 1.0) * (n - 1) as f64;
+            let i = (x.floor() as usize).min(n.saturating_sub(2));
+            (i, (i + 1).min(n - 1), x - i as f64)
+        };
+        let (x0, x1, fx) = axis(point.x(), self.nx);
+        let (y0, y1, fy) = axis(point.y(), self.ny);
+        let (z0, z1, fz) = axis(point.z(), self.nz);
+        let at = |x: usize, y: usize, z: usize| self.values[x + self.nx * (y + self.ny * z)];
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+        let plane = |z: usize| {
+            lerp(
+                lerp(at(x0, y0, z), at(x1, y0, z), fx),
+                lerp(at(x0, y1, z), at(x1, y1, z), fx),
+                fy,
+            )
+        };
+        lerp(plane(z0), plane(z1), fz)
+    }
+}
+
+// ------------------------------------------------------
+/// Medium whose density varies through an axis aligned box, such as a
+/// cloud or a smoke plume. Collisions are found by delta tracking and
+/// transmittance is estimated by ratio tracking, both against the grid's
+/// maximum density.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heterogeneous {
+    grid: DensityGrid,
+    min: Point,
+    max: Point,
+    sigma_t: f64,
+    albedo: Color,
+    phase: HenyeyGreenstein,
+}
+
+impl Heterogeneous {
+    /// `grid` is stretched over the box from `min` to `max`; its densities
+    /// scale the extinction coefficient `sigma_t`.
+    pub fn new(
+        grid: DensityGrid,
+        min: Point,
+        max: Point,
+        sigma_t: f64,
+        albedo: Color,
+        g: f64,
+    ) -> Self {
+        if min.x() >= max.x() || min.y() >= max.y() || min.z() >= max.z() {
+            panic!("Invalid medium bounds.");
+        }
+        if sigma_t < 0.0 {
+            panic!("Invalid medium density.");
+        }
+        Heterogeneous {
+            grid,
+            min,
+            max,
+            sigma_t,
+            albedo,
+            phase: HenyeyGreenstein::new(g),
+        }
+    }
+
+    pub fn grid(&self) -> &DensityGrid {
+        &self.grid
+    }
+
+    /// Extinction coefficient at `point`.
+    pub fn extinction(&self, point: Point) -> f64 {
+        let local = Point::new(
+            (point.x() - self.min.x()) / (self.max.x() - self.min.x()),
+            (point.y() - self.min.y()) / (self.max.y() - self.min.y()),
+            (point.z() - self.min.z()) / (self.max.z() - self.min.z()),
+        );
+        self.sigma_t * self.grid.density(local)
+    }
+
+    /// Tentative collisions inside the box, spaced for the majorant; calls
+    /// `collide` with each until it returns false. `None` if the ray misses
+    /// the box or the medium is empty.
+    fn track(
+        &self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut Rng,
+        mut collide: impl FnMut(f64, &mut Rng) -> bool,
+    ) -> Option<()> {
+        let majorant = self.sigma_t * self.grid.max();
+        if majorant <= 0.0 {
+            return None;
+        }
+        let (t0, t1) = clip(ray, self.min, self.max, t_min, t_max)?;
+        let scale = majorant * ray.direction.magnitude();
+        let mut t = t0;
+        loop {
+            t -= (1.0 - rng.next_f64()).ln() / scale;
+            if t >= t1 || !collide(t, rng) {
+                return Some(());
+            }
+        }
+    }
+}
+
+impl Medium for Heterogeneous {
+    fn phase(&self) -> HenyeyGreenstein {
+        self.phase
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut Rng) -> f64 {
+        let majorant = self.sigma_t * self.grid.max();
+        let mut res = 1.0;
+        self.track(ray, t_min, t_max, rng, |t, _| {
+            res *= 1.0 - self.extinction(ray.position(t)) / majorant;
+            res > 0.0
+        });
+        res
+    }
+
+    fn sample(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut Rng) -> Option<MediumSample> {
+        let majorant = self.sigma_t * self.grid.max();
+        let mut res = None;
+        self.track(ray, t_min, t_max, rng, |t, rng| {
+            let point = ray.position(t);
+            if rng.next_f64() < self.extinction(point) / majorant {
+                res = Some(MediumSample {
+                    t,
+                    point,
+                    albedo: self.albedo,
+                });
+                return false;
+            }
+            true
+        });
+        res
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::Homogeneous;
+    use crate::primitives::Vector;
+    use approx::assert_abs_diff_eq;
+
+    fn unit_box() -> (Point, Point) {
+        (Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn density_grid_interpolates() {
+        let grid = DensityGrid::new(2, 1, 1, vec![1.0, 3.0]);
+        assert_eq!(grid.max(), 3.0);
+        assert_eq!(grid.density(Point::new(0.0, 0.5, 0.5)), 1.0);
+        assert_eq!(grid.density(Point::new(0.25, 0.5, 0.5)), 1.5);
+        assert_eq!(grid.density(Point::new(2.0, 0.5, 0.5)), 3.0);
+
+        let grid = DensityGrid::from_fn(3, 3, 3, |p| p.x() + 2.0 * p.y() + 4.0 * p.z());
+        assert_abs_diff_eq!(
+            grid.density(Point::new(0.3, 0.6, 0.9)),
+            0.3 + 1.2 + 3.6,
+            epsilon = 1.0e-12
+        );
+    }
+
+    #[test]
+    fn heterogeneous_constant_grid_matches_homogeneous() {
+        let (min, max) = unit_box();
+        let grid = DensityGrid::new(2, 2, 2, vec![0.5; 8]);
+        let medium = Heterogeneous::new(grid, min, max, 2.0, Color::white(), 0.0);
+        let ray = Ray::new(Point::new(0.5, 0.5, -1.0), Vector::new(0.0, 0.0, 1.0));
+        let mut rng = Rng::new(4);
+
+        // A constant grid never rejects a tentative collision.
+        let expected =
+            Homogeneous::new(1.0, Color::white(), 0.0).transmittance(&ray, 1.0, 2.0, &mut rng);
+        let n = 50_000;
+        let escaped = (0..n)
+            .filter(|_| medium.sample(&ray, 0.0, f64::INFINITY, &mut rng).is_none())
+            .count();
+        assert_abs_diff_eq!(escaped as f64 / n as f64, expected, epsilon = 0.01);
+    }
+
+    #[test]
+    fn heterogeneous_tracking_is_unbiased() {
+        // Density rising linearly along z: the optical depth through the
+        // box is sigma_t / 2.
+        let (min, max) = unit_box();
+        let grid = DensityGrid::from_fn(2, 2, 2, |p| p.z());
+        let medium = Heterogeneous::new(grid, min, max, 3.0, Color::white(), 0.0);
+        let ray = Ray::new(Point::new(0.5, 0.5, -1.0), Vector::new(0.0, 0.0, 1.0));
+        let expected = (-1.5f64).exp();
+        let mut rng = Rng::new(6);
+
+        let n = 50_000;
+        let ratio: f64 = (0..n)
+            .map(|_| medium.transmittance(&ray, 0.0, f64::INFINITY, &mut rng))
+            .sum::<f64>()
+            / n as f64;
+        assert_abs_diff_eq!(ratio, expected, epsilon = 0.01);
+
+        let mut depth = 0.0;
+        let mut collisions = 0;
+        for _ in 0..n {
+            if let Some(sample) = medium.sample(&ray, 0.0, f64::INFINITY, &mut rng) {
+                depth += sample.point.z();
+                collisions += 1;
+            }
+        }
+        assert_abs_diff_eq!(1.0 - collisions as f64 / n as f64, expected, epsilon = 0.01);
+        // Collisions bunch up where the smoke is thicker.
+        assert!(depth / collisions as f64 > 0.5);
+    }
+
+    #[test]
+    fn heterogeneous_empty_grid() {
+        let (min, max) = unit_box();
+        let medium = Heterogeneous::new(
+            DensityGrid::new(1, 1, 1, vec![0.0]),
+            min,
+            max,
+            1.0,
+            Color::white(),
+            0.0,
+        );
+        let ray = Ray::new(Point::new(0.5, 0.5, -1.0), Vector::new(0.0, 0.0, 1.0));
+        let mut rng = Rng::new(0);
+        assert_eq!(
+            medium.transmittance(&ray, 0.0, f64::INFINITY, &mut rng),
+            1.0
+        );
+        assert!(medium.sample(&ray, 0.0, f64::INFINITY, &mut rng).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn density_grid_invalid_dimensions() {
+        DensityGrid::new(2, 2, 2, vec![1.0; 7]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn density_grid_negative_density() {
+        DensityGrid::new(1, 1, 1, vec![-1.0]);
+    }
+}
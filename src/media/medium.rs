@@ -0,0 +1,266 @@
+use crate::media::HenyeyGreenstein;
+use crate::primitives::{Color, Point, Ray, Tuple};
+use crate::sampling::Rng;
+
+// ------------------------------------------------------
+/// Where a ray scatters inside a medium.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MediumSample {
+    /// Ray parameter of the collision.
+    pub t: f64,
+    pub point: Point,
+    /// Scattering over extinction, the factor the path throughput is
+    /// multiplied by.
+    pub albedo: Color,
+}
+
+/// Participating medium such as fog or smoke. Extinction is the same for
+/// all wavelengths; color comes from the single scattering albedo. Ray
+/// parameters need not be distances: the ray direction may have any
+/// length.
+pub trait Medium {
+    fn phase(&self) -> HenyeyGreenstein;
+
+    /// Fraction of the light surviving along `ray` between `t_min` and
+    /// `t_max`. Unbiased, but not necessarily exact.
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut Rng) -> f64;
+
+    /// First collision between `t_min` and `t_max`, drawn with the density
+    /// of light being scattered there, or `None` if the ray passes through.
+    fn sample(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut Rng) -> Option<MediumSample>;
+}
+
+// ------------------------------------------------------
+/// Medium of constant density filling all of space, such as haze.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Homogeneous {
+    sigma_t: f64,
+    albedo: Color,
+    phase: HenyeyGreenstein,
+}
+
+impl Homogeneous {
+    /// `sigma_t` is the extinction coefficient per unit distance and `g`
+    /// the asymmetry of the Henyey-Greenstein phase function.
+    pub fn new(sigma_t: f64, albedo: Color, g: f64) -> Self {
+        if sigma_t < 0.0 {
+            panic!("Invalid medium density.");
+        }
+        Homogeneous {
+            sigma_t,
+            albedo,
+            phase: HenyeyGreenstein::new(g),
+        }
+    }
+
+    pub fn sigma_t(&self) -> f64 {
+        self.sigma_t
+    }
+
+    pub fn albedo(&self) -> Color {
+        self.albedo
+    }
+}
+
+impl Medium for Homogeneous {
+    fn phase(&self) -> HenyeyGreenstein {
+        self.phase
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64, _rng: &mut Rng) -> f64 {
+        if self.sigma_t == 0.0 {
+            return 1.0;
+        }
+        (-self.sigma_t * (t_max - t_min) * ray.direction.magnitude()).exp()
+    }
+
+    fn sample(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut Rng) -> Option<MediumSample> {
+        if self.sigma_t == 0.0 {
+            return None;
+        }
+        let distance = -(1.0 - rng.next_f64()).ln() / self.sigma_t;
+        let t = t_min + distance / ray.direction.magnitude();
+        (t < t_max).then(|| MediumSample {
+            t,
+            point: ray.position(t),
+            albedo: self.albedo,
+        })
+    }
+}
+
+// ------------------------------------------------------
+/// Medium confined to an axis aligned box, such as smoke in a glass.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounded<M> {
+    medium: M,
+    min: Point,
+    max: Point,
+}
+
+impl<M: Medium> Bounded<M> {
+    pub fn new(medium: M, min: Point, max: Point) -> Self {
+        if min.x() > max.x() || min.y() > max.y() || min.z() > max.z() {
+            panic!("Invalid medium bounds.");
+        }
+        Bounded { medium, min, max }
+    }
+
+    pub fn medium(&self) -> &M {
+        &self.medium
+    }
+}
+
+impl<M: Medium> Medium for Bounded<M> {
+    fn phase(&self) -> HenyeyGreenstein {
+        self.medium.phase()
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut Rng) -> f64 {
+        match clip(ray, self.min, self.max, t_min, t_max) {
+            Some((t0, t1)) => self.medium.transmittance(ray, t0, t1, rng),
+            None => 1.0,
+        }
+    }
+
+    fn sample(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut Rng) -> Option<MediumSample> {
+        let (t0, t1) = clip(ray, self.min, self.max, t_min, t_max)?;
+        self.medium.sample(ray, t0, t1, rng)
+    }
+}
+
+/// Part of `[t_min, t_max]` for which `ray` is inside the box, by the slab
+/// method.
+pub(crate) fn clip(
+    ray: &Ray,
+    min: Point,
+    max: Point,
+    t_min: f64,
+    t_max: f64,
+) -> Option<(f64, f64)> {
+    let (mut t0, mut t1) = (t_min, t_max);
+    let axes = [
+        (ray.origin.x(), ray.direction.x(), min.x(), max.x()),
+        (ray.origin.y(), ray.direction.y(), min.y(), max.y()),
+        (ray.origin.z(), ray.direction.z(), min.z(), max.z()),
+    ];
+    for (origin, direction, lo, hi) in axes {
+        if direction == 0.0 {
+            if origin < lo || origin > hi {
+                return None;
+            }
+            continue;
+        }
+        let (a, b) = ((lo - origin) / direction, (hi - origin) / direction);
+        t0 = t0.max(a.min(b));
+        t1 = t1.min(a.max(b));
+    }
+    (t0 < t1).then_some((t0, t1))
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Vector;
+    use approx::assert_abs_diff_eq;
+
+    fn fog() -> Homogeneous {
+        Homogeneous::new(0.5, Color::new(0.9, 0.8, 0.7), 0.0)
+    }
+
+    #[test]
+    fn homogeneous_transmittance() {
+        let ray = Ray::new(Point::default(), Vector::new(0.0, 0.0, 2.0));
+        let mut rng = Rng::new(0);
+        // Ray parameters are scaled by the direction's length.
+        assert_abs_diff_eq!(
+            fog().transmittance(&ray, 1.0, 2.0, &mut rng),
+            (-1.0f64).exp(),
+            epsilon = 1.0e-12
+        );
+        assert_eq!(fog().transmittance(&ray, 0.0, f64::INFINITY, &mut rng), 0.0);
+
+        let clear = Homogeneous::new(0.0, Color::white(), 0.0);
+        assert_eq!(clear.transmittance(&ray, 0.0, f64::INFINITY, &mut rng), 1.0);
+        assert!(clear.sample(&ray, 0.0, f64::INFINITY, &mut rng).is_none());
+    }
+
+    #[test]
+    fn homogeneous_sampling_matches_transmittance() {
+        let ray = Ray::new(Point::default(), Vector::new(1.0, 0.0, 0.0));
+        let mut rng = Rng::new(1);
+        let medium = fog();
+        let n = 100_000;
+        let mut escaped = 0;
+        for _ in 0..n {
+            match medium.sample(&ray, 0.0, 3.0, &mut rng) {
+                Some(sample) => {
+                    assert!(sample.t >= 0.0 && sample.t < 3.0);
+                    assert_eq!(sample.point, ray.position(sample.t));
+                    assert_eq!(sample.albedo, medium.albedo());
+                }
+                None => escaped += 1,
+            }
+        }
+        let expected = medium.transmittance(&ray, 0.0, 3.0, &mut rng);
+        assert_abs_diff_eq!(escaped as f64 / n as f64, expected, epsilon = 0.005);
+    }
+
+    #[test]
+    fn bounded_medium_clips_rays() {
+        let smoke = Bounded::new(
+            fog(),
+            Point::new(-1.0, -1.0, -1.0),
+            Point::new(1.0, 1.0, 1.0),
+        );
+        let mut rng = Rng::new(2);
+
+        // Two units of smoke along the z axis, none beside the box.
+        let through = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        let beside = Ray::new(Point::new(2.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_abs_diff_eq!(
+            smoke.transmittance(&through, 0.0, f64::INFINITY, &mut rng),
+            (-1.0f64).exp(),
+            epsilon = 1.0e-12
+        );
+        assert_eq!(
+            smoke.transmittance(&beside, 0.0, f64::INFINITY, &mut rng),
+            1.0
+        );
+        assert_eq!(smoke.transmittance(&through, 0.0, 3.0, &mut rng), 1.0);
+
+        for _ in 0..1000 {
+            assert!(smoke
+                .sample(&beside, 0.0, f64::INFINITY, &mut rng)
+                .is_none());
+            if let Some(sample) = smoke.sample(&through, 0.0, f64::INFINITY, &mut rng) {
+                assert!(sample.point.z() >= -1.0 && sample.point.z() <= 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn clip_starts_inside() {
+        let ray = Ray::new(Point::default(), Vector::new(0.0, 2.0, 0.0));
+        let res = clip(
+            &ray,
+            Point::new(-1.0, -1.0, -1.0),
+            Point::new(1.0, 1.0, 1.0),
+            0.0,
+            10.0,
+        );
+        assert_eq!(res, Some((0.0, 0.5)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn homogeneous_invalid_density() {
+        Homogeneous::new(-1.0, Color::white(), 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bounded_invalid_bounds() {
+        Bounded::new(fog(), Point::new(1.0, 0.0, 0.0), Point::new(-1.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,158 @@
+use std::f64::consts::PI;
+
+use crate::primitives::Vector;
+use crate::sampling::orthonormal_basis;
+
+// ------------------------------------------------------
+/// Henyey-Greenstein phase function. `g` is the mean cosine of the
+/// scattering angle: positive values scatter forward, negative values
+/// backward and zero evenly in all directions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HenyeyGreenstein {
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(g: f64) -> Self {
+        if g.is_nan() || g.abs() >= 1.0 {
+            panic!("Invalid phase asymmetry.");
+        }
+        HenyeyGreenstein { g }
+    }
+
+    pub fn g(&self) -> f64 {
+        self.g
+    }
+
+    /// Fraction of the light arriving from `wi` scattered towards `wo`, per
+    /// steradian. Both directions point away from the scattering point, as
+    /// with BSDFs, and are normalized.
+    pub fn eval(&self, wo: Vector, wi: Vector) -> f64 {
+        let g = self.g;
+        let denom = 1.0 + g * g + 2.0 * g * wo.dot(wi);
+        (1.0 - g * g) / (4.0 * PI * denom * denom.sqrt())
+    }
+
+    /// Draws `wi` with density `eval(wo, wi)` for the unit square sample
+    /// `(u, v)`.
+    pub fn sample(&self, wo: Vector, u: f64, v: f64) -> Vector {
+        let g = self.g;
+        // Cosine between the propagation directions before and after.
+        let cos = if g.abs() < 1.0e-3 {
+            1.0 - 2.0 * u
+        } else {
+            let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * u);
+            (1.0 + g * g - s * s) / (2.0 * g)
+        };
+        let sin = (1.0 - cos * cos).max(0.0).sqrt();
+        let phi = 2.0 * PI * v;
+
+        // Light continuing straight on towards the viewer arrives from
+        // directly opposite it.
+        let forward = -wo;
+        let (tangent, bitangent) = orthonormal_basis(forward);
+        tangent * (sin * phi.cos()) + bitangent * (sin * phi.sin()) + forward * cos
+    }
+}
+
+impl Default for HenyeyGreenstein {
+    fn default() -> Self {
+        HenyeyGreenstein::new(0.0)
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Tuple;
+    use crate::sampling::Rng;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn henyey_greenstein_isotropic() {
+        let phase = HenyeyGreenstein::default();
+        let wo = Vector::new(0.0, 0.0, 1.0);
+        for wi in [wo, -wo, Vector::new(1.0, 0.0, 0.0)] {
+            assert_abs_diff_eq!(phase.eval(wo, wi), 0.25 / PI, epsilon = 1.0e-12);
+        }
+    }
+
+    #[test]
+    fn henyey_greenstein_forward_scattering() {
+        let phase = HenyeyGreenstein::new(0.8);
+        let wo = Vector::new(0.0, 0.0, 1.0);
+        // Light travelling along -z keeps going towards a viewer at -z...
+        assert!(phase.eval(-wo, wo) > 10.0 * phase.eval(wo, wo));
+        // ...while a viewer at +z mostly sees light from behind itself.
+        assert!(phase.eval(wo, -wo) > phase.eval(wo, wo));
+    }
+
+    #[test]
+    fn henyey_greenstein_normalized() {
+        let mut rng = Rng::new(3);
+        let wo = Vector::new(0.0, 0.6, 0.8);
+        for g in [-0.7, 0.0, 0.3, 0.9] {
+            let phase = HenyeyGreenstein::new(g);
+
+            // Uniform sphere estimate of the integral of the phase function.
+            let n = 200_000;
+            let sum: f64 = (0..n)
+                .map(|_| {
+                    let z = 1.0 - 2.0 * rng.next_f64();
+                    let phi = 2.0 * PI * rng.next_f64();
+                    let r = (1.0 - z * z).sqrt();
+                    let wi = Vector::new(r * phi.cos(), r * phi.sin(), z);
+                    phase.eval(wo, wi) * 4.0 * PI
+                })
+                .sum();
+            assert_abs_diff_eq!(sum / n as f64, 1.0, epsilon = 0.03);
+        }
+    }
+
+    #[test]
+    fn henyey_greenstein_sampling_mean_cosine() {
+        let mut rng = Rng::new(5);
+        let wo = Vector::new(1.0, 0.0, 0.0);
+        for g in [-0.5, 0.0, 0.6] {
+            let phase = HenyeyGreenstein::new(g);
+            let n = 100_000;
+            let mean: f64 = (0..n)
+                .map(|_| {
+                    let wi = phase.sample(wo, rng.next_f64(), rng.next_f64());
+                    assert_abs_diff_eq!(wi.magnitude(), 1.0, epsilon = 1.0e-9);
+                    // Light travels along -wi before and along wo after.
+                    (-wi).dot(wo)
+                })
+                .sum::<f64>()
+                / n as f64;
+            assert_abs_diff_eq!(mean, g, epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn henyey_greenstein_sampling_matches_eval() {
+        // Averaging 1 / pdf over samples drawn with that pdf gives the
+        // area of the sphere.
+        let mut rng = Rng::new(7);
+        let wo = Vector::new(0.0, 0.6, 0.8);
+        for g in [-0.6, 0.0, 0.3, 0.8] {
+            let phase = HenyeyGreenstein::new(g);
+            let n = 200_000;
+            let mean: f64 = (0..n)
+                .map(|_| {
+                    let wi = phase.sample(wo, rng.next_f64(), rng.next_f64());
+                    1.0 / phase.eval(wo, wi)
+                })
+                .sum::<f64>()
+                / n as f64;
+            assert!((mean - 4.0 * PI).abs() < 0.03 * 4.0 * PI, "{} {}", g, mean);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn henyey_greenstein_invalid() {
+        HenyeyGreenstein::new(1.0);
+    }
+}
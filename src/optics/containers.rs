@@ -0,0 +1,68 @@
+use crate::primitives::Float;
+
+// ------------------------------------------------------
+/// Refractive indices `(n1, n2)` on either side of the intersection at
+/// `hit`.
+///
+/// `intersections` must be sorted by distance along the ray and hold one
+/// entry per object crossing. Objects the ray is currently inside are
+/// tracked in a containers list; `ior` gives the refractive index of an
+/// object and empty space counts as a vacuum (`1.0`).
+pub fn refractive_indices<O, T>(intersections: &[O], hit: usize, ior: impl Fn(&O) -> T) -> (T, T)
+where
+    O: PartialEq,
+    T: Float,
+{
+    let mut containers: Vec<&O> = Vec::new();
+    let outermost = |containers: &Vec<&O>| containers.last().map_or(T::one(), |o| ior(o));
+    let mut n1 = T::one();
+
+    for (idx, object) in intersections.iter().enumerate() {
+        if idx == hit {
+            n1 = outermost(&containers);
+        }
+
+        match containers.iter().position(|o| *o == object) {
+            Some(pos) => {
+                containers.remove(pos);
+            }
+            None => containers.push(object),
+        }
+
+        if idx == hit {
+            return (n1, outermost(&containers));
+        }
+    }
+
+    (n1, T::one())
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refractive_indices_at_various_intersections() {
+        // Scenario Outline: Finding n1 and n2 at various intersections
+        // Three overlapping glass spheres A (1.5), B (2.0) and C (2.5).
+        let xs = ['A', 'B', 'C', 'B', 'C', 'A'];
+        let ior = |o: &char| match o {
+            'A' => 1.5,
+            'B' => 2.0,
+            _ => 2.5,
+        };
+
+        let expected = [
+            (1.0, 1.5),
+            (1.5, 2.0),
+            (2.0, 2.5),
+            (2.5, 2.5),
+            (2.5, 1.5),
+            (1.5, 1.0),
+        ];
+        for (idx, e) in expected.iter().enumerate() {
+            assert_eq!(refractive_indices(&xs, idx, ior), *e);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::primitives::{Color, Float};
+
+// ------------------------------------------------------
+/// Schlick's approximation of the Fresnel reflectance.
+///
+/// `cos` is the cosine of the angle between the eye vector and the surface
+/// normal, `n1` and `n2` the refractive indices on the incoming and outgoing
+/// side. Returns `1.0` under total internal reflection.
+pub fn schlick<T: Float>(cos: T, n1: T, n2: T) -> T {
+    let one = T::one();
+    let mut cos = cos;
+
+    if n1 > n2 {
+        let n = n1 / n2;
+        let sin2_t = n * n * (one - cos * cos);
+        if sin2_t > one {
+            return one;
+        }
+
+        // When going from the denser medium, use cos(theta_t) instead.
+        cos = (one - sin2_t).sqrt();
+    }
+
+    let r0 = ((n1 - n2) / (n1 + n2)).powi(2);
+    r0 + (one - r0) * (one - cos).powi(5)
+}
+
+/// Exact Fresnel reflectance of unpolarized light at a dielectric boundary,
+/// with the same arguments as `schlick`.
+pub fn fresnel_dielectric<T: Float>(cos: T, n1: T, n2: T) -> T {
+    let one = T::one();
+    let cos_i = cos.min(one);
+    let n = n1 / n2;
+    let sin2_t = n * n * (one - cos_i * cos_i);
+    if sin2_t > one {
+        return one;
+    }
+
+    let cos_t = (one - sin2_t).sqrt();
+    let r_s = (n1 * cos_i - n2 * cos_t) / (n1 * cos_i + n2 * cos_t);
+    let r_p = (n2 * cos_i - n1 * cos_t) / (n2 * cos_i + n1 * cos_t);
+    (r_s * r_s + r_p * r_p) / T::from_f64(2.0)
+}
+
+/// Fresnel reflectance of a conductor with complex refractive index
+/// `eta + i k` per color channel, relative to the outside medium.
+pub fn fresnel_conductor<T: Float>(cos: T, eta: Color<T>, k: Color<T>) -> Color<T> {
+    let channel = |eta: T, k: T| {
+        let one = T::one();
+        let two = T::from_f64(2.0);
+        let cos2 = cos * cos;
+        let sin2 = one - cos2;
+        let t0 = eta * eta - k * k - sin2;
+        let a2_plus_b2 = (t0 * t0 + T::from_f64(4.0) * eta * eta * k * k).sqrt();
+        let a = ((a2_plus_b2 + t0) / two).max(T::zero()).sqrt();
+        let t1 = a2_plus_b2 + cos2;
+        let t2 = two * cos * a;
+        let r_s = (t1 - t2) / (t1 + t2);
+        let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+        let t4 = t2 * sin2;
+        let r_p = r_s * (t3 - t4) / (t3 + t4);
+        (r_s + r_p) / two
+    };
+    Color::new(
+        channel(eta.r(), k.r()),
+        channel(eta.g(), k.g()),
+        channel(eta.b(), k.b()),
+    )
+}
+
+/// Schlick's approximation from the reflectance `f0` at normal incidence,
+/// per color channel.
+pub fn schlick_color<T: Float>(cos: T, f0: Color<T>) -> Color<T> {
+    let w = (T::one() - cos).max(T::zero()).powi(5);
+    f0 + (Color::white() - f0) * w
+}
+
+/// Combines the surface, reflected and refracted contributions, weighting
+/// the last two by the Fresnel `reflectance`.
+pub fn fresnel_blend<T: Float>(
+    surface: Color<T>,
+    reflected: Color<T>,
+    refracted: Color<T>,
+    reflectance: T,
+) -> Color<T> {
+    surface + reflected * reflectance + refracted * (T::one() - reflectance)
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn schlick_total_internal_reflection() {
+        // Scenario: The Schlick approximation under total internal reflection
+        let cos = 2.0f64.sqrt() / 2.0;
+        assert_eq!(schlick(cos, 1.5, 1.0), 1.0);
+    }
+
+    #[test]
+    fn schlick_perpendicular() {
+        // Scenario: The Schlick approximation with a perpendicular viewing angle
+        assert_abs_diff_eq!(schlick(1.0, 1.5, 1.0), 0.04, epsilon = 1.0e-5);
+        assert_abs_diff_eq!(schlick(1.0, 1.0, 1.5), 0.04, epsilon = 1.0e-5);
+    }
+
+    #[test]
+    fn schlick_small_angle() {
+        // Scenario: The Schlick approximation with small angle and n2 > n1
+        let cos = (1.0f64 - 0.99 * 0.99).sqrt();
+        assert_abs_diff_eq!(schlick(cos, 1.0, 1.5), 0.48881, epsilon = 1.0e-5);
+    }
+
+    #[test]
+    fn fresnel_blend_weights() {
+        let res = fresnel_blend(
+            Color::new(0.1, 0.1, 0.1),
+            Color::white(),
+            Color::new(0.0, 0.5, 1.0),
+            0.25,
+        );
+        assert_eq!(res, Color::new(0.35, 0.725, 1.1));
+    }
+
+    #[test]
+    fn fresnel_dielectric_exact() {
+        // Normal incidence matches ((n1 - n2) / (n1 + n2))^2.
+        assert_abs_diff_eq!(fresnel_dielectric(1.0, 1.0, 1.5), 0.04, epsilon = 1.0e-9);
+        assert_abs_diff_eq!(fresnel_dielectric(1.0, 1.5, 1.0), 0.04, epsilon = 1.0e-9);
+        assert_abs_diff_eq!(fresnel_dielectric(0.0, 1.0, 1.5), 1.0, epsilon = 1.0e-9);
+        assert_eq!(fresnel_dielectric(0.5, 1.5, 1.0), 1.0);
+
+        // Brewster's angle: the parallel component vanishes.
+        let brewster = 1.5f64.atan();
+        let r_s = ((brewster.cos() - 1.5 * (brewster.sin() / 1.5).asin().cos())
+            / (brewster.cos() + 1.5 * (brewster.sin() / 1.5).asin().cos()))
+        .powi(2);
+        assert_abs_diff_eq!(
+            fresnel_dielectric(brewster.cos(), 1.0, 1.5),
+            r_s / 2.0,
+            epsilon = 1.0e-9
+        );
+    }
+
+    #[test]
+    fn fresnel_conductor_limits() {
+        // Without absorption a conductor behaves like a dielectric.
+        let eta = Color::new(1.5, 1.5, 1.5);
+        let k = Color::black();
+        for cos in [1.0, 0.7, 0.3] {
+            let res = fresnel_conductor(cos, eta, k);
+            assert_abs_diff_eq!(res.r(), fresnel_dielectric(cos, 1.0, 1.5), epsilon = 1.0e-9);
+        }
+
+        // Gold reflects more red than blue and everything at grazing angles.
+        let gold_eta = Color::new(0.143, 0.374, 1.442);
+        let gold_k = Color::new(3.983, 2.385, 1.603);
+        let normal = fresnel_conductor(1.0, gold_eta, gold_k);
+        assert!(normal.r() > normal.b());
+        assert_abs_diff_eq!(
+            fresnel_conductor(0.0, gold_eta, gold_k),
+            Color::white(),
+            epsilon = 1.0e-9
+        );
+    }
+
+    #[test]
+    fn schlick_color_limits() {
+        let f0 = Color::new(0.9, 0.6, 0.3);
+        assert_eq!(schlick_color(1.0, f0), f0);
+        assert_eq!(schlick_color(0.0, f0), Color::white());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::patterns::{Pattern, PatternTransform};
+use crate::primitives::{Color, Matrix, Point};
+
+// ------------------------------------------------------
+/// Mixes two patterns sampled at the same point, `weight` of `b` over `a`
+/// (an even average by default).
+pub struct Blend {
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+    weight: f64,
+    transform: PatternTransform,
+}
+
+impl Blend {
+    pub fn new(a: impl Into<Box<dyn Pattern>>, b: impl Into<Box<dyn Pattern>>) -> Self {
+        Blend {
+            a: a.into(),
+            b: b.into(),
+            weight: 0.5,
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_weight(self, weight: f64) -> Self {
+        Blend { weight, ..self }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        Blend {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for Blend {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let a = self.a.pattern_at_object(point);
+        let b = self.b.pattern_at_object(point);
+        a * (1.0 - self.weight) + b * self.weight
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Stripe;
+    use crate::primitives::Tuple;
+
+    #[test]
+    fn blend_averages_patterns() {
+        let pattern = Blend::new(Color::red(), Color::blue());
+        let c = pattern.pattern_at(Point::new(0.0, 0.0, 0.0));
+        assert_eq!(c, Color::new(0.5, 0.0, 0.5));
+
+        let pattern = Blend::new(Color::red(), Color::blue()).with_weight(0.25);
+        let c = pattern.pattern_at(Point::new(0.0, 0.0, 0.0));
+        assert_eq!(c, Color::new(0.75, 0.0, 0.25));
+    }
+
+    #[test]
+    fn blend_of_crossed_stripes() {
+        // Plaid: stripes along x blended with stripes along z.
+        let along_x = Stripe::new(Color::white(), Color::black());
+        #[rustfmt::skip]
+        let rotate_y = Matrix::new4().init([
+            0.0, 0.0, 1.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            -1.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+        let along_z = Stripe::new(Color::white(), Color::black()).with_transform(rotate_y);
+        let pattern = Blend::new(along_x, along_z);
+
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.5, 0.0, -0.5)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(1.5, 0.0, -0.5)),
+            Color::new(0.5, 0.5, 0.5)
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use crate::patterns::{Pattern, PatternTransform};
+use crate::primitives::{Color, Matrix, Point, Tuple};
+
+// ------------------------------------------------------
+/// Alternating unit cubes of `a` and `b` in all three dimensions.
+pub struct Checkers {
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+    transform: PatternTransform,
+}
+
+impl Checkers {
+    pub fn new(a: impl Into<Box<dyn Pattern>>, b: impl Into<Box<dyn Pattern>>) -> Self {
+        Checkers {
+            a: a.into(),
+            b: b.into(),
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        Checkers {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for Checkers {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let sum = point.x().floor() + point.y().floor() + point.z().floor();
+        if (sum as i64).rem_euclid(2) == 0 {
+            self.a.pattern_at_object(point)
+        } else {
+            self.b.pattern_at_object(point)
+        }
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkers_repeat_in_each_axis() {
+        // Scenario: Checkers should repeat in x, y and z
+        let pattern = Checkers::new(Color::white(), Color::black());
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.0, 0.0, 0.0)),
+            Color::white()
+        );
+        for (x, y, z) in [(0.99, 0.0, 0.0), (0.0, 0.99, 0.0), (0.0, 0.0, 0.99)] {
+            assert_eq!(pattern.pattern_at(Point::new(x, y, z)), Color::white());
+        }
+        for (x, y, z) in [(1.01, 0.0, 0.0), (0.0, 1.01, 0.0), (0.0, 0.0, 1.01)] {
+            assert_eq!(pattern.pattern_at(Point::new(x, y, z)), Color::black());
+        }
+        assert_eq!(
+            pattern.pattern_at(Point::new(-0.5, -0.5, 0.0)),
+            Color::white()
+        );
+    }
+}
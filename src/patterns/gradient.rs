@@ -0,0 +1,68 @@
+use crate::patterns::{Pattern, PatternTransform};
+use crate::primitives::{Color, Matrix, Point, Tuple};
+
+// ------------------------------------------------------
+/// Blends linearly from `a` to `b` over each unit along x.
+pub struct Gradient {
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+    transform: PatternTransform,
+}
+
+impl Gradient {
+    pub fn new(a: impl Into<Box<dyn Pattern>>, b: impl Into<Box<dyn Pattern>>) -> Self {
+        Gradient {
+            a: a.into(),
+            b: b.into(),
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        Gradient {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for Gradient {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let a = self.a.pattern_at_object(point);
+        let b = self.b.pattern_at_object(point);
+        let fraction = point.x() - point.x().floor();
+        a + (b - a) * fraction
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gradient_interpolates() {
+        // Scenario: A gradient linearly interpolates between colors
+        let pattern = Gradient::new(Color::white(), Color::black());
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.0, 0.0, 0.0)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.25, 0.0, 0.0)),
+            Color::new(0.75, 0.75, 0.75)
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.5, 0.0, 0.0)),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.75, 0.0, 0.0)),
+            Color::new(0.25, 0.25, 0.25)
+        );
+    }
+}
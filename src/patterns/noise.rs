@@ -0,0 +1,91 @@
+use crate::primitives::{Point, Tuple};
+
+// ------------------------------------------------------
+/// Perlin gradient noise at `point`, roughly in `[-1, 1]`, and exactly `0`
+/// at integer lattice points.
+pub fn perlin(point: Point) -> f64 {
+    let (x, y, z) = (point.x(), point.y(), point.z());
+    let (xi, yi, zi) = (x.floor(), y.floor(), z.floor());
+    let (xf, yf, zf) = (x - xi, y - yi, z - zi);
+    let (xi, yi, zi) = (xi as i64, yi as i64, zi as i64);
+
+    let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+    let corner = |dx: i64, dy: i64, dz: i64| {
+        grad(
+            hash(xi + dx, yi + dy, zi + dz),
+            xf - dx as f64,
+            yf - dy as f64,
+            zf - dz as f64,
+        )
+    };
+
+    let x00 = lerp(u, corner(0, 0, 0), corner(1, 0, 0));
+    let x10 = lerp(u, corner(0, 1, 0), corner(1, 1, 0));
+    let x01 = lerp(u, corner(0, 0, 1), corner(1, 0, 1));
+    let x11 = lerp(u, corner(0, 1, 1), corner(1, 1, 1));
+    lerp(w, lerp(v, x00, x10), lerp(v, x01, x11))
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Integer hash of a lattice point, standing in for Perlin's permutation
+/// table.
+fn hash(x: i64, y: i64, z: i64) -> u64 {
+    let mut h = (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (z as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    h ^ (h >> 33)
+}
+
+/// Dot product with one of the twelve cube-edge gradient directions.
+fn grad(hash: u64, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = match h {
+        0..=3 => y,
+        12 | 14 => x,
+        _ => z,
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perlin_zero_on_lattice() {
+        assert_eq!(perlin(Point::new(0.0, 0.0, 0.0)), 0.0);
+        assert_eq!(perlin(Point::new(3.0, -2.0, 7.0)), 0.0);
+    }
+
+    #[test]
+    fn perlin_bounded_and_varying() {
+        let mut min = f64::MAX;
+        let mut max = f64::MIN;
+        for i in 0..1000 {
+            let t = i as f64 * 0.0137;
+            let n = perlin(Point::new(t * 3.1, t * 1.7 + 0.3, t * 2.3 + 0.6));
+            min = min.min(n);
+            max = max.max(n);
+        }
+        assert!((-1.5..=1.5).contains(&min));
+        assert!((-1.5..=1.5).contains(&max));
+        assert!(max - min > 0.5);
+    }
+
+    #[test]
+    fn perlin_is_deterministic() {
+        let p = Point::new(1.3, 2.7, -0.4);
+        assert_eq!(perlin(p), perlin(p));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::primitives::{Color, Matrix, Point};
+
+// ------------------------------------------------------
+/// A color defined over 3D space.
+///
+/// Every pattern carries its own transform, so it can be scaled, moved or
+/// rotated relative to the object it is painted on. Points are mapped from
+/// world space to object space by the shape, then to pattern space by the
+/// pattern's inverse transform.
+pub trait Pattern {
+    fn transform(&self) -> &PatternTransform;
+
+    /// Color at a point given in pattern space.
+    fn pattern_at(&self, point: Point) -> Color;
+
+    /// Color at a point given in object space.
+    fn pattern_at_object(&self, object_point: Point) -> Color {
+        self.pattern_at(self.transform().inverse() * object_point)
+    }
+
+    /// Color at a point given in world space, on a shape whose inverse
+    /// transform is `world_to_object`.
+    fn pattern_at_shape(&self, world_to_object: &Matrix, world_point: Point) -> Color {
+        self.pattern_at_object(world_to_object * world_point)
+    }
+}
+
+impl<P: Pattern + 'static> From<P> for Box<dyn Pattern> {
+    fn from(pattern: P) -> Self {
+        Box::new(pattern)
+    }
+}
+
+impl From<Color> for Box<dyn Pattern> {
+    fn from(color: Color) -> Self {
+        Box::new(crate::patterns::Solid::new(color))
+    }
+}
+
+// ------------------------------------------------------
+/// A pattern transform along with its cached inverse.
+#[derive(Clone, Debug)]
+pub struct PatternTransform {
+    matrix: Matrix,
+    inverse: Matrix,
+}
+
+impl PatternTransform {
+    pub fn new(matrix: Matrix) -> Self {
+        let inverse = match matrix.inverse() {
+            Some(inverse) => inverse,
+            None => panic!("Pattern transform must be invertible."),
+        };
+
+        PatternTransform { matrix, inverse }
+    }
+
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    pub fn inverse(&self) -> &Matrix {
+        &self.inverse
+    }
+}
+
+impl Default for PatternTransform {
+    fn default() -> Self {
+        PatternTransform {
+            matrix: Matrix::identity(),
+            inverse: Matrix::identity(),
+        }
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::primitives::Tuple;
+
+    pub(crate) fn scaling(x: f64, y: f64, z: f64) -> Matrix {
+        #[rustfmt::skip]
+        let m = Matrix::new4().init([
+            x, 0.0, 0.0, 0.0,
+            0.0, y, 0.0, 0.0,
+            0.0, 0.0, z, 0.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+        m
+    }
+
+    pub(crate) fn translation(x: f64, y: f64, z: f64) -> Matrix {
+        #[rustfmt::skip]
+        let m = Matrix::new4().init([
+            1.0, 0.0, 0.0, x,
+            0.0, 1.0, 0.0, y,
+            0.0, 0.0, 1.0, z,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+        m
+    }
+
+    /// Reports the pattern-space point it was sampled at as a color.
+    struct TestPattern {
+        transform: PatternTransform,
+    }
+
+    impl Pattern for TestPattern {
+        fn transform(&self) -> &PatternTransform {
+            &self.transform
+        }
+
+        fn pattern_at(&self, point: Point) -> Color {
+            Color::new(point.x(), point.y(), point.z())
+        }
+    }
+
+    #[test]
+    fn pattern_default_transform() {
+        let t = PatternTransform::default();
+        assert_eq!(*t.matrix(), Matrix::identity());
+        assert_eq!(*t.inverse(), Matrix::identity());
+    }
+
+    #[test]
+    fn pattern_with_object_transformation() {
+        // Scenario: A pattern with an object transformation
+        let pattern = TestPattern {
+            transform: PatternTransform::default(),
+        };
+        let world_to_object = scaling(2.0, 2.0, 2.0).inverse().unwrap();
+        let c = pattern.pattern_at_shape(&world_to_object, Point::new(2.0, 3.0, 4.0));
+        assert_eq!(c, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_with_pattern_transformation() {
+        // Scenario: A pattern with a pattern transformation
+        let pattern = TestPattern {
+            transform: PatternTransform::new(scaling(2.0, 2.0, 2.0)),
+        };
+        let c = pattern.pattern_at_object(Point::new(2.0, 3.0, 4.0));
+        assert_eq!(c, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_with_object_and_pattern_transformation() {
+        // Scenario: A pattern with both an object and a pattern transformation
+        let pattern = TestPattern {
+            transform: PatternTransform::new(translation(0.5, 1.0, 1.5)),
+        };
+        let world_to_object = scaling(2.0, 2.0, 2.0).inverse().unwrap();
+        let c = pattern.pattern_at_shape(&world_to_object, Point::new(2.5, 3.0, 3.5));
+        assert_eq!(c, Color::new(0.75, 0.5, 0.25));
+    }
+
+    #[test]
+    #[should_panic]
+    fn pattern_singular_transform() {
+        PatternTransform::new(Matrix::new4());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::patterns::{noise::perlin, Pattern, PatternTransform};
+use crate::primitives::{Color, Matrix, Point, Tuple, Vector};
+
+// ------------------------------------------------------
+/// Jitters the sample point with Perlin noise before handing it to the
+/// inner pattern, giving its edges an organic, wavy look.
+pub struct Perturbed {
+    pattern: Box<dyn Pattern>,
+    scale: f64,
+    transform: PatternTransform,
+}
+
+impl Perturbed {
+    pub fn new(pattern: impl Into<Box<dyn Pattern>>, scale: f64) -> Self {
+        Perturbed {
+            pattern: pattern.into(),
+            scale,
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        Perturbed {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for Perturbed {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        // Offset the lookups so the three axes get uncorrelated noise.
+        let jitter = Vector::new(
+            perlin(point),
+            perlin(point + Vector::new(31.4, 15.9, 26.5)),
+            perlin(point + Vector::new(-27.1, 82.8, -18.2)),
+        );
+        self.pattern.pattern_at_object(point + jitter * self.scale)
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Stripe;
+
+    #[test]
+    fn perturbed_zero_scale_is_unchanged() {
+        let pattern = Perturbed::new(Stripe::new(Color::white(), Color::black()), 0.0);
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.9, 0.3, 0.2)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(1.1, 0.3, 0.2)),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn perturbed_moves_edges() {
+        let plain = Stripe::new(Color::white(), Color::black());
+        let wavy = Perturbed::new(Stripe::new(Color::white(), Color::black()), 0.5);
+
+        let differs = (0..100)
+            .map(|i| Point::new(0.95 + i as f64 * 0.001, i as f64 * 0.37, i as f64 * 0.11))
+            .filter(|p| plain.pattern_at(*p) != wavy.pattern_at(*p))
+            .count();
+        assert!(differs > 0);
+    }
+}
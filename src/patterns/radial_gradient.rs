@@ -0,0 +1,64 @@
+use crate::patterns::{Pattern, PatternTransform};
+use crate::primitives::{Color, Matrix, Point, Tuple};
+
+// ------------------------------------------------------
+/// Blends from `a` to `b` outward from the y axis, repeating every unit of
+/// radius.
+pub struct RadialGradient {
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+    transform: PatternTransform,
+}
+
+impl RadialGradient {
+    pub fn new(a: impl Into<Box<dyn Pattern>>, b: impl Into<Box<dyn Pattern>>) -> Self {
+        RadialGradient {
+            a: a.into(),
+            b: b.into(),
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        RadialGradient {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for RadialGradient {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let a = self.a.pattern_at_object(point);
+        let b = self.b.pattern_at_object(point);
+        let radius = point.x().hypot(point.z());
+        a + (b - a) * (radius - radius.floor())
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radial_gradient_interpolates_by_radius() {
+        let pattern = RadialGradient::new(Color::white(), Color::black());
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.0, 0.0, 0.0)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.3, 5.0, 0.4)),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.0, 0.0, -1.25)),
+            Color::new(0.75, 0.75, 0.75)
+        );
+    }
+}
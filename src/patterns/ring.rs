@@ -0,0 +1,71 @@
+use crate::patterns::{Pattern, PatternTransform};
+use crate::primitives::{Color, Matrix, Point, Tuple};
+
+// ------------------------------------------------------
+/// Concentric rings around the y axis, alternating every unit of radius.
+pub struct Ring {
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+    transform: PatternTransform,
+}
+
+impl Ring {
+    pub fn new(a: impl Into<Box<dyn Pattern>>, b: impl Into<Box<dyn Pattern>>) -> Self {
+        Ring {
+            a: a.into(),
+            b: b.into(),
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        Ring {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for Ring {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let radius = point.x().hypot(point.z());
+        if (radius.floor() as i64).rem_euclid(2) == 0 {
+            self.a.pattern_at_object(point)
+        } else {
+            self.b.pattern_at_object(point)
+        }
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_extends_in_x_and_z() {
+        // Scenario: A ring should extend in both x and z
+        let pattern = Ring::new(Color::white(), Color::black());
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.0, 0.0, 0.0)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(1.0, 0.0, 0.0)),
+            Color::black()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.0, 0.0, 1.0)),
+            Color::black()
+        );
+        // 0.708 = just slightly more than √2/2
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.708, 0.0, 0.708)),
+            Color::black()
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use crate::patterns::{Pattern, PatternTransform};
+use crate::primitives::{Color, Matrix, Point};
+
+// ------------------------------------------------------
+/// A single flat color everywhere; the leaf of nested patterns.
+#[derive(Clone, Debug)]
+pub struct Solid {
+    color: Color,
+    transform: PatternTransform,
+}
+
+impl Solid {
+    pub fn new(color: Color) -> Self {
+        Solid {
+            color,
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        Solid {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for Solid {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, _point: Point) -> Color {
+        self.color
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Tuple;
+
+    #[test]
+    fn solid_is_constant() {
+        let pattern = Solid::new(Color::red());
+        assert_eq!(pattern.pattern_at(Point::new(0.0, 0.0, 0.0)), Color::red());
+        assert_eq!(
+            pattern.pattern_at(Point::new(-3.5, 7.0, 12.25)),
+            Color::red()
+        );
+    }
+}
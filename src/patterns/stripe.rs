@@ -0,0 +1,122 @@
+use crate::patterns::{Pattern, PatternTransform};
+use crate::primitives::{Color, Matrix, Point, Tuple};
+
+// ------------------------------------------------------
+/// Alternates between `a` and `b` every unit along x.
+pub struct Stripe {
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+    transform: PatternTransform,
+}
+
+impl Stripe {
+    pub fn new(a: impl Into<Box<dyn Pattern>>, b: impl Into<Box<dyn Pattern>>) -> Self {
+        Stripe {
+            a: a.into(),
+            b: b.into(),
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        Stripe {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for Stripe {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        if (point.x().floor() as i64).rem_euclid(2) == 0 {
+            self.a.pattern_at_object(point)
+        } else {
+            self.b.pattern_at_object(point)
+        }
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::pattern::tests::scaling;
+
+    fn stripe() -> Stripe {
+        Stripe::new(Color::white(), Color::black())
+    }
+
+    #[test]
+    fn stripe_constant_in_y_and_z() {
+        // Scenario: A stripe pattern is constant in y
+        // Scenario: A stripe pattern is constant in z
+        let pattern = stripe();
+        for p in [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(0.0, 2.0, 0.0),
+            Point::new(0.0, 0.0, 1.0),
+            Point::new(0.0, 0.0, 2.0),
+        ] {
+            assert_eq!(pattern.pattern_at(p), Color::white());
+        }
+    }
+
+    #[test]
+    fn stripe_alternates_in_x() {
+        // Scenario: A stripe pattern alternates in x
+        let pattern = stripe();
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.0, 0.0, 0.0)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.9, 0.0, 0.0)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(1.0, 0.0, 0.0)),
+            Color::black()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(-0.1, 0.0, 0.0)),
+            Color::black()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(-1.0, 0.0, 0.0)),
+            Color::black()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(-1.1, 0.0, 0.0)),
+            Color::white()
+        );
+    }
+
+    #[test]
+    fn stripe_with_pattern_transformation() {
+        // Scenario: Stripes with a pattern transformation
+        let pattern = stripe().with_transform(scaling(2.0, 2.0, 2.0));
+        let c = pattern.pattern_at_object(Point::new(1.5, 0.0, 0.0));
+        assert_eq!(c, Color::white());
+    }
+
+    #[test]
+    fn stripe_nested() {
+        // Stripes of stripes: the inner ones are twice as narrow.
+        let inner = Stripe::new(Color::red(), Color::blue()).with_transform(scaling(0.5, 1.0, 1.0));
+        let pattern = Stripe::new(inner, Color::black());
+        assert_eq!(pattern.pattern_at(Point::new(0.25, 0.0, 0.0)), Color::red());
+        assert_eq!(
+            pattern.pattern_at(Point::new(0.75, 0.0, 0.0)),
+            Color::blue()
+        );
+        assert_eq!(
+            pattern.pattern_at(Point::new(1.25, 0.0, 0.0)),
+            Color::black()
+        );
+    }
+}
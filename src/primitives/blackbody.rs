@@ -0,0 +1,81 @@
+use crate::primitives::{cie_xyz, Color, Float, Xyz};
+
+/// Wavelength range, in nanometres, colors of spectra are integrated over.
+const VISIBLE: std::ops::Range<usize> = 360..830;
+
+// ------------------------------------------------------
+/// Spectral radiance of a blackbody at `kelvin`, in W / (m² sr nm), at
+/// `lambda` nanometres (Planck's law).
+pub fn planck(lambda: f64, kelvin: f64) -> f64 {
+    const H: f64 = 6.626_070_15e-34;
+    const C: f64 = 299_792_458.0;
+    const K_B: f64 = 1.380_649e-23;
+
+    let l = lambda * 1.0e-9;
+    2.0 * H * C * C / (l.powi(5) * (H * C / (l * K_B * kelvin)).exp_m1()) * 1.0e-9
+}
+
+impl<T: Float> Color<T> {
+    /// Linear sRGB color of a blackbody radiator at `kelvin`, with unit
+    /// luminance. Low temperatures fall outside the sRGB gamut; their
+    /// negative components are clipped before normalizing.
+    pub fn from_temperature(kelvin: f64) -> Self {
+        if kelvin.is_nan() || kelvin <= 0.0 {
+            panic!("Invalid temperature.");
+        }
+
+        let mut xyz = [0.0; 3];
+        for lambda in VISIBLE {
+            let lambda = lambda as f64 + 0.5;
+            let radiance = planck(lambda, kelvin);
+            for (c, cmf) in xyz.iter_mut().zip(cie_xyz(lambda)) {
+                *c += radiance * cmf;
+            }
+        }
+        let [x, y, z] = xyz.map(|c| c / xyz[1]);
+
+        let rgb = Color::<f64>::from_xyz(Xyz { x, y, z });
+        let rgb = Color::new(rgb.r().max(0.0), rgb.g().max(0.0), rgb.b().max(0.0));
+        (rgb * (1.0 / rgb.luminance())).cast()
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn planck_law() {
+        // Wien's displacement law puts the peak of a 5000 K body at 579.6 nm.
+        let peak = (400..800)
+            .max_by(|&a, &b| planck(a as f64, 5000.0).total_cmp(&planck(b as f64, 5000.0)))
+            .unwrap();
+        assert!((579..=580).contains(&peak));
+        assert!(planck(550.0, 6000.0) > planck(550.0, 3000.0));
+    }
+
+    #[test]
+    fn color_from_temperature() {
+        let candle: Color = Color::from_temperature(1900.0);
+        let tungsten: Color = Color::from_temperature(2700.0);
+        let daylight: Color = Color::from_temperature(6504.0);
+        let sky: Color = Color::from_temperature(12000.0);
+
+        for color in [candle, tungsten, daylight, sky] {
+            assert_abs_diff_eq!(color.luminance(), 1.0, epsilon = 1.0e-9);
+        }
+        assert!(candle.r() > candle.g() && candle.g() > candle.b());
+        assert!(candle.b() / candle.r() < tungsten.b() / tungsten.r());
+        assert!(sky.b() > sky.g() && sky.g() > sky.r());
+        // The blackbody closest to D65 is nearly white.
+        assert_abs_diff_eq!(daylight, Color::white(), epsilon = 0.05);
+    }
+
+    #[test]
+    #[should_panic]
+    fn color_from_invalid_temperature() {
+        Color::<f64>::from_temperature(0.0);
+    }
+}
@@ -1,19 +1,19 @@
-use crate::primitives::color::Color;
+use crate::primitives::{color::Color, Float, Xyz};
 
 #[derive(Debug)]
-pub struct Canvas {
+pub struct Canvas<T: Float = f64> {
     width: usize,
     height: usize,
-    pixels: Vec<Color>,
+    pixels: Vec<Color<T>>,
 }
 
 // ------------------------------------------------------
-impl Canvas {
+impl<T: Float> Canvas<T> {
     pub fn new(width: usize, height: usize) -> Self {
         Canvas::new_with_bgcolor(width, height, Color::black())
     }
 
-    pub fn new_with_bgcolor(width: usize, height: usize, color: Color) -> Self {
+    pub fn new_with_bgcolor(width: usize, height: usize, color: Color<T>) -> Self {
         if width == 0 || height == 0 {
             panic!("Invalid canvas dimensions.");
         }
@@ -33,14 +33,41 @@ impl Canvas {
         self.height
     }
 
-    pub fn write_pixel(&mut self, x: usize, y: usize, color: Color) {
+    pub fn write_pixel(&mut self, x: usize, y: usize, color: Color<T>) {
         self[x][y] = color;
     }
 
-    pub fn pixel_at(&self, x: usize, y: usize) -> Color {
+    pub fn pixel_at(&self, x: usize, y: usize) -> Color<T> {
         self[x][y]
     }
 
+    /// Canvas as seen by a camera balanced for the illuminant color
+    /// `white`: pixels of that color become neutral grey of the same
+    /// luminance. Adaptation uses the Bradford transform.
+    pub fn white_balance(&self, white: Color<T>) -> Canvas<T> {
+        let luminance = white.luminance().as_f64();
+        if luminance.is_nan() || luminance <= 0.0 {
+            panic!("Invalid white point.");
+        }
+
+        let from = white.to_xyz();
+        let from = Xyz {
+            x: from.x / luminance,
+            y: 1.0,
+            z: from.z / luminance,
+        };
+        let to = Color::<f64>::white().to_xyz();
+        Canvas {
+            width: self.width,
+            height: self.height,
+            pixels: self
+                .pixels
+                .iter()
+                .map(|pixel| Color::from_xyz(pixel.to_xyz().adapt(from, to)))
+                .collect(),
+        }
+    }
+
     pub fn to_ppm(self) -> String {
         let mut ppm = format!("P3\n{} {}\n255\n", self.width, self.height);
 
@@ -53,7 +80,7 @@ impl Canvas {
                     let comp_str = format!("{} ", comp);
                     if line.len() + comp_str.len() > 70 {
                         line.pop();
-                        line.push_str("\n");
+                        line.push('\n');
                         ppm.push_str(&line);
                         line.clear();
                     }
@@ -64,47 +91,80 @@ impl Canvas {
                 line.pop();
             }
             ppm.push_str(&line);
-            ppm.push_str("\n");
+            ppm.push('\n');
         }
 
         ppm
     }
 }
 
-fn scale_color_components(color: Color) -> (u8, u8, u8) {
-    let r = (color.r().clamp(0.0, 1.0) * 255.0).round() as u8;
-    let g = (color.g().clamp(0.0, 1.0) * 255.0).round() as u8;
-    let b = (color.b().clamp(0.0, 1.0) * 255.0).round() as u8;
+fn scale_color_components<T: Float>(color: Color<T>) -> (u8, u8, u8) {
+    let r = (color.r().as_f64().clamp(0.0, 1.0) * 255.0).round() as u8;
+    let g = (color.g().as_f64().clamp(0.0, 1.0) * 255.0).round() as u8;
+    let b = (color.b().as_f64().clamp(0.0, 1.0) * 255.0).round() as u8;
     (r, g, b)
 }
 
 // ------------------------------------------------------
-impl std::ops::Index<usize> for Canvas {
-    type Output = [Color];
+impl<T: Float> std::ops::Index<usize> for Canvas<T> {
+    type Output = [Color<T>];
 
-    fn index(&self, row: usize) -> &[Color] {
+    fn index(&self, row: usize) -> &[Color<T>] {
         let start = row * self.height;
         // println!("Index col = {}: {:?}", row, &self.pixels[start..start + self.height]);
         &self.pixels[start..start + self.height]
     }
 }
 
-impl std::ops::IndexMut<usize> for Canvas {
-    fn index_mut(&mut self, row: usize) -> &mut [Color] {
+impl<T: Float> std::ops::IndexMut<usize> for Canvas<T> {
+    fn index_mut(&mut self, row: usize) -> &mut [Color<T>] {
         let start = row * self.height;
         // println!("IndexMut row = {}: {:?}", row, &self.pixels[start..start + self.height]);
         &mut self.pixels[start..start + self.height]
     }
 }
 
+// ------------------------------------------------------
+/// Only the canvas dimensions are serialized; pixels are written with
+/// `to_ppm`. Deserializing yields a black canvas of that size.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CanvasMetadata {
+    width: usize,
+    height: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Float> serde::Serialize for Canvas<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CanvasMetadata {
+            width: self.width,
+            height: self.height,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Float> serde::Deserialize<'de> for Canvas<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let meta = CanvasMetadata::deserialize(deserializer)?;
+        if meta.width == 0 || meta.height == 0 {
+            return Err(serde::de::Error::custom("Invalid canvas dimensions."));
+        }
+        Ok(Canvas::new(meta.width, meta.height))
+    }
+}
+
 // ------------------------------------------------------
 #[cfg(test)]
+#[allow(clippy::unused_enumerate_index)]
 mod tests {
     use super::*;
 
     #[test]
     fn canvas_construction() {
-        let canvas = Canvas::new(10, 20);
+        let canvas: Canvas = Canvas::new(10, 20);
         // println!("{:?}", c);
         assert_eq!(canvas.width, 10);
         assert_eq!(canvas.height, 20);
@@ -116,7 +176,7 @@ mod tests {
 
     #[test]
     fn canvas_ppm_header() {
-        let c = Canvas::new(5, 3);
+        let c: Canvas = Canvas::new(5, 3);
         let res = c.to_ppm();
         let v: Vec<&str> = res.split("\n").collect();
         assert_eq!(v[0], "P3");
@@ -126,7 +186,7 @@ mod tests {
 
     #[test]
     fn canves_ppm_pixel_data() {
-        let mut c = Canvas::new(5, 3);
+        let mut c: Canvas = Canvas::new(5, 3);
         let c1 = Color::new(1.5, 0.0, 0.0);
         let c2 = Color::new(0.0, 0.5, 0.0);
         let c3 = Color::new(-0.5, 0.0, 1.0);
@@ -152,28 +212,28 @@ mod tests {
 
     #[test]
     fn canvas_ppm_ends_with_newline() {
-        let c = Canvas::new(5, 3);
+        let c: Canvas = Canvas::new(5, 3);
         let ppm = c.to_ppm();
         assert!(ppm.ends_with("\n"));
     }
 
     #[test]
     fn canvas_pixel_access_by_indexer() {
-        let mut canvas = Canvas::new(10, 20);
+        let mut canvas: Canvas = Canvas::new(10, 20);
         canvas[2][3] = Color::red();
         assert_eq!(canvas[2][3], Color::red());
     }
 
     #[test]
     fn canvas_pixel_access_by_fn() {
-        let mut canvas = Canvas::new(10, 20);
+        let mut canvas: Canvas = Canvas::new(10, 20);
         canvas.write_pixel(2, 3, Color::red());
         assert_eq!(canvas.pixel_at(2, 3), Color::red());
     }
 
     #[test]
     fn canvas_pixel_access_mix() {
-        let mut canvas = Canvas::new(10, 20);
+        let mut canvas: Canvas = Canvas::new(10, 20);
 
         let w = canvas.width;
         let h = canvas.height;
@@ -215,4 +275,51 @@ mod tests {
             println!();
         }
     }
+
+    #[test]
+    fn canvas_white_balance() {
+        // A grey card and a red object lit by a 3000 K lamp.
+        let lamp: Color = Color::from_temperature(3000.0);
+        let mut canvas: Canvas = Canvas::new(2, 1);
+        canvas[0][0] = lamp * 0.5;
+        canvas[1][0] = lamp * Color::new(0.6, 0.1, 0.1);
+
+        let balanced = canvas.white_balance(lamp);
+        approx::assert_abs_diff_eq!(balanced[0][0], Color::white() * 0.5, epsilon = 1.0e-6);
+        let red = balanced[1][0];
+        assert!(red.r() > 3.0 * red.g() && red.r() > 3.0 * red.b());
+
+        // Balancing for white leaves the canvas untouched.
+        let same = canvas.white_balance(Color::white() * 2.0);
+        assert_eq!(same[1][0], canvas[1][0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn canvas_white_balance_invalid_white() {
+        let canvas: Canvas = Canvas::new(1, 1);
+        canvas.white_balance(Color::black());
+    }
+
+    #[test]
+    fn canvas_f32_framebuffer() {
+        let mut canvas: Canvas<f32> = Canvas::new(5, 3);
+        canvas[2][1] = Color::new(0.0, 0.5, 0.0);
+        let ppm = canvas.to_ppm();
+        let v: Vec<&str> = ppm.split("\n").collect();
+        assert_eq!(v[4], "0 0 0 0 0 0 0 128 0 0 0 0 0 0 0");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn canvas_serde_metadata() {
+        let c: Canvas = Canvas::new(5, 3);
+        let json = serde_json::to_string(&c).unwrap();
+        assert_eq!(json, r#"{"width":5,"height":3}"#);
+
+        let res: Canvas = serde_json::from_str(&json).unwrap();
+        assert_eq!(res.width(), 5);
+        assert_eq!(res.height(), 3);
+        assert!(serde_json::from_str::<Canvas>(r#"{"width":0,"height":3}"#).is_err());
+    }
 }
@@ -1,116 +1,203 @@
-use approx::abs_diff_eq;
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+use crate::primitives::{Float, EPSILON};
 
 #[derive(Clone, Copy, Debug)]
-pub struct Color {
-    r: f64,
-    g: f64,
-    b: f64,
+pub struct Color<T: Float = f64> {
+    r: T,
+    g: T,
+    b: T,
 }
 
 // ------------------------------------------------------
-impl Color {
-    pub fn new(r: f64, g: f64, b: f64) -> Self {
+impl<T: Float> Color<T> {
+    pub fn new(r: T, g: T, b: T) -> Self {
         Color { r, g, b }
     }
 
-    pub fn r(&self) -> f64 {
+    pub fn r(&self) -> T {
         self.r
     }
 
-    pub fn g(&self) -> f64 {
+    pub fn g(&self) -> T {
         self.g
     }
 
-    pub fn b(&self) -> f64 {
+    pub fn b(&self) -> T {
         self.b
     }
 
-    pub fn black() -> Color {
-        Color::new(0.0, 0.0, 0.0)
+    pub fn black() -> Color<T> {
+        Color::new(T::zero(), T::zero(), T::zero())
+    }
+
+    pub fn white() -> Color<T> {
+        Color::new(T::one(), T::one(), T::one())
     }
 
-    pub fn white() -> Color {
-        Color::new(1.0, 1.0, 1.0)
+    pub fn red() -> Color<T> {
+        Color::new(T::one(), T::zero(), T::zero())
     }
 
-    pub fn red() -> Color {
-        Color::new(1.0, 0.0, 0.0)
+    pub fn green() -> Color<T> {
+        Color::new(T::zero(), T::one(), T::zero())
     }
 
-    pub fn green() -> Color {
-        Color::new(0.0, 1.0, 0.0)
+    pub fn blue() -> Color<T> {
+        Color::new(T::zero(), T::zero(), T::one())
     }
 
-    pub fn blue() -> Color {
-        Color::new(0.0, 0.0, 1.0)
+    /// Whether every component is exactly zero; `==` allows [`EPSILON`].
+    pub fn is_black(&self) -> bool {
+        self.r == T::zero() && self.g == T::zero() && self.b == T::zero()
     }
 
-    pub fn add(lhs: Color, rhs: Color) -> Color {
-        Color::new(lhs.r() + rhs.r(), lhs.g() + rhs.g(), lhs.b() + rhs.b())
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(lhs: Color<T>, rhs: Color<T>) -> Color<T> {
+        lhs + rhs
     }
 
-    pub fn sub(lhs: Color, rhs: Color) -> Color {
-        Color::new(lhs.r() - rhs.r(), lhs.g() - rhs.g(), lhs.b() - rhs.b())
+    #[allow(clippy::should_implement_trait)]
+    pub fn sub(lhs: Color<T>, rhs: Color<T>) -> Color<T> {
+        lhs - rhs
     }
 
-    pub fn mul_by_scalar(color: Color, num: f64) -> Color {
+    pub fn mul_by_scalar(color: Color<T>, num: T) -> Color<T> {
         Color::new(color.r() * num, color.g() * num, color.b() * num)
     }
 
-    pub fn mul(lhs: Color, rhs: Color) -> Color {
-        Color::new(rhs.r() * lhs.r(), rhs.g() * lhs.g(), rhs.b() * lhs.b())
+    #[allow(clippy::should_implement_trait)]
+    pub fn mul(lhs: Color<T>, rhs: Color<T>) -> Color<T> {
+        lhs * rhs
+    }
+
+    fn to_array(self) -> [T; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    fn from_array([r, g, b]: [T; 3]) -> Color<T> {
+        Color { r, g, b }
+    }
+
+    pub fn cast<U: Float>(self) -> Color<U> {
+        Color::new(self.r.cast(), self.g.cast(), self.b.cast())
+    }
+}
+
+impl From<Color<f32>> for Color<f64> {
+    fn from(c: Color<f32>) -> Self {
+        c.cast()
     }
 }
 
 // ------------------------------------------------------
-impl PartialEq for Color {
-    fn eq(&self, other: &Color) -> bool {
-        abs_diff_eq!(self.r, other.r, epsilon = f64::EPSILON)
-            && abs_diff_eq!(self.g, other.g, epsilon = f64::EPSILON)
-            && abs_diff_eq!(self.b, other.b, epsilon = f64::EPSILON)
+/// Component-wise comparison within [`EPSILON`].
+impl<T: Float> PartialEq for Color<T> {
+    fn eq(&self, other: &Color<T>) -> bool {
+        self.abs_diff_eq(other, Self::default_epsilon())
+    }
+}
+
+impl<T: Float> AbsDiffEq for Color<T> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> T {
+        T::from_f64(EPSILON)
+    }
+
+    fn abs_diff_eq(&self, other: &Color<T>, epsilon: T) -> bool {
+        self.r.abs_diff_eq(&other.r, epsilon)
+            && self.g.abs_diff_eq(&other.g, epsilon)
+            && self.b.abs_diff_eq(&other.b, epsilon)
+    }
+}
+
+impl<T: Float> RelativeEq for Color<T> {
+    fn default_max_relative() -> T {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Color<T>, epsilon: T, max_relative: T) -> bool {
+        self.r.relative_eq(&other.r, epsilon, max_relative)
+            && self.g.relative_eq(&other.g, epsilon, max_relative)
+            && self.b.relative_eq(&other.b, epsilon, max_relative)
+    }
+}
+
+impl<T: Float> UlpsEq for Color<T> {
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Color<T>, epsilon: T, max_ulps: u32) -> bool {
+        self.r.ulps_eq(&other.r, epsilon, max_ulps)
+            && self.g.ulps_eq(&other.g, epsilon, max_ulps)
+            && self.b.ulps_eq(&other.b, epsilon, max_ulps)
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Add for Color {
-    type Output = Color;
+impl<T: Float> std::ops::Add for Color<T> {
+    type Output = Color<T>;
 
-    fn add(self, rhs: Color) -> Self::Output {
-        Color::add(self, rhs)
+    fn add(self, rhs: Color<T>) -> Self::Output {
+        Color::from_array(T::add3(self.to_array(), rhs.to_array()))
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Sub for Color {
-    type Output = Color;
+impl<T: Float> std::ops::Sub for Color<T> {
+    type Output = Color<T>;
 
-    fn sub(self, rhs: Color) -> Self::Output {
-        Color::sub(self, rhs)
+    fn sub(self, rhs: Color<T>) -> Self::Output {
+        Color::from_array(T::sub3(self.to_array(), rhs.to_array()))
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Mul<f64> for Color {
-    type Output = Color;
+impl<T: Float> std::ops::Mul<T> for Color<T> {
+    type Output = Color<T>;
 
-    fn mul(self, num: f64) -> Self::Output {
+    fn mul(self, num: T) -> Self::Output {
         Color::mul_by_scalar(self, num)
     }
 }
 
-impl std::ops::Mul<Color> for f64 {
-    type Output = Color;
+macro_rules! impl_scalar_mul_color {
+    ($($t:ty),*) => {$(
+        impl std::ops::Mul<Color<$t>> for $t {
+            type Output = Color<$t>;
+
+            fn mul(self, color: Color<$t>) -> Self::Output {
+                Color::mul_by_scalar(color, self)
+            }
+        }
+    )*};
+}
+
+impl_scalar_mul_color!(f32, f64);
 
-    fn mul(self, color: Color) -> Self::Output {
-        Color::mul_by_scalar(color, self)
+impl<T: Float> std::ops::Mul for Color<T> {
+    type Output = Color<T>;
+
+    fn mul(self, rhs: Color<T>) -> Self::Output {
+        Color::from_array(T::mul3(rhs.to_array(), self.to_array()))
     }
 }
 
-impl std::ops::Mul for Color {
-    type Output = Color;
+// ------------------------------------------------------
+#[cfg(feature = "serde")]
+impl<T: Float + serde::Serialize> serde::Serialize for Color<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.r, self.g, self.b].serialize(serializer)
+    }
+}
 
-    fn mul(self, rhs: Color) -> Self::Output {
-        Color::mul(self, rhs)
+#[cfg(feature = "serde")]
+impl<'de, T: Float + serde::Deserialize<'de>> serde::Deserialize<'de> for Color<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [r, g, b] = <[T; 3]>::deserialize(deserializer)?;
+        Ok(Color { r, g, b })
     }
 }
 
@@ -162,4 +249,45 @@ mod tests {
         let expected = Color::new(0.4, 0.6, 0.8);
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn color_is_black_is_exact() {
+        assert!(Color::<f64>::black().is_black());
+        assert!(Color::new(0.0, -0.0, 0.0).is_black());
+        let dim = Color::new(0.0, 1e-7, 0.0);
+        assert_eq!(dim, Color::black());
+        assert!(!dim.is_black());
+    }
+
+    #[test]
+    fn color_f32_precision() {
+        let c: Color<f32> = Color::new(0.5, 0.25, 1.0);
+        let res = c * Color::white() * 2.0;
+        assert_eq!(res, Color::new(1.0f32, 0.5, 2.0));
+
+        let wide: Color = res.into();
+        assert_eq!(wide, Color::new(1.0, 0.5, 2.0));
+    }
+
+    #[test]
+    fn color_approx_eq() {
+        let c = Color::new(0.1, 0.2, 0.3) * 3.0;
+        assert_eq!(c, Color::new(0.3, 0.6, 0.9));
+        assert_ne!(c, Color::new(0.3, 0.6, 0.9001));
+
+        approx::assert_abs_diff_eq!(c, Color::new(0.3, 0.6, 0.9001), epsilon = 1.0e-3);
+        approx::assert_ulps_eq!(c, Color::new(0.3, 0.6, 0.9), max_ulps = 4);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn color_serde_array_form() {
+        let c = Color::new(1.0, 2.0, 3.0);
+        let json = serde_json::to_string(&c).unwrap();
+        assert_eq!(json, "[1.0,2.0,3.0]");
+
+        let res: Color = serde_json::from_str("[1, 2, 3]").unwrap();
+        assert_eq!(res, c);
+        assert!(serde_json::from_str::<Color>("[1, 2]").is_err());
+    }
 }
@@ -0,0 +1,389 @@
+use std::str::FromStr;
+
+use crate::primitives::{Color, Float};
+
+// ------------------------------------------------------
+#[derive(Debug, PartialEq)]
+pub enum ColorParseError {
+    InvalidHex(String),
+    InvalidComponent(String),
+    UnknownName(String),
+}
+
+impl std::fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorParseError::InvalidHex(text) => write!(f, "invalid hex color: {}", text),
+            ColorParseError::InvalidComponent(text) => {
+                write!(f, "invalid color component: {}", text)
+            }
+            ColorParseError::UnknownName(name) => write!(f, "unknown color name: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+// ------------------------------------------------------
+impl<T: Float> Color<T> {
+    /// Parses `#rgb` or `#rrggbb`; the `#` is optional. Digits map
+    /// `0..=255` to `[0, 1]` without a transfer function, as
+    /// `Canvas::to_ppm` writes pixels; use `Color::from_srgb` to treat them
+    /// as gamma encoded.
+    pub fn from_hex(text: &str) -> Result<Self, ColorParseError> {
+        let digits = text.trim();
+        let digits = digits.strip_prefix('#').unwrap_or(digits);
+        let invalid = || ColorParseError::InvalidHex(text.to_string());
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let value = u32::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        match digits.len() {
+            3 => {
+                let nibble = |shift: u32| ((value >> shift) & 0xf) * 0x11;
+                Ok(Color::from_rgb8(nibble(8), nibble(4), nibble(0)))
+            }
+            6 => Ok(Color::from_u32(value)),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// `#rrggbb` with components clamped to `[0, 1]`.
+    pub fn to_hex(&self) -> String {
+        let byte = |c: T| (c.as_f64().clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            byte(self.r()),
+            byte(self.g()),
+            byte(self.b())
+        )
+    }
+
+    /// CSS named color, ignoring case. Note that CSS `green` is `#008000`,
+    /// unlike `Color::green()`.
+    pub fn named(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        NAMED_COLORS
+            .binary_search_by(|(key, _)| key.cmp(&name.as_str()))
+            .ok()
+            .map(|i| Color::from_u32(NAMED_COLORS[i].1))
+    }
+
+    /// The CSS named colors in alphabetical order.
+    pub fn palette() -> impl Iterator<Item = (&'static str, Self)> {
+        NAMED_COLORS
+            .iter()
+            .map(|&(name, value)| (name, Color::from_u32(value)))
+    }
+
+    fn from_u32(value: u32) -> Self {
+        Color::from_rgb8((value >> 16) & 0xff, (value >> 8) & 0xff, value & 0xff)
+    }
+
+    fn from_rgb8(r: u32, g: u32, b: u32) -> Self {
+        let c = |v: u32| T::from_f64(v as f64 / 255.0);
+        Color::new(c(r), c(g), c(b))
+    }
+}
+
+impl<T: Float> FromStr for Color<T> {
+    type Err = ColorParseError;
+
+    /// Parses hex notation, CSS `rgb()` with numbers in `0..=255` or
+    /// percentages, separated by commas or spaces, or a CSS color name.
+    /// As in `from_hex` the `#` is optional; names win over bare hex
+    /// digits. Like `from_hex`, `rgb()` components map to `[0, 1]` without
+    /// a transfer function.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let trimmed = text.trim();
+        if trimmed.starts_with('#') {
+            return Color::from_hex(trimmed);
+        }
+
+        let lower = trimmed.to_ascii_lowercase();
+        if let Some(args) = lower
+            .strip_prefix("rgb(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            let components = args
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(component)
+                .collect::<Result<Vec<f64>, _>>()?;
+            return match components[..] {
+                [r, g, b] => Ok(Color::new(T::from_f64(r), T::from_f64(g), T::from_f64(b))),
+                _ => Err(ColorParseError::InvalidComponent(text.to_string())),
+            };
+        }
+
+        if let Some(color) = Color::named(&lower) {
+            return Ok(color);
+        }
+        let hex = (trimmed.len() == 3 || trimmed.len() == 6)
+            && trimmed.chars().all(|c| c.is_ascii_hexdigit());
+        if hex {
+            return Color::from_hex(trimmed);
+        }
+
+        Err(ColorParseError::UnknownName(trimmed.to_string()))
+    }
+}
+
+/// One `rgb()` argument scaled to `[0, 1]`.
+fn component(text: &str) -> Result<f64, ColorParseError> {
+    let invalid = || ColorParseError::InvalidComponent(text.to_string());
+    let (number, max) = match text.strip_suffix('%') {
+        Some(number) => (number, 100.0),
+        None => (text, 255.0),
+    };
+    let value: f64 = number.parse().map_err(|_| invalid())?;
+    if !(0.0..=max).contains(&value) {
+        return Err(invalid());
+    }
+    Ok(value / max)
+}
+
+/// CSS Color Module Level 4 named colors, sorted by name.
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_from_hex() {
+        let c: Color = Color::from_hex("#ff8800").unwrap();
+        assert_eq!(c, Color::new(1.0, 136.0 / 255.0, 0.0));
+        assert_eq!(Color::<f64>::from_hex("F80").unwrap(), c);
+        assert_eq!(Color::<f64>::from_hex(" #FF8800 ").unwrap(), c);
+
+        for bad in ["#ff880", "#ff88000", "#gg8800", "", "#", "#+f8800"] {
+            assert_eq!(
+                Color::<f64>::from_hex(bad),
+                Err(ColorParseError::InvalidHex(bad.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn color_to_hex() {
+        assert_eq!(Color::new(1.0, 136.0 / 255.0, 0.0).to_hex(), "#ff8800");
+        assert_eq!(Color::new(2.0, -1.0, 0.5).to_hex(), "#ff0080");
+        for (_, color) in Color::<f64>::palette() {
+            assert_eq!(Color::from_hex(&color.to_hex()).unwrap(), color);
+        }
+    }
+
+    #[test]
+    fn color_from_str() {
+        let orange: Color = "#ff8800".parse().unwrap();
+        assert_eq!("rgb(255, 136, 0)".parse::<Color>().unwrap(), orange);
+        assert_eq!("RGB(255 136 0)".parse::<Color>().unwrap(), orange);
+        assert_eq!(
+            "rgb(100%, 50%, 0%)".parse::<Color>().unwrap(),
+            Color::new(1.0, 0.5, 0.0)
+        );
+        assert_eq!(
+            " CornflowerBlue ".parse::<Color>().unwrap(),
+            Color::from_hex("#6495ed").unwrap()
+        );
+        assert_eq!(
+            "rebeccapurple".parse::<Color<f32>>().unwrap(),
+            Color::from_hex("#663399").unwrap()
+        );
+    }
+
+    #[test]
+    fn color_from_str_agrees_with_from_hex_without_hash() {
+        for text in ["ff8800", " FF8800 ", "f80", "#f80"] {
+            assert_eq!(text.parse::<Color>(), Color::from_hex(text));
+        }
+        assert_eq!("f80".parse::<Color>(), "ff8800".parse::<Color>());
+    }
+
+    #[test]
+    fn color_from_str_errors() {
+        assert_eq!(
+            "rgb(256, 0, 0)".parse::<Color>(),
+            Err(ColorParseError::InvalidComponent("256".to_string()))
+        );
+        assert_eq!(
+            "rgb(1, 2)".parse::<Color>(),
+            Err(ColorParseError::InvalidComponent("rgb(1, 2)".to_string()))
+        );
+        assert_eq!(
+            "blurple".parse::<Color>(),
+            Err(ColorParseError::UnknownName("blurple".to_string()))
+        );
+        assert_eq!(
+            "ff880".parse::<Color>(),
+            Err(ColorParseError::UnknownName("ff880".to_string()))
+        );
+        let err = "#12".parse::<Color>().unwrap_err();
+        assert_eq!(err.to_string(), "invalid hex color: #12");
+    }
+
+    #[test]
+    fn color_palette() {
+        assert_eq!(Color::<f64>::palette().count(), 148);
+        assert!(NAMED_COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(Color::<f64>::named("red"), Some(Color::red()));
+        assert_eq!(
+            Color::named("green"),
+            Some(Color::new(0.0, 128.0 / 255.0, 0.0))
+        );
+        assert_eq!(Color::<f64>::named("grey"), Color::named("gray"));
+        assert_eq!(Color::<f64>::named("nope"), None);
+    }
+}
@@ -0,0 +1,523 @@
+use crate::primitives::{Color, Float};
+
+// ------------------------------------------------------
+/// Hue in degrees `[0, 360)`, saturation and value in `[0, 1]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsv {
+    pub h: f64,
+    pub s: f64,
+    pub v: f64,
+}
+
+/// Hue in degrees `[0, 360)`, saturation and lightness in `[0, 1]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsl {
+    pub h: f64,
+    pub s: f64,
+    pub l: f64,
+}
+
+/// CIE 1931 tristimulus values, with `y = 1` for the white of linear sRGB.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Xyz {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// CIE 1976 L*a*b* relative to the D65 white point, with `l` in `[0, 100]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+/// Oklab perceptual color space (Ottosson 2020), with `l` in `[0, 1]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+/// D65 white point, as used by sRGB.
+const WHITE: Xyz = Xyz {
+    x: 0.95047,
+    y: 1.0,
+    z: 1.08883,
+};
+
+// ------------------------------------------------------
+/// Conversions treat the color as linear sRGB, the space the renderer works
+/// in, except for HSV and HSL which act on the components as stored.
+impl<T: Float> Color<T> {
+    fn to_f64(self) -> [f64; 3] {
+        [self.r().as_f64(), self.g().as_f64(), self.b().as_f64()]
+    }
+
+    fn from_f64([r, g, b]: [f64; 3]) -> Self {
+        Color::new(T::from_f64(r), T::from_f64(g), T::from_f64(b))
+    }
+
+    /// Relative luminance, the `y` of `to_xyz`.
+    pub fn luminance(&self) -> T {
+        let [r, g, b] = self.to_f64();
+        T::from_f64(0.2126729 * r + 0.7151522 * g + 0.0721750 * b)
+    }
+
+    /// Gamma encoded sRGB components, as stored in 8-bit images.
+    pub fn to_srgb(&self) -> Self {
+        Color::from_f64(self.to_f64().map(|c| {
+            let a = c.abs();
+            let encoded = if a <= 0.0031308 {
+                12.92 * a
+            } else {
+                1.055 * a.powf(1.0 / 2.4) - 0.055
+            };
+            encoded.copysign(c)
+        }))
+    }
+
+    /// Linear color of gamma encoded sRGB components.
+    pub fn from_srgb(srgb: Color<T>) -> Self {
+        Color::from_f64(srgb.to_f64().map(|c| {
+            let a = c.abs();
+            let linear = if a <= 0.04045 {
+                a / 12.92
+            } else {
+                ((a + 0.055) / 1.055).powf(2.4)
+            };
+            linear.copysign(c)
+        }))
+    }
+
+    pub fn to_hsv(&self) -> Hsv {
+        let [r, g, b] = self.to_f64();
+        let max = r.max(g).max(b);
+        let chroma = max - r.min(g).min(b);
+        Hsv {
+            h: hue(r, g, b, max, chroma),
+            s: if max > 0.0 { chroma / max } else { 0.0 },
+            v: max,
+        }
+    }
+
+    pub fn from_hsv(hsv: Hsv) -> Self {
+        let chroma = hsv.v * hsv.s;
+        Color::from_f64(from_hue(hsv.h, chroma).map(|c| c + hsv.v - chroma))
+    }
+
+    pub fn to_hsl(&self) -> Hsl {
+        let [r, g, b] = self.to_f64();
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let chroma = max - min;
+        let l = 0.5 * (max + min);
+        let s = if l > 0.0 && l < 1.0 {
+            chroma / (1.0 - (2.0 * l - 1.0).abs())
+        } else {
+            0.0
+        };
+        Hsl {
+            h: hue(r, g, b, max, chroma),
+            s,
+            l,
+        }
+    }
+
+    pub fn from_hsl(hsl: Hsl) -> Self {
+        let chroma = (1.0 - (2.0 * hsl.l - 1.0).abs()) * hsl.s;
+        Color::from_f64(from_hue(hsl.h, chroma).map(|c| c + hsl.l - 0.5 * chroma))
+    }
+
+    pub fn to_xyz(&self) -> Xyz {
+        let [r, g, b] = self.to_f64();
+        Xyz {
+            x: 0.4124564 * r + 0.3575761 * g + 0.1804375 * b,
+            y: 0.2126729 * r + 0.7151522 * g + 0.0721750 * b,
+            z: 0.0193339 * r + 0.1191920 * g + 0.9503041 * b,
+        }
+    }
+
+    pub fn from_xyz(xyz: Xyz) -> Self {
+        let Xyz { x, y, z } = xyz;
+        Color::from_f64([
+            3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+            -0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+            0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+        ])
+    }
+
+    pub fn to_lab(&self) -> Lab {
+        let xyz = self.to_xyz();
+        let f = |t: f64| {
+            const DELTA: f64 = 6.0 / 29.0;
+            if t > DELTA * DELTA * DELTA {
+                t.cbrt()
+            } else {
+                t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+            }
+        };
+        let (fx, fy, fz) = (f(xyz.x / WHITE.x), f(xyz.y / WHITE.y), f(xyz.z / WHITE.z));
+        Lab {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+
+    pub fn from_lab(lab: Lab) -> Self {
+        let f_inv = |t: f64| {
+            const DELTA: f64 = 6.0 / 29.0;
+            if t > DELTA {
+                t * t * t
+            } else {
+                3.0 * DELTA * DELTA * (t - 4.0 / 29.0)
+            }
+        };
+        let fy = (lab.l + 16.0) / 116.0;
+        Color::from_xyz(Xyz {
+            x: WHITE.x * f_inv(fy + lab.a / 500.0),
+            y: WHITE.y * f_inv(fy),
+            z: WHITE.z * f_inv(fy - lab.b / 200.0),
+        })
+    }
+
+    pub fn to_oklab(&self) -> Oklab {
+        let [r, g, b] = self.to_f64();
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        Oklab {
+            l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        }
+    }
+
+    pub fn from_oklab(oklab: Oklab) -> Self {
+        let Oklab { l, a, b } = oklab;
+        let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+        Color::from_f64([
+            4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+            -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+            -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+        ])
+    }
+
+    /// Perceptual difference to `other` by the CIEDE2000 formula; about 1
+    /// is the smallest difference most observers notice.
+    pub fn delta_e(&self, other: &Color<T>) -> f64 {
+        self.to_lab().delta_e(&other.to_lab())
+    }
+}
+
+/// CIE 1931 2° color matching functions `[x, y, z]` at `lambda`
+/// nanometres, using the multi-lobe fit of Wyman, Sloan and Shirley (2013).
+pub fn cie_xyz(lambda: f64) -> [f64; 3] {
+    let g = |mu: f64, below: f64, above: f64| {
+        let t = (lambda - mu) / if lambda < mu { below } else { above };
+        (-0.5 * t * t).exp()
+    };
+    [
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    ]
+}
+
+/// Hue in degrees of a color with the given largest component and chroma.
+fn hue(r: f64, g: f64, b: f64, max: f64, chroma: f64) -> f64 {
+    if chroma == 0.0 {
+        return 0.0;
+    }
+    let sector = if max == r {
+        ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    60.0 * sector
+}
+
+/// Components of a color with `hue` and `chroma` and the smallest
+/// component zero.
+fn from_hue(hue: f64, chroma: f64) -> [f64; 3] {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+    match sector as usize {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    }
+}
+
+// ------------------------------------------------------
+impl Xyz {
+    /// Corresponding color under the white point `to` of a color seen
+    /// under `from`, by the Bradford transform.
+    pub fn adapt(self, from: Xyz, to: Xyz) -> Xyz {
+        #[rustfmt::skip]
+        const BRADFORD: [[f64; 3]; 3] = [
+            [0.8951, 0.2664, -0.1614],
+            [-0.7502, 1.7135, 0.0367],
+            [0.0389, -0.0685, 1.0296],
+        ];
+        #[rustfmt::skip]
+        const INVERSE: [[f64; 3]; 3] = [
+            [0.9869929, -0.1470543, 0.1599627],
+            [0.4323053, 0.5183603, 0.0492912],
+            [-0.0085287, 0.0400428, 0.9684867],
+        ];
+        let mul = |m: [[f64; 3]; 3], v: [f64; 3]| {
+            m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+        };
+
+        let (src, dst) = (mul(BRADFORD, from.into()), mul(BRADFORD, to.into()));
+        let lms = mul(BRADFORD, self.into());
+        let [x, y, z] = mul(INVERSE, [0, 1, 2].map(|i| lms[i] * dst[i] / src[i]));
+        Xyz { x, y, z }
+    }
+}
+
+impl From<Xyz> for [f64; 3] {
+    fn from(xyz: Xyz) -> Self {
+        [xyz.x, xyz.y, xyz.z]
+    }
+}
+
+// ------------------------------------------------------
+impl Lab {
+    /// CIEDE2000 color difference (Sharma, Wu and Dalal 2005).
+    pub fn delta_e(&self, other: &Lab) -> f64 {
+        let pow7 = |c: f64| c.powi(7);
+        let rad = f64::to_radians;
+
+        let c_bar = 0.5 * (self.a.hypot(self.b) + other.a.hypot(other.b));
+        let g = 0.5 * (1.0 - (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt());
+        let prime = |lab: &Lab| {
+            let a = (1.0 + g) * lab.a;
+            let c = a.hypot(lab.b);
+            let h = if c == 0.0 {
+                0.0
+            } else {
+                lab.b.atan2(a).to_degrees().rem_euclid(360.0)
+            };
+            (c, h)
+        };
+        let (c1, h1) = prime(self);
+        let (c2, h2) = prime(other);
+
+        let dl = other.l - self.l;
+        let dc = c2 - c1;
+        let dh = if c1 * c2 == 0.0 {
+            0.0
+        } else if (h2 - h1).abs() <= 180.0 {
+            h2 - h1
+        } else if h2 > h1 {
+            h2 - h1 - 360.0
+        } else {
+            h2 - h1 + 360.0
+        };
+        let dh = 2.0 * (c1 * c2).sqrt() * rad(0.5 * dh).sin();
+
+        let l_bar = 0.5 * (self.l + other.l);
+        let c_bar = 0.5 * (c1 + c2);
+        let h_bar = if c1 * c2 == 0.0 {
+            h1 + h2
+        } else if (h1 - h2).abs() <= 180.0 {
+            0.5 * (h1 + h2)
+        } else if h1 + h2 < 360.0 {
+            0.5 * (h1 + h2 + 360.0)
+        } else {
+            0.5 * (h1 + h2 - 360.0)
+        };
+
+        let t = 1.0 - 0.17 * rad(h_bar - 30.0).cos()
+            + 0.24 * rad(2.0 * h_bar).cos()
+            + 0.32 * rad(3.0 * h_bar + 6.0).cos()
+            - 0.20 * rad(4.0 * h_bar - 63.0).cos();
+        let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+        let r_c = 2.0 * (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt();
+        let l50 = (l_bar - 50.0).powi(2);
+        let s_l = 1.0 + 0.015 * l50 / (20.0 + l50).sqrt();
+        let s_c = 1.0 + 0.045 * c_bar;
+        let s_h = 1.0 + 0.015 * c_bar * t;
+        let r_t = -rad(2.0 * d_theta).sin() * r_c;
+
+        let (l, c, h) = (dl / s_l, dc / s_c, dh / s_h);
+        (l * l + c * c + h * h + r_t * c * h).sqrt()
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn samples() -> [Color; 6] {
+        [
+            Color::new(0.2, 0.4, 0.6),
+            Color::new(0.9, 0.1, 0.3),
+            Color::new(0.05, 0.8, 0.05),
+            Color::new(0.5, 0.5, 0.5),
+            Color::white(),
+            Color::black(),
+        ]
+    }
+
+    #[test]
+    fn color_hsv() {
+        let hsv = Color::new(1.0, 0.5, 0.0).to_hsv();
+        assert_abs_diff_eq!(hsv.h, 30.0);
+        assert_abs_diff_eq!(hsv.s, 1.0);
+        assert_abs_diff_eq!(hsv.v, 1.0);
+        assert_eq!(Color::<f64>::blue().to_hsv().h, 240.0);
+        assert_eq!(Color::new(1.0, 0.0, 0.5).to_hsv().h, 330.0);
+
+        for color in samples() {
+            assert_eq!(Color::from_hsv(color.to_hsv()), color);
+        }
+    }
+
+    #[test]
+    fn color_hsl() {
+        let hsl = Color::new(0.25, 0.75, 0.25).to_hsl();
+        assert_abs_diff_eq!(hsl.h, 120.0);
+        assert_abs_diff_eq!(hsl.s, 0.5);
+        assert_abs_diff_eq!(hsl.l, 0.5);
+        assert_eq!(Color::<f64>::white().to_hsl().s, 0.0);
+
+        for color in samples() {
+            assert_eq!(Color::from_hsl(color.to_hsl()), color);
+        }
+    }
+
+    #[test]
+    fn color_srgb_transfer() {
+        let srgb = Color::new(0.5, 0.0, 1.0).to_srgb();
+        assert_abs_diff_eq!(srgb, Color::new(0.735357, 0.0, 1.0), epsilon = 1.0e-6);
+        assert_abs_diff_eq!(
+            Color::from_srgb(srgb),
+            Color::new(0.5, 0.0, 1.0),
+            epsilon = 1.0e-12
+        );
+        // The linear segment near black.
+        assert_abs_diff_eq!(Color::new(0.001, 0.001, 0.001).to_srgb().r(), 0.01292);
+    }
+
+    #[test]
+    fn color_xyz_and_luminance() {
+        let white = Color::<f64>::white().to_xyz();
+        assert_abs_diff_eq!(white.x, WHITE.x, epsilon = 1.0e-4);
+        assert_abs_diff_eq!(white.y, 1.0, epsilon = 1.0e-6);
+        assert_abs_diff_eq!(white.z, WHITE.z, epsilon = 1.0e-4);
+        assert_abs_diff_eq!(Color::<f64>::green().luminance(), 0.7151522);
+
+        for color in samples() {
+            assert_abs_diff_eq!(Color::from_xyz(color.to_xyz()), color, epsilon = 1.0e-6);
+            assert_abs_diff_eq!(color.luminance(), color.to_xyz().y);
+        }
+    }
+
+    #[test]
+    fn color_lab() {
+        let white = Color::<f64>::white().to_lab();
+        assert_abs_diff_eq!(white.l, 100.0, epsilon = 1.0e-3);
+        assert_abs_diff_eq!(white.a, 0.0, epsilon = 1.0e-2);
+        assert_abs_diff_eq!(white.b, 0.0, epsilon = 1.0e-2);
+
+        // sRGB red is L* 53.24, a* 80.09, b* 67.20.
+        let red = Color::<f64>::red().to_lab();
+        assert_abs_diff_eq!(red.l, 53.24, epsilon = 0.01);
+        assert_abs_diff_eq!(red.a, 80.09, epsilon = 0.02);
+        assert_abs_diff_eq!(red.b, 67.20, epsilon = 0.02);
+
+        for color in samples() {
+            assert_abs_diff_eq!(Color::from_lab(color.to_lab()), color, epsilon = 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn color_oklab() {
+        let white = Color::<f64>::white().to_oklab();
+        assert_abs_diff_eq!(white.l, 1.0, epsilon = 1.0e-6);
+        assert_abs_diff_eq!(white.a, 0.0, epsilon = 1.0e-6);
+
+        // Reference value from Ottosson's article.
+        let red = Color::<f64>::red().to_oklab();
+        assert_abs_diff_eq!(red.l, 0.627955, epsilon = 1.0e-5);
+        assert_abs_diff_eq!(red.a, 0.224863, epsilon = 1.0e-5);
+        assert_abs_diff_eq!(red.b, 0.125846, epsilon = 1.0e-5);
+
+        for color in samples() {
+            assert_abs_diff_eq!(Color::from_oklab(color.to_oklab()), color, epsilon = 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn color_f32_conversions() {
+        let c: Color<f32> = Color::new(0.2, 0.4, 0.6);
+        assert_abs_diff_eq!(Color::<f32>::from_hsv(c.to_hsv()), c, epsilon = 1.0e-6);
+        assert!(c.luminance() > 0.0);
+    }
+
+    #[test]
+    fn xyz_bradford_adaptation() {
+        let a = Xyz {
+            x: 1.09850,
+            y: 1.0,
+            z: 0.35585,
+        };
+        let d65 = Color::<f64>::white().to_xyz();
+        let res = a.adapt(a, d65);
+        assert_abs_diff_eq!(res.x, d65.x, epsilon = 1.0e-6);
+        assert_abs_diff_eq!(res.y, d65.y, epsilon = 1.0e-6);
+        assert_abs_diff_eq!(res.z, d65.z, epsilon = 1.0e-6);
+
+        let c = Color::new(0.2, 0.5, 0.3);
+        let same = Color::from_xyz(c.to_xyz().adapt(d65, d65));
+        assert_abs_diff_eq!(same, c, epsilon = 1.0e-6);
+    }
+
+    #[test]
+    fn lab_delta_e_2000() {
+        // Test data of Sharma, Wu and Dalal.
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, 3.1571, -77.2803), (50.0, 0.0, -82.7485), 2.8615),
+            ((50.0, -1.0, 2.0), (50.0, 0.0, 0.0), 2.3669),
+            ((50.0, 2.49, -0.001), (50.0, -2.49, 0.0009), 7.1792),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+        ];
+        for ((l1, a1, b1), (l2, a2, b2), expected) in pairs {
+            let lab1 = Lab {
+                l: l1,
+                a: a1,
+                b: b1,
+            };
+            let lab2 = Lab {
+                l: l2,
+                a: a2,
+                b: b2,
+            };
+            assert_abs_diff_eq!(lab1.delta_e(&lab2), expected, epsilon = 1.0e-4);
+            assert_abs_diff_eq!(lab2.delta_e(&lab1), expected, epsilon = 1.0e-4);
+        }
+    }
+
+    #[test]
+    fn color_delta_e() {
+        let c = Color::new(0.3, 0.5, 0.7);
+        assert_eq!(c.delta_e(&c), 0.0);
+        assert!(c.delta_e(&Color::new(0.301, 0.5, 0.7)) < 1.0);
+        assert!(c.delta_e(&Color::new(0.5, 0.5, 0.7)) > 5.0);
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt::{Debug, Display};
+
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+use super::simd::SimdKernels;
+
+// ------------------------------------------------------
+/// Absolute tolerance used by `PartialEq` on every primitive, and the
+/// default epsilon of their `approx` implementations.
+///
+/// `==` always compares against this value. For a different tolerance, use
+/// the `approx` traits (`abs_diff_eq`, `relative_eq`, `ulps_eq`) directly.
+pub const EPSILON: f64 = 1.0e-5;
+
+// ------------------------------------------------------
+/// Scalar type the primitives are generic over.
+///
+/// Implemented for `f32` (compact meshes and framebuffers) and `f64`
+/// (reference renders, the default everywhere).
+pub trait Float:
+    num_traits::Float
+    + SimdKernels
+    + AbsDiffEq<Epsilon = Self>
+    + RelativeEq
+    + UlpsEq
+    + Default
+    + Debug
+    + Display
+    + Send
+    + Sync
+    + 'static
+{
+    fn from_f64(value: f64) -> Self;
+    fn as_f64(self) -> f64;
+
+    fn cast<U: Float>(self) -> U {
+        U::from_f64(self.as_f64())
+    }
+}
+
+// ------------------------------------------------------
+impl Float for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Float for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_cast_between_precisions() {
+        let x: f64 = 0.5;
+        let y: f32 = x.cast();
+        assert_eq!(y, 0.5f32);
+        assert_eq!(y.cast::<f64>(), 0.5);
+    }
+}
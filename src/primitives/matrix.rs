@@ -1,17 +1,17 @@
-use approx::abs_diff_eq;
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
 
-use super::Tuple;
+use super::{Float, Tuple, EPSILON};
 
-#[derive(Debug)]
-pub struct Matrix {
+#[derive(Clone, Debug)]
+pub struct Matrix<T: Float = f64> {
     width: usize,
     height: usize,
-    data: Vec<f64>,
+    data: Vec<T>,
 }
 
 // ------------------------------------------------------
-impl Matrix {
-    pub fn new(width: usize, height: usize, default_value: f64) -> Self {
+impl<T: Float> Matrix<T> {
+    pub fn new(width: usize, height: usize, default_value: T) -> Self {
         Matrix {
             width,
             height,
@@ -20,27 +20,27 @@ impl Matrix {
     }
 
     pub fn new2() -> Self {
-        Self::new(2, 2, 0.0)
+        Self::new(2, 2, T::zero())
     }
 
     pub fn new3() -> Self {
-        Self::new(3, 3, 0.0)
+        Self::new(3, 3, T::zero())
     }
 
     pub fn new4() -> Self {
-        Self::new(4, 4, 0.0)
+        Self::new(4, 4, T::zero())
     }
 
-    pub fn identity() -> Matrix {
+    pub fn identity() -> Matrix<T> {
         let mut m = Self::new4();
-        m[0][0] = 1.0;
-        m[1][1] = 1.0;
-        m[2][2] = 1.0;
-        m[3][3] = 1.0;
+        m[0][0] = T::one();
+        m[1][1] = T::one();
+        m[2][2] = T::one();
+        m[3][3] = T::one();
         m
     }
 
-    pub fn init(self, vals: Vec<f64>) -> Self {
+    pub fn init(self, vals: Vec<T>) -> Self {
         Matrix { data: vals, ..self }
     }
 
@@ -52,8 +52,8 @@ impl Matrix {
         self.height
     }
 
-    pub fn transpose(&self) -> Matrix {
-        let mut result = Matrix::new(self.width, self.height, 0.0);
+    pub fn transpose(&self) -> Matrix<T> {
+        let mut result = Matrix::new(self.width, self.height, T::zero());
         for row in 0..self.width {
             for col in 0..self.height {
                 result[row][col] = self[col][row];
@@ -62,20 +62,20 @@ impl Matrix {
         result
     }
 
-    pub fn determinant(&self) -> f64 {
+    pub fn determinant(&self) -> T {
         if self.width == 2 {
             return self[0][0] * self[1][1] - self[0][1] * self[1][0];
         }
 
-        let mut det: f64 = 0.0;
+        let mut det = T::zero();
         for col in 0..self.width {
-            det += self[0][col] * self.cofactor(0, col);
+            det = det + self[0][col] * self.cofactor(0, col);
         }
         det
     }
 
-    pub fn sub_matrix(&self, row: usize, col: usize) -> Matrix {
-        let mut result = Matrix::new(self.width - 1, self.height - 1, 0.0);
+    pub fn sub_matrix(&self, row: usize, col: usize) -> Matrix<T> {
+        let mut result = Matrix::new(self.width - 1, self.height - 1, T::zero());
         let mut rd: usize = 0;
         for r in 0..self.width {
             if r == row {
@@ -97,11 +97,13 @@ impl Matrix {
         result
     }
 
-    pub fn minor(&self, row: usize, col: usize) -> f64 {
+    pub fn minor(&self, row: usize, col: usize) -> T {
         self.sub_matrix(row, col).determinant()
     }
 
-    pub fn cofactor(&self, row: usize, col: usize) -> f64 {
+    // `is_multiple_of` needs Rust 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn cofactor(&self, row: usize, col: usize) -> T {
         let m = self.minor(row, col);
         if (row + col) % 2 == 0 {
             m
@@ -111,15 +113,15 @@ impl Matrix {
     }
 
     pub fn is_invertible(&self) -> bool {
-        self.determinant() != 0.0
+        self.determinant() != T::zero()
     }
 
-    pub fn inverse(&self) -> Option<Matrix> {
+    pub fn inverse(&self) -> Option<Matrix<T>> {
         if !self.is_invertible() {
             return None;
         }
 
-        let mut m2 = Matrix::new(self.width, self.height, 0.0);
+        let mut m2 = Matrix::new(self.width, self.height, T::zero());
         let det = self.determinant();
 
         for row in 0..self.width {
@@ -133,47 +135,93 @@ impl Matrix {
         }
         Some(m2)
     }
+
+    pub fn cast<U: Float>(&self) -> Matrix<U> {
+        Matrix {
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().map(|v| v.cast()).collect(),
+        }
+    }
+}
+
+impl From<Matrix<f32>> for Matrix<f64> {
+    fn from(m: Matrix<f32>) -> Self {
+        m.cast()
+    }
 }
 
 // ------------------------------------------------------
-impl PartialEq for Matrix {
-    fn eq(&self, other: &Matrix) -> bool {
-        if self.width == other.width && self.height == other.height {
-            for idx in 0..self.data.len() {
-                // Lower precision compare, setting epsilon.
-                if !abs_diff_eq!(self.data[idx], other.data[idx], epsilon = 1.0e-3) {
-                    return false;
-                }
-            }
-            return true;
-        }
-        return false;
+/// Same dimensions and element-wise comparison within [`EPSILON`].
+impl<T: Float> PartialEq for Matrix<T> {
+    fn eq(&self, other: &Matrix<T>) -> bool {
+        self.abs_diff_eq(other, Self::default_epsilon())
+    }
+}
+
+impl<T: Float> Matrix<T> {
+    fn all_elements(&self, other: &Matrix<T>, eq: impl Fn(&T, &T) -> bool) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.data.iter().zip(&other.data).all(|(a, b)| eq(a, b))
+    }
+}
+
+impl<T: Float> AbsDiffEq for Matrix<T> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> T {
+        T::from_f64(EPSILON)
+    }
+
+    fn abs_diff_eq(&self, other: &Matrix<T>, epsilon: T) -> bool {
+        self.all_elements(other, |a, b| a.abs_diff_eq(b, epsilon))
+    }
+}
+
+impl<T: Float> RelativeEq for Matrix<T> {
+    fn default_max_relative() -> T {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Matrix<T>, epsilon: T, max_relative: T) -> bool {
+        self.all_elements(other, |a, b| a.relative_eq(b, epsilon, max_relative))
+    }
+}
+
+impl<T: Float> UlpsEq for Matrix<T> {
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Matrix<T>, epsilon: T, max_ulps: u32) -> bool {
+        self.all_elements(other, |a, b| a.ulps_eq(b, epsilon, max_ulps))
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Index<usize> for Matrix {
-    type Output = [f64];
+impl<T: Float> std::ops::Index<usize> for Matrix<T> {
+    type Output = [T];
 
-    fn index(&self, row: usize) -> &[f64] {
+    fn index(&self, row: usize) -> &[T] {
         let start = row * self.width;
         &self.data[start..start + self.width]
     }
 }
 
-impl std::ops::IndexMut<usize> for Matrix {
-    fn index_mut(&mut self, row: usize) -> &mut [f64] {
+impl<T: Float> std::ops::IndexMut<usize> for Matrix<T> {
+    fn index_mut(&mut self, row: usize) -> &mut [T] {
         let start = row * self.width;
         &mut self.data[start..start + self.width]
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Mul for Matrix {
-    type Output = Matrix;
+impl<T: Float> std::ops::Mul for Matrix<T> {
+    type Output = Matrix<T>;
 
-    fn mul(self, rhs: Matrix) -> Self::Output {
-        let mut result = Matrix::new(self.width, self.height, 0.0);
+    fn mul(self, rhs: Matrix<T>) -> Self::Output {
+        let mut result = Matrix::new(self.width, self.height, T::zero());
 
         for row in 0..self.width {
             for col in 0..self.height {
@@ -188,32 +236,68 @@ impl std::ops::Mul for Matrix {
     }
 }
 
-impl<T> std::ops::Mul<T> for Matrix
+impl<T, P> std::ops::Mul<P> for Matrix<T>
+where
+    T: Float,
+    P: Tuple<Scalar = T>,
+{
+    type Output = P;
+
+    fn mul(self, rhs: P) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl<T, P> std::ops::Mul<P> for &Matrix<T>
 where
-    T: Tuple,
+    T: Float,
+    P: Tuple<Scalar = T>,
 {
-    type Output = T;
-
-    fn mul(self, rhs: T) -> Self::Output {
-        Self::Output::new(
-            self[0][0] * rhs.x()
-                + self[0][1] * rhs.y()
-                + self[0][2] * rhs.z()
-                + self[0][3] * rhs.w(),
-            self[1][0] * rhs.x()
-                + self[1][1] * rhs.y()
-                + self[1][2] * rhs.z()
-                + self[1][3] * rhs.w(),
-            self[2][0] * rhs.x()
-                + self[2][1] * rhs.y()
-                + self[2][2] * rhs.z()
-                + self[2][3] * rhs.w(),
-        )
+    type Output = P;
+
+    fn mul(self, rhs: P) -> Self::Output {
+        let [x, y, z] = T::mat4_mul_tuple(&self.data, [rhs.x(), rhs.y(), rhs.z(), rhs.w()]);
+        Self::Output::new(x, y, z)
+    }
+}
+
+// ------------------------------------------------------
+/// Serialized as a list of rows, e.g. `[[1, 0], [0, 1]]`.
+#[cfg(feature = "serde")]
+impl<T: Float + serde::Serialize> serde::Serialize for Matrix<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let mut seq = serializer.serialize_seq(Some(self.height))?;
+        for row in 0..self.height {
+            seq.serialize_element(&self.data[row * self.width..(row + 1) * self.width])?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Float + serde::Deserialize<'de>> serde::Deserialize<'de> for Matrix<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<Vec<T>>::deserialize(deserializer)?;
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(serde::de::Error::custom(
+                "matrix rows must be of equal length",
+            ));
+        }
+
+        Ok(Matrix {
+            width,
+            height: rows.len(),
+            data: rows.concat(),
+        })
     }
 }
 
 // ------------------------------------------------------
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::assertions_on_constants)]
 mod tests {
     use crate::primitives::{Point, Tuple};
 
@@ -358,6 +442,14 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn matrix_ref_mult_tuple() {
+        let m = Matrix::identity();
+        let p = Point::new(1.0, 2.0, 3.0);
+        assert_eq!(&m * p, p);
+        assert_eq!(m.clone() * p, &m * p);
+    }
+
     #[test]
     fn matrix_mult_by_identity() {
         #[rustfmt::skip]
@@ -668,4 +760,78 @@ mod tests {
             None => assert!(false),
         }
     }
+
+    #[test]
+    fn matrix_f32_precision() {
+        #[rustfmt::skip]
+        let a: Matrix<f32> = Matrix::new4().init([
+            1.0, 2.0, 3.0, 4.0,
+            2.0, 4.0, 4.0, 2.0,
+            8.0, 6.0, 4.0, 1.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+
+        let p = Point::new(1.0f32, 2.0, 3.0);
+        assert_eq!(a.cast::<f64>() * p.cast(), Point::new(18.0, 24.0, 33.0));
+        assert_eq!(a * p, Point::new(18.0f32, 24.0, 33.0));
+    }
+
+    #[test]
+    fn matrix_approx_eq() {
+        let angle = std::f64::consts::FRAC_PI_2;
+        #[rustfmt::skip]
+        let rotation = Matrix::new4().init([
+            1.0, 0.0, 0.0, 0.0,
+            0.0, angle.cos(), -angle.sin(), 0.0,
+            0.0, angle.sin(), angle.cos(), 0.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+
+        #[rustfmt::skip]
+        let expected = Matrix::new4().init([
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, -1.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+
+        assert_eq!(rotation, expected);
+        approx::assert_ulps_eq!(rotation, expected);
+
+        let mut coarse = Matrix::identity();
+        coarse[0][0] = 1.0005;
+        assert_ne!(coarse, Matrix::identity());
+        approx::assert_abs_diff_eq!(coarse, Matrix::identity(), epsilon = 1.0e-3);
+        assert_ne!(Matrix::new2(), Matrix::<f64>::new3());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn matrix_serde_rows() {
+        #[rustfmt::skip]
+        let m = Matrix::new2().init([
+            -3.0, 5.0,
+            1.0, -2.0
+        ].to_vec());
+
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, "[[-3.0,5.0],[1.0,-2.0]]");
+
+        let res: Matrix = serde_json::from_str("[[-3, 5], [1, -2]]").unwrap();
+        assert_eq!(res, m);
+        assert!(serde_json::from_str::<Matrix>("[[1, 2], [3]]").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn matrix_serde_empty() {
+        for (width, height, json) in [(0, 0, "[]"), (0, 2, "[[],[]]")] {
+            let m = Matrix::new(width, height, 0.0);
+            assert_eq!(serde_json::to_string(&m).unwrap(), json);
+
+            let res: Matrix = serde_json::from_str(json).unwrap();
+            assert_eq!((res.width, res.height), (width, height));
+            assert_eq!(res, m);
+        }
+    }
 }
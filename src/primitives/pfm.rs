@@ -0,0 +1,161 @@
+use crate::primitives::ppm::Reader;
+use crate::primitives::{Canvas, Color, Float};
+
+// ------------------------------------------------------
+#[derive(Debug, PartialEq)]
+pub enum PfmError {
+    UnsupportedFormat,
+    MissingHeader,
+    InvalidNumber(String),
+    InvalidDimensions,
+    MissingPixelData,
+}
+
+impl std::fmt::Display for PfmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PfmError::UnsupportedFormat => write!(f, "only PF and Pf PFM files are supported"),
+            PfmError::MissingHeader => write!(f, "PFM header is incomplete"),
+            PfmError::InvalidNumber(token) => write!(f, "invalid number in PFM: {}", token),
+            PfmError::InvalidDimensions => {
+                write!(f, "PFM dimensions or scale are zero or too large")
+            }
+            PfmError::MissingPixelData => write!(f, "PFM has fewer pixels than its header says"),
+        }
+    }
+}
+
+impl std::error::Error for PfmError {}
+
+// ------------------------------------------------------
+impl<T: Float> Canvas<T> {
+    /// Reads a color (`PF`) or grayscale (`Pf`) portable float map, the
+    /// usual exchange format for high dynamic range images such as light
+    /// probes. Samples keep their range, scaled by the magnitude of the
+    /// header's scale; its sign gives the byte order.
+    pub fn from_pfm(data: &[u8]) -> Result<Canvas<T>, PfmError> {
+        let mut reader = Reader { data, pos: 0 };
+
+        let channels = match reader.token() {
+            Some(b"PF") => 3,
+            Some(b"Pf") => 1,
+            Some(_) => return Err(PfmError::UnsupportedFormat),
+            None => return Err(PfmError::MissingHeader),
+        };
+
+        let width: usize = number(&mut reader)?;
+        let height: usize = number(&mut reader)?;
+        let scale: f64 = number(&mut reader)?;
+        if width == 0 || height == 0 || scale == 0.0 || !scale.is_finite() {
+            return Err(PfmError::InvalidDimensions);
+        }
+        let little_endian = scale < 0.0;
+
+        // A single whitespace byte separates the header from the samples.
+        reader.pos += 1;
+
+        let len = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(4 * channels))
+            .ok_or(PfmError::InvalidDimensions)?;
+        let samples = data
+            .get(reader.pos..)
+            .and_then(|rest| rest.get(..len))
+            .ok_or(PfmError::MissingPixelData)?;
+
+        let sample = |i: usize| {
+            let bytes = [0, 1, 2, 3].map(|b| samples[4 * i + b]);
+            let value = if little_endian {
+                f32::from_le_bytes(bytes)
+            } else {
+                f32::from_be_bytes(bytes)
+            };
+            T::from_f64(value as f64 * scale.abs())
+        };
+
+        // Rows are stored from the bottom of the image up.
+        let mut canvas = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let i = ((height - 1 - y) * width + x) * channels;
+                canvas[x][y] = if channels == 3 {
+                    Color::new(sample(i), sample(i + 1), sample(i + 2))
+                } else {
+                    Color::new(sample(i), sample(i), sample(i))
+                };
+            }
+        }
+
+        Ok(canvas)
+    }
+}
+
+fn number<N: std::str::FromStr>(reader: &mut Reader) -> Result<N, PfmError> {
+    let token = reader.token().ok_or(PfmError::MissingHeader)?;
+    let text = String::from_utf8_lossy(token);
+    text.parse()
+        .map_err(|_| PfmError::InvalidNumber(text.into_owned()))
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pfm(header: &str, samples: &[f32], little_endian: bool) -> Vec<u8> {
+        let mut data = header.as_bytes().to_vec();
+        for sample in samples {
+            if little_endian {
+                data.extend_from_slice(&sample.to_le_bytes());
+            } else {
+                data.extend_from_slice(&sample.to_be_bytes());
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn pfm_read_color_keeps_high_dynamic_range() {
+        // Bottom row first.
+        let data = pfm(
+            "PF\n2 2\n-1.0\n",
+            &[
+                0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 250.0, 0.5, 0.0, 0.0, 0.0, 1e-3,
+            ],
+            true,
+        );
+        let canvas: Canvas = Canvas::from_pfm(&data).unwrap();
+        assert_eq!(canvas.width(), 2);
+        assert_eq!(canvas.height(), 2);
+        assert_eq!(canvas.pixel_at(0, 0), Color::new(250.0, 0.5, 0.0));
+        assert_eq!(canvas.pixel_at(1, 0), Color::new(0.0, 0.0, 1e-3));
+        assert_eq!(canvas.pixel_at(1, 1), Color::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn pfm_read_grayscale_big_endian_scaled() {
+        let data = pfm("Pf\n1 1\n4.0\n", &[2.5], false);
+        let canvas: Canvas = Canvas::from_pfm(&data).unwrap();
+        assert_eq!(canvas.pixel_at(0, 0), Color::new(10.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn pfm_read_errors() {
+        let read = |data: &[u8]| Canvas::<f64>::from_pfm(data).unwrap_err();
+        assert_eq!(read(b"P6\n1 1\n255\n"), PfmError::UnsupportedFormat);
+        assert_eq!(read(b"PF\n1"), PfmError::MissingHeader);
+        assert_eq!(read(b"PF\n1 1\n0\n"), PfmError::InvalidDimensions);
+        assert_eq!(
+            read(b"PF\n1 1\nbig\n"),
+            PfmError::InvalidNumber("big".to_string())
+        );
+        assert_eq!(
+            read(&pfm("PF\n1 1\n-1\n", &[1.0, 2.0], true)),
+            PfmError::MissingPixelData
+        );
+        assert_eq!(
+            read(b"PF 4000000000 4000000000 -1\n"),
+            PfmError::InvalidDimensions
+        );
+    }
+}
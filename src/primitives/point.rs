@@ -1,51 +1,133 @@
-use crate::primitives::{tuple::Tuple, vector::Vector};
-use approx::abs_diff_eq;
+use crate::primitives::{tuple::Tuple, vector::Vector, Float, EPSILON};
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
 
 // ------------------------------------------------------
-#[derive(Clone, Copy, Debug)]
-pub struct Point {
-    x: f64,
-    y: f64,
-    z: f64,
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Point<T: Float = f64> {
+    x: T,
+    y: T,
+    z: T,
 }
 
 // ------------------------------------------------------
-impl Tuple for Point {
-    fn new(x: f64, y: f64, z: f64) -> Self {
+impl<T: Float> Point<T> {
+    /// Position vector from the origin to this point.
+    pub fn to_vector(self) -> Vector<T> {
+        Vector::new(self.x, self.y, self.z)
+    }
+
+    pub fn cast<U: Float>(self) -> Point<U> {
+        Point::new(self.x.cast(), self.y.cast(), self.z.cast())
+    }
+}
+
+impl From<Point<f32>> for Point<f64> {
+    fn from(p: Point<f32>) -> Self {
+        p.cast()
+    }
+}
+
+impl<T: Float> From<[T; 3]> for Point<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Point { x, y, z }
+    }
+}
+
+impl<T: Float> From<Point<T>> for [T; 4] {
+    fn from(p: Point<T>) -> Self {
+        p.to_array()
+    }
+}
+
+// ------------------------------------------------------
+impl<T: Float> std::fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "point(")?;
+        std::fmt::Display::fmt(&self.x, f)?;
+        write!(f, ", ")?;
+        std::fmt::Display::fmt(&self.y, f)?;
+        write!(f, ", ")?;
+        std::fmt::Display::fmt(&self.z, f)?;
+        write!(f, ")")
+    }
+}
+
+// ------------------------------------------------------
+impl<T: Float> Tuple for Point<T> {
+    type Scalar = T;
+
+    fn new(x: T, y: T, z: T) -> Self {
         Point { x, y, z }
     }
 
-    fn x(&self) -> f64 {
+    fn x(&self) -> T {
         self.x
     }
 
-    fn y(&self) -> f64 {
+    fn y(&self) -> T {
         self.y
     }
 
-    fn z(&self) -> f64 {
+    fn z(&self) -> T {
         self.z
     }
 
-    fn w(&self) -> f64 {
-        1.0
+    fn w(&self) -> T {
+        T::one()
     }
 }
 
 // ------------------------------------------------------
-impl PartialEq for Point {
-    fn eq(&self, other: &Point) -> bool {
-        abs_diff_eq!(self.x, other.x, epsilon = f64::EPSILON)
-            && abs_diff_eq!(self.y, other.y, epsilon = f64::EPSILON)
-            && abs_diff_eq!(self.z, other.z, epsilon = f64::EPSILON)
+/// Component-wise comparison within [`EPSILON`].
+impl<T: Float> PartialEq for Point<T> {
+    fn eq(&self, other: &Point<T>) -> bool {
+        self.abs_diff_eq(other, Self::default_epsilon())
+    }
+}
+
+impl<T: Float> AbsDiffEq for Point<T> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> T {
+        T::from_f64(EPSILON)
+    }
+
+    fn abs_diff_eq(&self, other: &Point<T>, epsilon: T) -> bool {
+        self.x.abs_diff_eq(&other.x, epsilon)
+            && self.y.abs_diff_eq(&other.y, epsilon)
+            && self.z.abs_diff_eq(&other.z, epsilon)
+    }
+}
+
+impl<T: Float> RelativeEq for Point<T> {
+    fn default_max_relative() -> T {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Point<T>, epsilon: T, max_relative: T) -> bool {
+        self.x.relative_eq(&other.x, epsilon, max_relative)
+            && self.y.relative_eq(&other.y, epsilon, max_relative)
+            && self.z.relative_eq(&other.z, epsilon, max_relative)
+    }
+}
+
+impl<T: Float> UlpsEq for Point<T> {
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Point<T>, epsilon: T, max_ulps: u32) -> bool {
+        self.x.ulps_eq(&other.x, epsilon, max_ulps)
+            && self.y.ulps_eq(&other.y, epsilon, max_ulps)
+            && self.z.ulps_eq(&other.z, epsilon, max_ulps)
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Add<Vector> for Point {
-    type Output = Point;
+impl<T: Float> std::ops::Add<Vector<T>> for Point<T> {
+    type Output = Point<T>;
 
-    fn add(self, rhs: Vector) -> Self::Output {
+    fn add(self, rhs: Vector<T>) -> Self::Output {
         Self {
             x: self.x + rhs.x(),
             y: self.y + rhs.y(),
@@ -54,10 +136,10 @@ impl std::ops::Add<Vector> for Point {
     }
 }
 
-impl std::ops::Add<Point> for Vector {
-    type Output = Point;
+impl<T: Float> std::ops::Add<Point<T>> for Vector<T> {
+    type Output = Point<T>;
 
-    fn add(self, rhs: Point) -> Self::Output {
+    fn add(self, rhs: Point<T>) -> Self::Output {
         Point {
             x: self.x() + rhs.x,
             y: self.y() + rhs.y,
@@ -66,10 +148,10 @@ impl std::ops::Add<Point> for Vector {
     }
 }
 
-impl std::ops::Add for Point {
-    type Output = Point;
+impl<T: Float> std::ops::Add for Point<T> {
+    type Output = Point<T>;
 
-    fn add(self, rhs: Point) -> Self::Output {
+    fn add(self, rhs: Point<T>) -> Self::Output {
         Point {
             x: self.x() + rhs.x,
             y: self.y() + rhs.y,
@@ -79,57 +161,79 @@ impl std::ops::Add for Point {
 }
 
 // ------------------------------------------------------
-impl std::ops::Sub for Point {
-    type Output = Vector;
+impl<T: Float> std::ops::Sub for Point<T> {
+    type Output = Vector<T>;
 
-    fn sub(self, rhs: Point) -> Self::Output {
+    fn sub(self, rhs: Point<T>) -> Self::Output {
         Vector::new(self.x - rhs.x(), self.y - rhs.y(), self.z - rhs.z())
     }
 }
 
-impl std::ops::Sub<Vector> for Point {
-    type Output = Point;
+impl<T: Float> std::ops::Sub<Vector<T>> for Point<T> {
+    type Output = Point<T>;
 
-    fn sub(self, rhs: Vector) -> Self::Output {
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
         Point::new(self.x - rhs.x(), self.y - rhs.y(), self.z - rhs.z())
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Mul<f64> for Point {
-    type Output = Point;
+impl<T: Float> std::ops::Mul<T> for Point<T> {
+    type Output = Point<T>;
 
-    fn mul(self, rhs: f64) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         Point::new(self.x * rhs, self.y * rhs, self.z * rhs)
     }
 }
 
-impl std::ops::Mul<Point> for f64 {
-    type Output = Point;
+macro_rules! impl_scalar_mul_point {
+    ($($t:ty),*) => {$(
+        impl std::ops::Mul<Point<$t>> for $t {
+            type Output = Point<$t>;
 
-    fn mul(self, rhs: Point) -> Self::Output {
-        Point::new(rhs.x * self, rhs.y * self, rhs.z * self)
-    }
+            fn mul(self, rhs: Point<$t>) -> Self::Output {
+                Point::new(rhs.x * self, rhs.y * self, rhs.z * self)
+            }
+        }
+    )*};
 }
 
+impl_scalar_mul_point!(f32, f64);
+
 // ------------------------------------------------------
-impl std::ops::Div<f64> for Point {
-    type Output = Point;
+impl<T: Float> std::ops::Div<T> for Point<T> {
+    type Output = Point<T>;
 
-    fn div(self, rhs: f64) -> Self::Output {
+    fn div(self, rhs: T) -> Self::Output {
         Point::new(self.x / rhs, self.y / rhs, self.z / rhs)
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Neg for Point {
-    type Output = Point;
+impl<T: Float> std::ops::Neg for Point<T> {
+    type Output = Point<T>;
 
     fn neg(self) -> Self::Output {
         Point::new(-self.x, -self.y, -self.z)
     }
 }
 
+// ------------------------------------------------------
+#[cfg(feature = "serde")]
+impl<T: Float + serde::Serialize> serde::Serialize for Point<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.x, self.y, self.z].serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Float + serde::Deserialize<'de>> serde::Deserialize<'de> for Point<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [x, y, z] = <[T; 3]>::deserialize(deserializer)?;
+        Ok(Point { x, y, z })
+    }
+}
+
 // ------------------------------------------------------
 #[cfg(test)]
 mod tests {
@@ -205,4 +309,64 @@ mod tests {
         let expected = Point::new(0.5, -1.0, 1.5);
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn point_f32_precision() {
+        let p: Point<f32> = Point::new(1.0, -2.0, 3.0);
+        let res = 2.0f32 * p + Vector::new(0.5, 0.5, 0.5);
+        assert_eq!(res, Point::new(2.5f32, -3.5, 6.5));
+
+        let wide: Point = res.into();
+        assert_eq!(wide, Point::new(2.5, -3.5, 6.5));
+        assert_eq!(wide.cast::<f32>(), res);
+    }
+
+    #[test]
+    fn point_approx_eq() {
+        let half_sqrt2 = 2.0f64.sqrt() / 2.0;
+        let angle = std::f64::consts::FRAC_PI_4;
+        let rotated = Point::new(0.0, angle.cos(), angle.sin());
+        assert_eq!(rotated, Point::new(0.0, half_sqrt2, half_sqrt2));
+        assert_ne!(rotated, Point::new(0.0, 0.707, 0.707));
+
+        approx::assert_abs_diff_eq!(rotated, Point::new(0.0, 0.707, 0.707), epsilon = 1.0e-3);
+        approx::assert_relative_eq!(
+            Point::new(1.0e6, 0.0, 1.0),
+            Point::new(1.0e6 + 1.0, 0.0, 1.0),
+            max_relative = 1.0e-5
+        );
+        approx::assert_ulps_eq!(rotated, Point::new(0.0, half_sqrt2, half_sqrt2));
+    }
+
+    #[test]
+    fn point_conversions() {
+        let p = Point::from([1.0, 2.0, 3.0]);
+        assert_eq!(p, Point::new(1.0, 2.0, 3.0));
+
+        let a: [f64; 4] = p.into();
+        assert_eq!(a, [1.0, 2.0, 3.0, 1.0]);
+
+        assert_eq!(p.to_vector(), Vector::new(1.0, 2.0, 3.0));
+        assert_eq!(p.to_vector().to_point(), p);
+        assert_eq!(Point::<f64>::default(), Point::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn point_display() {
+        let p = Point::new(1.0, -2.5, 3.0);
+        assert_eq!(p.to_string(), "point(1, -2.5, 3)");
+        assert_eq!(format!("{:.2}", p), "point(1.00, -2.50, 3.00)");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn point_serde_array_form() {
+        let p = Point::new(1.0, 2.0, 3.0);
+        let json = serde_json::to_string(&p).unwrap();
+        assert_eq!(json, "[1.0,2.0,3.0]");
+
+        let res: Point = serde_json::from_str("[1, 2, 3]").unwrap();
+        assert_eq!(res, p);
+        assert!(serde_json::from_str::<Point>("[1, 2]").is_err());
+    }
 }
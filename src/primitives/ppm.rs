@@ -0,0 +1,231 @@
+use crate::primitives::{Canvas, Color, Float};
+
+// ------------------------------------------------------
+#[derive(Debug, PartialEq)]
+pub enum PpmError {
+    UnsupportedFormat,
+    MissingHeader,
+    InvalidNumber(String),
+    InvalidDimensions,
+    MissingPixelData,
+    SampleTooLarge(usize),
+}
+
+impl std::fmt::Display for PpmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PpmError::UnsupportedFormat => write!(f, "only P3 and P6 PPM files are supported"),
+            PpmError::MissingHeader => write!(f, "PPM header is incomplete"),
+            PpmError::InvalidNumber(token) => write!(f, "invalid number in PPM: {}", token),
+            PpmError::InvalidDimensions => {
+                write!(f, "PPM dimensions or maximum value are zero or too large")
+            }
+            PpmError::MissingPixelData => write!(f, "PPM has fewer pixels than its header says"),
+            PpmError::SampleTooLarge(value) => {
+                write!(f, "PPM sample exceeds the maximum value: {}", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PpmError {}
+
+// ------------------------------------------------------
+impl<T: Float> Canvas<T> {
+    /// Reads a plain (`P3`) or binary (`P6`) PPM image, scaling every
+    /// component into `[0, 1]`.
+    pub fn from_ppm(data: &[u8]) -> Result<Canvas<T>, PpmError> {
+        let mut reader = Reader { data, pos: 0 };
+
+        let binary = match reader.token() {
+            Some(b"P3") => false,
+            Some(b"P6") => true,
+            Some(_) => return Err(PpmError::UnsupportedFormat),
+            None => return Err(PpmError::MissingHeader),
+        };
+
+        let width = reader.number()?;
+        let height = reader.number()?;
+        let max_value = reader.number()?;
+        if width == 0 || height == 0 || max_value == 0 || max_value > 65535 {
+            return Err(PpmError::InvalidDimensions);
+        }
+
+        // A single whitespace byte separates the header from binary data.
+        reader.pos += 1;
+
+        // Check the header against the data before allocating: every sample
+        // takes at least one byte (two in 16-bit binary files).
+        let sample_len = if binary && max_value >= 256 { 2 } else { 1 };
+        let min_len = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(3 * sample_len))
+            .ok_or(PpmError::InvalidDimensions)?;
+        if data.len().saturating_sub(reader.pos) < min_len {
+            return Err(PpmError::MissingPixelData);
+        }
+
+        let scale = 1.0 / max_value as f64;
+        let mut canvas = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut rgb = [0.0; 3];
+                for c in rgb.iter_mut() {
+                    let value = if binary {
+                        reader.binary_sample(max_value)?
+                    } else {
+                        reader.number().map_err(|e| match e {
+                            PpmError::MissingHeader => PpmError::MissingPixelData,
+                            e => e,
+                        })?
+                    };
+                    if value > max_value {
+                        return Err(PpmError::SampleTooLarge(value));
+                    }
+                    *c = value as f64 * scale;
+                }
+                canvas[x][y] = Color::new(
+                    T::from_f64(rgb[0]),
+                    T::from_f64(rgb[1]),
+                    T::from_f64(rgb[2]),
+                );
+            }
+        }
+
+        Ok(canvas)
+    }
+}
+
+/// Netpbm header tokens, also used for PFM files.
+pub(super) struct Reader<'a> {
+    pub(super) data: &'a [u8],
+    pub(super) pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Next whitespace separated token, skipping `#` comments.
+    pub(super) fn token(&mut self) -> Option<&'a [u8]> {
+        loop {
+            while self.pos < self.data.len() && self.data[self.pos].is_ascii_whitespace() {
+                self.pos += 1;
+            }
+            if self.data.get(self.pos) == Some(&b'#') {
+                while self.pos < self.data.len() && self.data[self.pos] != b'\n' {
+                    self.pos += 1;
+                }
+                continue;
+            }
+            break;
+        }
+
+        let start = self.pos;
+        while self.pos < self.data.len() && !self.data[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| &self.data[start..self.pos])
+    }
+
+    fn number(&mut self) -> Result<usize, PpmError> {
+        let token = self.token().ok_or(PpmError::MissingHeader)?;
+        let text = String::from_utf8_lossy(token);
+        text.parse()
+            .map_err(|_| PpmError::InvalidNumber(text.into_owned()))
+    }
+
+    fn binary_sample(&mut self, max_value: usize) -> Result<usize, PpmError> {
+        let len = if max_value < 256 { 1 } else { 2 };
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or(PpmError::MissingPixelData)?;
+        self.pos += len;
+        Ok(bytes.iter().fold(0, |acc, b| (acc << 8) | *b as usize))
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ppm_read_plain() {
+        let ppm = b"P3\n# a comment\n2 1\n255\n255 0 0  0 51 255\n";
+        let canvas: Canvas = Canvas::from_ppm(ppm).unwrap();
+        assert_eq!(canvas.width(), 2);
+        assert_eq!(canvas.height(), 1);
+        assert_eq!(canvas.pixel_at(0, 0), Color::red());
+        assert_eq!(canvas.pixel_at(1, 0), Color::new(0.0, 0.2, 1.0));
+    }
+
+    #[test]
+    fn ppm_read_binary() {
+        let mut ppm = b"P6\n1 2\n255\n".to_vec();
+        ppm.extend_from_slice(&[255, 0, 0, 0, 0, 255]);
+        let canvas: Canvas = Canvas::from_ppm(&ppm).unwrap();
+        assert_eq!(canvas.pixel_at(0, 0), Color::red());
+        assert_eq!(canvas.pixel_at(0, 1), Color::blue());
+    }
+
+    #[test]
+    fn ppm_round_trip() {
+        let mut canvas: Canvas = Canvas::new(10, 2);
+        canvas[3][1] = Color::new(1.0, 0.2, 0.6);
+        let canvas: Canvas = Canvas::from_ppm(canvas.to_ppm().as_bytes()).unwrap();
+        assert_eq!(canvas.pixel_at(3, 1), Color::new(1.0, 0.2, 0.6));
+        assert_eq!(canvas.pixel_at(0, 0), Color::black());
+    }
+
+    #[test]
+    fn ppm_read_errors() {
+        let read = |data: &[u8]| Canvas::<f64>::from_ppm(data).unwrap_err();
+        assert_eq!(read(b"P5\n1 1\n255\n0"), PpmError::UnsupportedFormat);
+        assert_eq!(read(b""), PpmError::MissingHeader);
+        assert_eq!(read(b"P3\n0 1\n255\n"), PpmError::InvalidDimensions);
+        assert_eq!(read(b"P3\n1 1\n255\n1 2"), PpmError::MissingPixelData);
+        assert_eq!(
+            read(b"P3\n1 x\n255\n"),
+            PpmError::InvalidNumber("x".to_string())
+        );
+    }
+
+    #[test]
+    fn ppm_oversized_header_is_rejected_before_allocating() {
+        let read = |data: &[u8]| Canvas::<f64>::from_ppm(data).unwrap_err();
+        assert_eq!(
+            read(b"P3 4000000000 4000000000 255\n0 0 0"),
+            PpmError::InvalidDimensions
+        );
+        assert_eq!(
+            read(b"P3 100000 100000 255\n0 0 0"),
+            PpmError::MissingPixelData
+        );
+        assert_eq!(
+            read(b"P6 2 1 65535\n\x00\x01\x00\x02\x00\x03"),
+            PpmError::MissingPixelData
+        );
+    }
+
+    #[test]
+    fn ppm_max_value_is_at_most_16_bits() {
+        let read = |data: &[u8]| Canvas::<f64>::from_ppm(data);
+        assert!(read(b"P3\n1 1\n65535\n0 0 65535").is_ok());
+        assert_eq!(
+            read(b"P3\n1 1\n65536\n0 0 0").unwrap_err(),
+            PpmError::InvalidDimensions
+        );
+    }
+
+    #[test]
+    fn ppm_samples_above_max_value_are_rejected() {
+        let read = |data: &[u8]| Canvas::<f64>::from_ppm(data).unwrap_err();
+        assert_eq!(
+            read(b"P3\n1 1\n100\n0 101 0"),
+            PpmError::SampleTooLarge(101)
+        );
+        assert_eq!(
+            read(b"P6\n1 1\n100\n\x00\x00\xff"),
+            PpmError::SampleTooLarge(255)
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crate::primitives::{Float, Matrix, Point, Vector};
+
+// ------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray<T: Float = f64> {
+    pub origin: Point<T>,
+    pub direction: Vector<T>,
+}
+
+// ------------------------------------------------------
+impl<T: Float> Ray<T> {
+    pub fn new(origin: Point<T>, direction: Vector<T>) -> Self {
+        Ray { origin, direction }
+    }
+
+    /// Point reached after travelling `t` times the direction.
+    pub fn position(&self, t: T) -> Point<T> {
+        self.origin + self.direction * t
+    }
+
+    pub fn transform(&self, m: &Matrix<T>) -> Ray<T> {
+        Ray::new(m * self.origin, m * self.direction)
+    }
+
+    pub fn cast<U: Float>(self) -> Ray<U> {
+        Ray::new(self.origin.cast(), self.direction.cast())
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Tuple;
+
+    #[test]
+    fn ray_position() {
+        let r = Ray::new(Point::new(2.0, 3.0, 4.0), Vector::new(1.0, 0.0, 0.0));
+        assert_eq!(r.position(0.0), Point::new(2.0, 3.0, 4.0));
+        assert_eq!(r.position(1.0), Point::new(3.0, 3.0, 4.0));
+        assert_eq!(r.position(-1.0), Point::new(1.0, 3.0, 4.0));
+        assert_eq!(r.position(2.5), Point::new(4.5, 3.0, 4.0));
+    }
+
+    #[test]
+    fn ray_transform() {
+        #[rustfmt::skip]
+        let translation = Matrix::new4().init([
+            1.0, 0.0, 0.0, 3.0,
+            0.0, 1.0, 0.0, 4.0,
+            0.0, 0.0, 1.0, 5.0,
+            0.0, 0.0, 0.0, 1.0
+        ].to_vec());
+        let r = Ray::new(Point::new(1.0, 2.0, 3.0), Vector::new(0.0, 1.0, 0.0));
+        let res = r.transform(&translation);
+        assert_eq!(res.origin, Point::new(4.0, 6.0, 8.0));
+        assert_eq!(res.direction, Vector::new(0.0, 1.0, 0.0));
+    }
+}
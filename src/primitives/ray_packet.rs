@@ -0,0 +1,105 @@
+use crate::primitives::{Float, Point, Tuple, Vector};
+
+// ------------------------------------------------------
+/// Four rays stored structure-of-arrays, one lane per ray, so the SIMD
+/// kernels can advance all of them at once.
+#[derive(Clone, Copy, Debug)]
+pub struct RayPacket<T: Float = f64> {
+    origin: [[T; 4]; 3],
+    direction: [[T; 4]; 3],
+}
+
+// ------------------------------------------------------
+impl<T: Float> RayPacket<T> {
+    pub fn new(origins: [Point<T>; 4], directions: [Vector<T>; 4]) -> Self {
+        RayPacket {
+            origin: [
+                origins.map(|p| p.x()),
+                origins.map(|p| p.y()),
+                origins.map(|p| p.z()),
+            ],
+            direction: [
+                directions.map(|v| v.x()),
+                directions.map(|v| v.y()),
+                directions.map(|v| v.z()),
+            ],
+        }
+    }
+
+    pub fn origin(&self, lane: usize) -> Point<T> {
+        Point::new(
+            self.origin[0][lane],
+            self.origin[1][lane],
+            self.origin[2][lane],
+        )
+    }
+
+    pub fn direction(&self, lane: usize) -> Vector<T> {
+        Vector::new(
+            self.direction[0][lane],
+            self.direction[1][lane],
+            self.direction[2][lane],
+        )
+    }
+
+    /// Point reached by each ray at its own distance `t[lane]`.
+    pub fn position(&self, t: [T; 4]) -> [Point<T>; 4] {
+        let x = T::mul_add4(self.origin[0], self.direction[0], t);
+        let y = T::mul_add4(self.origin[1], self.direction[1], t);
+        let z = T::mul_add4(self.origin[2], self.direction[2], t);
+        [0, 1, 2, 3].map(|lane| Point::new(x[lane], y[lane], z[lane]))
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_packet_lanes() {
+        let origins = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 2.0, 3.0),
+            Point::new(-1.0, 0.5, 2.0),
+            Point::new(2.0, 3.0, 4.0),
+        ];
+        let directions = [
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, -1.0, 0.0),
+            Vector::new(1.0, 1.0, 1.0),
+        ];
+        let packet = RayPacket::new(origins, directions);
+
+        for lane in 0..4 {
+            assert_eq!(packet.origin(lane), origins[lane]);
+            assert_eq!(packet.direction(lane), directions[lane]);
+        }
+    }
+
+    #[test]
+    fn ray_packet_position_matches_scalar() {
+        let origins = [Point::new(2.0, 3.0, 4.0); 4];
+        let directions = [
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(0.5, -0.5, 0.25),
+        ];
+        let t = [0.0, 1.0, -1.0, 2.5];
+        let packet = RayPacket::new(origins, directions);
+
+        let res = packet.position(t);
+        for lane in 0..4 {
+            assert_eq!(res[lane], origins[lane] + directions[lane] * t[lane]);
+        }
+
+        let narrow: RayPacket<f32> =
+            RayPacket::new(origins.map(|p| p.cast()), directions.map(|v| v.cast()));
+        let res32 = narrow.position(t.map(|v| v as f32));
+        for lane in 0..4 {
+            assert_eq!(res32[lane].cast::<f64>(), res[lane]);
+        }
+    }
+}
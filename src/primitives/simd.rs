@@ -0,0 +1,334 @@
+// ------------------------------------------------------
+/// Arithmetic kernels behind the hot paths of `Vector`, `Color` and
+/// `Matrix`.
+///
+/// The default methods are the plain scalar code. With the `simd` feature on
+/// x86_64, `f32` and `f64` override them with SSE (and AVX when the target
+/// enables it) intrinsics; every other configuration keeps the scalar path.
+///
+/// The trait is public only so it can bound [`Float`](super::Float); the
+/// `simd` module is private, which keeps it unnameable (and unimplementable)
+/// outside the crate.
+pub trait SimdKernels: num_traits::Float {
+    fn dot3(a: [Self; 3], b: [Self; 3]) -> Self {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+
+    fn cross3(a: [Self; 3], b: [Self; 3]) -> [Self; 3] {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    }
+
+    fn add3(a: [Self; 3], b: [Self; 3]) -> [Self; 3] {
+        [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+    }
+
+    fn sub3(a: [Self; 3], b: [Self; 3]) -> [Self; 3] {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+    }
+
+    fn mul3(a: [Self; 3], b: [Self; 3]) -> [Self; 3] {
+        [a[0] * b[0], a[1] * b[1], a[2] * b[2]]
+    }
+
+    /// `a + b * c`, lane by lane.
+    fn mul_add4(a: [Self; 4], b: [Self; 4], c: [Self; 4]) -> [Self; 4] {
+        [
+            a[0] + b[0] * c[0],
+            a[1] + b[1] * c[1],
+            a[2] + b[2] * c[2],
+            a[3] + b[3] * c[3],
+        ]
+    }
+
+    /// First three rows of a row-major 4x4 matrix times a homogeneous tuple.
+    fn mat4_mul_tuple(m: &[Self], t: [Self; 4]) -> [Self; 3] {
+        let row = |r: usize| {
+            m[r * 4] * t[0] + m[r * 4 + 1] * t[1] + m[r * 4 + 2] * t[2] + m[r * 4 + 3] * t[3]
+        };
+        [row(0), row(1), row(2)]
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+impl SimdKernels for f32 {}
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+impl SimdKernels for f64 {}
+
+// ------------------------------------------------------
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    use super::SimdKernels;
+
+    // SSE and SSE2 are part of the x86_64 baseline, so the intrinsics below
+    // are always available on this architecture.
+
+    #[inline]
+    unsafe fn load3_ps(a: [f32; 3]) -> __m128 {
+        _mm_set_ps(0.0, a[2], a[1], a[0])
+    }
+
+    #[inline]
+    unsafe fn store3_ps(v: __m128) -> [f32; 3] {
+        let mut out = [0.0f32; 4];
+        _mm_storeu_ps(out.as_mut_ptr(), v);
+        [out[0], out[1], out[2]]
+    }
+
+    #[inline]
+    unsafe fn hsum_ps(v: __m128) -> f32 {
+        let shuf = _mm_shuffle_ps::<0b10_11_00_01>(v, v);
+        let sums = _mm_add_ps(v, shuf);
+        let shuf = _mm_movehl_ps(shuf, sums);
+        _mm_cvtss_f32(_mm_add_ss(sums, shuf))
+    }
+
+    impl SimdKernels for f32 {
+        fn dot3(a: [f32; 3], b: [f32; 3]) -> f32 {
+            unsafe { hsum_ps(_mm_mul_ps(load3_ps(a), load3_ps(b))) }
+        }
+
+        fn cross3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+            unsafe {
+                let a = load3_ps(a);
+                let b = load3_ps(b);
+                // yzx and zxy swizzles.
+                let a_yzx = _mm_shuffle_ps::<0b11_00_10_01>(a, a);
+                let b_yzx = _mm_shuffle_ps::<0b11_00_10_01>(b, b);
+                let a_zxy = _mm_shuffle_ps::<0b11_01_00_10>(a, a);
+                let b_zxy = _mm_shuffle_ps::<0b11_01_00_10>(b, b);
+                store3_ps(_mm_sub_ps(
+                    _mm_mul_ps(a_yzx, b_zxy),
+                    _mm_mul_ps(a_zxy, b_yzx),
+                ))
+            }
+        }
+
+        fn add3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+            unsafe { store3_ps(_mm_add_ps(load3_ps(a), load3_ps(b))) }
+        }
+
+        fn sub3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+            unsafe { store3_ps(_mm_sub_ps(load3_ps(a), load3_ps(b))) }
+        }
+
+        fn mul3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+            unsafe { store3_ps(_mm_mul_ps(load3_ps(a), load3_ps(b))) }
+        }
+
+        fn mul_add4(a: [f32; 4], b: [f32; 4], c: [f32; 4]) -> [f32; 4] {
+            let mut out = [0.0f32; 4];
+            unsafe {
+                let prod = _mm_mul_ps(_mm_loadu_ps(b.as_ptr()), _mm_loadu_ps(c.as_ptr()));
+                _mm_storeu_ps(out.as_mut_ptr(), _mm_add_ps(_mm_loadu_ps(a.as_ptr()), prod));
+            }
+            out
+        }
+
+        fn mat4_mul_tuple(m: &[f32], t: [f32; 4]) -> [f32; 3] {
+            assert!(m.len() >= 12);
+            unsafe {
+                let t = _mm_loadu_ps(t.as_ptr());
+                let row = |r: usize| hsum_ps(_mm_mul_ps(_mm_loadu_ps(m[r * 4..].as_ptr()), t));
+                [row(0), row(1), row(2)]
+            }
+        }
+    }
+
+    // ------------------------------------------------------
+    #[inline]
+    unsafe fn hsum_pd(v: __m128d) -> f64 {
+        _mm_cvtsd_f64(_mm_add_sd(v, _mm_unpackhi_pd(v, v)))
+    }
+
+    #[cfg(target_feature = "avx")]
+    #[inline]
+    unsafe fn dot4_pd(a: *const f64, b: *const f64) -> f64 {
+        let prod = _mm256_mul_pd(_mm256_loadu_pd(a), _mm256_loadu_pd(b));
+        hsum_pd(_mm_add_pd(
+            _mm256_castpd256_pd128(prod),
+            _mm256_extractf128_pd::<1>(prod),
+        ))
+    }
+
+    #[cfg(not(target_feature = "avx"))]
+    #[inline]
+    unsafe fn dot4_pd(a: *const f64, b: *const f64) -> f64 {
+        let lo = _mm_mul_pd(_mm_loadu_pd(a), _mm_loadu_pd(b));
+        let hi = _mm_mul_pd(_mm_loadu_pd(a.add(2)), _mm_loadu_pd(b.add(2)));
+        hsum_pd(_mm_add_pd(lo, hi))
+    }
+
+    /// Applies a two-lane operation to `[x, y]` and a scalar-lane one to `z`.
+    #[inline]
+    unsafe fn map3_pd(
+        a: [f64; 3],
+        b: [f64; 3],
+        pair: unsafe fn(__m128d, __m128d) -> __m128d,
+    ) -> [f64; 3] {
+        let mut out = [0.0f64; 3];
+        _mm_storeu_pd(
+            out.as_mut_ptr(),
+            pair(_mm_loadu_pd(a.as_ptr()), _mm_loadu_pd(b.as_ptr())),
+        );
+        _mm_store_sd(
+            out.as_mut_ptr().add(2),
+            pair(_mm_load_sd(&a[2]), _mm_load_sd(&b[2])),
+        );
+        out
+    }
+
+    impl SimdKernels for f64 {
+        fn dot3(a: [f64; 3], b: [f64; 3]) -> f64 {
+            let a = [a[0], a[1], a[2], 0.0];
+            let b = [b[0], b[1], b[2], 0.0];
+            unsafe { dot4_pd(a.as_ptr(), b.as_ptr()) }
+        }
+
+        fn cross3(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+            let mut out = [0.0f64; 3];
+            unsafe {
+                // x and y lanes: a.yz * b.zx - a.zx * b.yz
+                let xy = _mm_sub_pd(
+                    _mm_mul_pd(_mm_set_pd(a[2], a[1]), _mm_set_pd(b[0], b[2])),
+                    _mm_mul_pd(_mm_set_pd(a[0], a[2]), _mm_set_pd(b[2], b[1])),
+                );
+                _mm_storeu_pd(out.as_mut_ptr(), xy);
+                let z = _mm_sub_sd(
+                    _mm_mul_sd(_mm_set_sd(a[0]), _mm_set_sd(b[1])),
+                    _mm_mul_sd(_mm_set_sd(a[1]), _mm_set_sd(b[0])),
+                );
+                _mm_store_sd(out.as_mut_ptr().add(2), z);
+            }
+            out
+        }
+
+        fn add3(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+            unsafe { map3_pd(a, b, |x, y| _mm_add_pd(x, y)) }
+        }
+
+        fn sub3(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+            unsafe { map3_pd(a, b, |x, y| _mm_sub_pd(x, y)) }
+        }
+
+        fn mul3(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+            unsafe { map3_pd(a, b, |x, y| _mm_mul_pd(x, y)) }
+        }
+
+        fn mul_add4(a: [f64; 4], b: [f64; 4], c: [f64; 4]) -> [f64; 4] {
+            let mut out = [0.0f64; 4];
+            unsafe {
+                for lane in [0, 2] {
+                    let prod = _mm_mul_pd(
+                        _mm_loadu_pd(b[lane..].as_ptr()),
+                        _mm_loadu_pd(c[lane..].as_ptr()),
+                    );
+                    _mm_storeu_pd(
+                        out[lane..].as_mut_ptr(),
+                        _mm_add_pd(_mm_loadu_pd(a[lane..].as_ptr()), prod),
+                    );
+                }
+            }
+            out
+        }
+
+        fn mat4_mul_tuple(m: &[f64], t: [f64; 4]) -> [f64; 3] {
+            assert!(m.len() >= 12);
+            let row = |r: usize| unsafe { dot4_pd(m[r * 4..].as_ptr(), t.as_ptr()) };
+            [row(0), row(1), row(2)]
+        }
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn scalar_dot3(a: [f64; 3], b: [f64; 3]) -> f64 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+
+    fn scalar_cross3(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    }
+
+    const A: [f64; 3] = [1.5, -2.25, 3.125];
+    const B: [f64; 3] = [-0.75, 4.5, 2.0];
+
+    fn narrow(a: [f64; 3]) -> [f32; 3] {
+        a.map(|v| v as f32)
+    }
+
+    fn assert_lanes_eq(res: &[f64], expected: &[f64]) {
+        for (r, e) in res.iter().zip(expected) {
+            assert_relative_eq!(r, e, max_relative = 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn simd_dot_matches_scalar() {
+        let expected = scalar_dot3(A, B);
+        assert_relative_eq!(f64::dot3(A, B), expected);
+        assert_relative_eq!(
+            f32::dot3(narrow(A), narrow(B)) as f64,
+            expected,
+            max_relative = 1.0e-6
+        );
+    }
+
+    #[test]
+    fn simd_cross_matches_scalar() {
+        let expected = scalar_cross3(A, B);
+        assert_lanes_eq(&f64::cross3(A, B), &expected);
+        assert_lanes_eq(&f32::cross3(narrow(A), narrow(B)).map(f64::from), &expected);
+    }
+
+    #[test]
+    fn simd_componentwise_matches_scalar() {
+        assert_lanes_eq(&f64::add3(A, B), &[0.75, 2.25, 5.125]);
+        assert_lanes_eq(&f64::sub3(A, B), &[2.25, -6.75, 1.125]);
+        assert_lanes_eq(&f64::mul3(A, B), &[-1.125, -10.125, 6.25]);
+        assert_lanes_eq(
+            &f32::add3(narrow(A), narrow(B)).map(f64::from),
+            &[0.75, 2.25, 5.125],
+        );
+        assert_lanes_eq(
+            &f32::mul3(narrow(A), narrow(B)).map(f64::from),
+            &[-1.125, -10.125, 6.25],
+        );
+        assert_lanes_eq(
+            &f64::mul_add4([1.0, 2.0, 3.0, 4.0], [0.5; 4], [2.0, 4.0, 6.0, 8.0]),
+            &[2.0, 4.0, 6.0, 8.0],
+        );
+    }
+
+    #[test]
+    fn simd_mat4_mul_tuple_matches_scalar() {
+        #[rustfmt::skip]
+        let m = [
+            1.0, 2.0, 3.0, 4.0,
+            2.0, 4.0, 4.0, 2.0,
+            8.0, 6.0, 4.0, 1.0,
+            0.0, 0.0, 0.0, 1.0,
+        ];
+        let t = [1.0, 2.0, 3.0, 1.0];
+        assert_lanes_eq(&f64::mat4_mul_tuple(&m, t), &[18.0, 24.0, 33.0]);
+        assert_lanes_eq(
+            &f32::mat4_mul_tuple(&m.map(|v| v as f32), t.map(|v| v as f32)).map(f64::from),
+            &[18.0, 24.0, 33.0],
+        );
+    }
+}
@@ -1,8 +1,102 @@
-pub trait Tuple {
-    fn new(x: f64, y: f64, z: f64) -> Self;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg};
 
-    fn x(&self) -> f64;
-    fn y(&self) -> f64;
-    fn z(&self) -> f64;
-    fn w(&self) -> f64;
+use crate::primitives::Float;
+
+pub trait Tuple:
+    Copy
+    + Default
+    + Display
+    + PartialEq
+    + Add<Output = Self>
+    + Mul<Self::Scalar, Output = Self>
+    + Div<Self::Scalar, Output = Self>
+    + Neg<Output = Self>
+{
+    type Scalar: Float;
+
+    fn new(x: Self::Scalar, y: Self::Scalar, z: Self::Scalar) -> Self;
+
+    fn x(&self) -> Self::Scalar;
+    fn y(&self) -> Self::Scalar;
+    fn z(&self) -> Self::Scalar;
+    fn w(&self) -> Self::Scalar;
+
+    fn to_array(&self) -> [Self::Scalar; 4] {
+        [self.x(), self.y(), self.z(), self.w()]
+    }
+
+    fn map(&self, f: impl Fn(Self::Scalar) -> Self::Scalar) -> Self {
+        Self::new(f(self.x()), f(self.y()), f(self.z()))
+    }
+
+    fn zip_map(
+        &self,
+        other: &Self,
+        f: impl Fn(Self::Scalar, Self::Scalar) -> Self::Scalar,
+    ) -> Self {
+        Self::new(
+            f(self.x(), other.x()),
+            f(self.y(), other.y()),
+            f(self.z(), other.z()),
+        )
+    }
+
+    fn min(&self, other: &Self) -> Self {
+        self.zip_map(other, num_traits::Float::min)
+    }
+
+    fn max(&self, other: &Self) -> Self {
+        self.zip_map(other, num_traits::Float::max)
+    }
+
+    fn abs(&self) -> Self {
+        self.map(num_traits::Float::abs)
+    }
+
+    /// Linear interpolation: `self` at `t = 0`, `other` at `t = 1`.
+    fn lerp(&self, other: &Self, t: Self::Scalar) -> Self {
+        self.zip_map(other, |a, b| a + (b - a) * t)
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{Point, Vector};
+
+    fn midpoint<P: Tuple>(a: P, b: P) -> P {
+        (a + b) / P::Scalar::from_f64(2.0)
+    }
+
+    #[test]
+    fn tuple_generic_arithmetic() {
+        let p = midpoint(Point::new(0.0, 2.0, 4.0), Point::new(2.0, 4.0, 6.0));
+        assert_eq!(p, Point::new(1.0, 3.0, 5.0));
+
+        let v = midpoint(Vector::new(1.0f32, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(v, Vector::new(0.5f32, 0.5, 0.0));
+    }
+
+    #[test]
+    fn tuple_componentwise() {
+        let a = Vector::new(1.0, -2.0, 3.0);
+        let b = Vector::new(-1.0, 4.0, 2.0);
+        assert_eq!(a.min(&b), Vector::new(-1.0, -2.0, 2.0));
+        assert_eq!(a.max(&b), Vector::new(1.0, 4.0, 3.0));
+        assert_eq!(a.abs(), Vector::new(1.0, 2.0, 3.0));
+
+        let p = Point::new(0.0, 0.0, 0.0);
+        let q = Point::new(10.0, -4.0, 2.0);
+        assert_eq!(p.lerp(&q, 0.0), p);
+        assert_eq!(p.lerp(&q, 1.0), q);
+        assert_eq!(p.lerp(&q, 0.25), Point::new(2.5, -1.0, 0.5));
+    }
+
+    #[test]
+    fn tuple_to_array() {
+        assert_eq!(Point::new(1.0, 2.0, 3.0).to_array(), [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(Vector::new(1.0, 2.0, 3.0).to_array(), [1.0, 2.0, 3.0, 0.0]);
+    }
 }
@@ -1,129 +1,251 @@
-use crate::primitives::tuple::Tuple;
-use approx::abs_diff_eq;
-
-#[derive(Clone, Copy, Debug)]
-pub struct Vector {
-    x: f64,
-    y: f64,
-    z: f64,
+use crate::primitives::{tuple::Tuple, Float, Point, EPSILON};
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Vector<T: Float = f64> {
+    x: T,
+    y: T,
+    z: T,
 }
 
 // ------------------------------------------------------
-impl Vector {
-    pub fn magnitude(&self) -> f64 {
-        f64::sqrt(self.x.powi(2) + self.y.powi(2) + self.z.powi(2))
+impl<T: Float> Vector<T> {
+    pub fn magnitude(&self) -> T {
+        T::sqrt(self.x.powi(2) + self.y.powi(2) + self.z.powi(2))
     }
 
-    pub fn normalize(&self) -> Vector {
+    pub fn normalize(&self) -> Vector<T> {
         *self / self.magnitude()
     }
 
-    pub fn dot(&self, rhs: Vector) -> f64 {
-        self.x * rhs.x() + self.y * rhs.y() + self.z * rhs.z() + self.w() * rhs.w()
+    pub fn dot(&self, rhs: Vector<T>) -> T {
+        T::dot3(self.components(), rhs.components())
+    }
+
+    /// Mirrors this vector about `normal`, e.g. a ray direction bouncing off
+    /// a surface to give the reflection vector.
+    pub fn reflect(&self, normal: Vector<T>) -> Vector<T> {
+        *self - normal * (T::from_f64(2.0) * self.dot(normal))
+    }
+
+    /// Bends this incoming direction through a surface with `normal` (facing
+    /// against the direction), where `eta` is `n1 / n2`. Returns `None` under
+    /// total internal reflection.
+    pub fn refract(&self, normal: Vector<T>, eta: T) -> Option<Vector<T>> {
+        let one = T::one();
+        let cos_i = -self.dot(normal);
+        let sin2_t = eta * eta * (one - cos_i * cos_i);
+        if sin2_t > one {
+            return None;
+        }
+
+        let cos_t = (one - sin2_t).sqrt();
+        Some(*self * eta + normal * (eta * cos_i - cos_t))
+    }
+
+    fn components(self) -> [T; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    fn from_components([x, y, z]: [T; 3]) -> Vector<T> {
+        Vector { x, y, z }
+    }
+
+    /// Point this vector reaches when applied from the origin.
+    pub fn to_point(self) -> Point<T> {
+        Point::new(self.x, self.y, self.z)
+    }
+
+    pub fn cast<U: Float>(self) -> Vector<U> {
+        Vector::new(self.x.cast(), self.y.cast(), self.z.cast())
+    }
+}
+
+impl From<Vector<f32>> for Vector<f64> {
+    fn from(v: Vector<f32>) -> Self {
+        v.cast()
+    }
+}
+
+impl<T: Float> From<[T; 3]> for Vector<T> {
+    fn from(a: [T; 3]) -> Self {
+        Vector::from_components(a)
+    }
+}
+
+impl<T: Float> From<Vector<T>> for [T; 4] {
+    fn from(v: Vector<T>) -> Self {
+        v.to_array()
     }
 }
 
 // ------------------------------------------------------
-impl Tuple for Vector {
-    fn new(x: f64, y: f64, z: f64) -> Self {
+impl<T: Float> std::fmt::Display for Vector<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "vector(")?;
+        std::fmt::Display::fmt(&self.x, f)?;
+        write!(f, ", ")?;
+        std::fmt::Display::fmt(&self.y, f)?;
+        write!(f, ", ")?;
+        std::fmt::Display::fmt(&self.z, f)?;
+        write!(f, ")")
+    }
+}
+
+// ------------------------------------------------------
+impl<T: Float> Tuple for Vector<T> {
+    type Scalar = T;
+
+    fn new(x: T, y: T, z: T) -> Self {
         Vector { x, y, z }
     }
 
-    fn x(&self) -> f64 {
+    fn x(&self) -> T {
         self.x
     }
 
-    fn y(&self) -> f64 {
+    fn y(&self) -> T {
         self.y
     }
 
-    fn z(&self) -> f64 {
+    fn z(&self) -> T {
         self.z
     }
 
-    fn w(&self) -> f64 {
-        0.0
+    fn w(&self) -> T {
+        T::zero()
     }
 }
 
 // ------------------------------------------------------
-impl PartialEq for Vector {
-    fn eq(&self, other: &Vector) -> bool {
-        abs_diff_eq!(self.x, other.x, epsilon = f64::EPSILON)
-            && abs_diff_eq!(self.y, other.y, epsilon = f64::EPSILON)
-            && abs_diff_eq!(self.z, other.z, epsilon = f64::EPSILON)
+/// Component-wise comparison within [`EPSILON`].
+impl<T: Float> PartialEq for Vector<T> {
+    fn eq(&self, other: &Vector<T>) -> bool {
+        self.abs_diff_eq(other, Self::default_epsilon())
+    }
+}
+
+impl<T: Float> AbsDiffEq for Vector<T> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> T {
+        T::from_f64(EPSILON)
+    }
+
+    fn abs_diff_eq(&self, other: &Vector<T>, epsilon: T) -> bool {
+        self.x.abs_diff_eq(&other.x, epsilon)
+            && self.y.abs_diff_eq(&other.y, epsilon)
+            && self.z.abs_diff_eq(&other.z, epsilon)
+    }
+}
+
+impl<T: Float> RelativeEq for Vector<T> {
+    fn default_max_relative() -> T {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Vector<T>, epsilon: T, max_relative: T) -> bool {
+        self.x.relative_eq(&other.x, epsilon, max_relative)
+            && self.y.relative_eq(&other.y, epsilon, max_relative)
+            && self.z.relative_eq(&other.z, epsilon, max_relative)
+    }
+}
+
+impl<T: Float> UlpsEq for Vector<T> {
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Vector<T>, epsilon: T, max_ulps: u32) -> bool {
+        self.x.ulps_eq(&other.x, epsilon, max_ulps)
+            && self.y.ulps_eq(&other.y, epsilon, max_ulps)
+            && self.z.ulps_eq(&other.z, epsilon, max_ulps)
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Add for Vector {
-    type Output = Vector;
-
-    fn add(self, rhs: Vector) -> Self::Output {
-        Vector {
-            x: self.x() + rhs.x,
-            y: self.y() + rhs.y,
-            z: self.z() + rhs.z,
-        }
+impl<T: Float> std::ops::Add for Vector<T> {
+    type Output = Vector<T>;
+
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        Vector::from_components(T::add3(self.components(), rhs.components()))
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Sub for Vector {
-    type Output = Vector;
+impl<T: Float> std::ops::Sub for Vector<T> {
+    type Output = Vector<T>;
 
-    fn sub(self, rhs: Vector) -> Self::Output {
-        Vector::new(self.x - rhs.x(), self.y - rhs.y(), self.z - rhs.z())
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
+        Vector::from_components(T::sub3(self.components(), rhs.components()))
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Mul<f64> for Vector {
-    type Output = Vector;
+impl<T: Float> std::ops::Mul<T> for Vector<T> {
+    type Output = Vector<T>;
 
-    fn mul(self, rhs: f64) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         Vector::new(self.x * rhs, self.y * rhs, self.z * rhs)
     }
 }
 
-impl std::ops::Mul<Vector> for f64 {
-    type Output = Vector;
+macro_rules! impl_scalar_mul_vector {
+    ($($t:ty),*) => {$(
+        impl std::ops::Mul<Vector<$t>> for $t {
+            type Output = Vector<$t>;
 
-    fn mul(self, rhs: Vector) -> Self::Output {
-        Vector::new(rhs.x * self, rhs.y * self, rhs.z * self)
-    }
+            fn mul(self, rhs: Vector<$t>) -> Self::Output {
+                Vector::new(rhs.x * self, rhs.y * self, rhs.z * self)
+            }
+        }
+    )*};
 }
 
-impl std::ops::Mul for Vector {
-    type Output = Vector;
+impl_scalar_mul_vector!(f32, f64);
+
+impl<T: Float> std::ops::Mul for Vector<T> {
+    type Output = Vector<T>;
 
-    fn mul(self, rhs: Vector) -> Self::Output {
-        Vector::new(
-            self.y * rhs.z() - self.z * rhs.y(),
-            self.z * rhs.x() - self.x * rhs.z(),
-            self.x * rhs.y() - self.y * rhs.x,
-        )
+    fn mul(self, rhs: Vector<T>) -> Self::Output {
+        Vector::from_components(T::cross3(self.components(), rhs.components()))
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Div<f64> for Vector {
-    type Output = Vector;
+impl<T: Float> std::ops::Div<T> for Vector<T> {
+    type Output = Vector<T>;
 
-    fn div(self, rhs: f64) -> Self::Output {
+    fn div(self, rhs: T) -> Self::Output {
         Vector::new(self.x / rhs, self.y / rhs, self.z / rhs)
     }
 }
 
 // ------------------------------------------------------
-impl std::ops::Neg for Vector {
-    type Output = Vector;
+impl<T: Float> std::ops::Neg for Vector<T> {
+    type Output = Vector<T>;
 
     fn neg(self) -> Self::Output {
         Vector::new(-self.x, -self.y, -self.z)
     }
 }
 
+// ------------------------------------------------------
+#[cfg(feature = "serde")]
+impl<T: Float + serde::Serialize> serde::Serialize for Vector<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.x, self.y, self.z].serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Float + serde::Deserialize<'de>> serde::Deserialize<'de> for Vector<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [x, y, z] = <[T; 3]>::deserialize(deserializer)?;
+        Ok(Vector { x, y, z })
+    }
+}
+
 // ------------------------------------------------------
 #[cfg(test)]
 mod tests {
@@ -219,7 +341,7 @@ mod tests {
         assert_eq!(res, expected);
 
         // Scenario: Normalizing vector(1, 2, 3)
-        let v = Vector::new(1.0, 2.0, 3.0);
+        let v: Vector = Vector::new(1.0, 2.0, 3.0);
         let res = v.normalize();
         let magnitude = f64::sqrt(v.x().powi(2) + v.y().powi(2) + v.z().powi(2));
         let expected = Vector::new(1.0 / magnitude, 2.0 / magnitude, 3.0 / magnitude);
@@ -251,4 +373,88 @@ mod tests {
         let expected = Vector::new(1.0, -2.0, 1.0);
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn vector_reflect() {
+        // Scenario: Reflecting a vector approaching at 45°
+        let v = Vector::new(1.0, -1.0, 0.0);
+        let n = Vector::new(0.0, 1.0, 0.0);
+        assert_eq!(v.reflect(n), Vector::new(1.0, 1.0, 0.0));
+
+        // Scenario: Reflecting a vector off a slanted surface
+        let v = Vector::new(0.0, -1.0, 0.0);
+        let n = Vector::new(2.0f64.sqrt() / 2.0, 2.0f64.sqrt() / 2.0, 0.0);
+        assert_eq!(v.reflect(n), Vector::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn vector_refract() {
+        // Straight through at normal incidence, whatever the indices.
+        let d = Vector::new(0.0, -1.0, 0.0);
+        let n = Vector::new(0.0, 1.0, 0.0);
+        assert_eq!(d.refract(n, 1.0 / 1.5), Some(d));
+
+        // Snell's law: n1 sin(i) = n2 sin(t), from air into glass.
+        let d = Vector::new(0.5, -(0.75f64.sqrt()), 0.0);
+        let t = d.refract(n, 1.0 / 1.5).unwrap();
+        assert_eq!(t.magnitude(), 1.0);
+        assert_eq!(t.x() * 1.5, d.x() * 1.0);
+
+        // Total internal reflection leaving glass at 45°.
+        let d = Vector::new(2.0f64.sqrt() / 2.0, -(2.0f64.sqrt()) / 2.0, 0.0);
+        assert_eq!(d.refract(n, 1.5), None);
+    }
+
+    #[test]
+    fn vector_f32_precision() {
+        let v: Vector<f32> = Vector::new(1.0, 2.0, 3.0);
+        let w = Vector::new(2.0f32, 3.0, 4.0);
+        assert_eq!(v.dot(w), 20.0f32);
+        assert_eq!(v * w, Vector::new(-1.0f32, 2.0, -1.0));
+
+        let wide: Vector = v.into();
+        assert_eq!(wide, Vector::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn vector_approx_eq() {
+        let v = Vector::new(0.0, 1.0, 0.0);
+        let angle = std::f64::consts::FRAC_PI_2;
+        let rotated = Vector::new(0.0, angle.cos(), angle.sin());
+        assert_eq!(rotated, Vector::new(0.0, 0.0, 1.0));
+        assert_ne!(v, Vector::new(0.0, 1.0001, 0.0));
+
+        approx::assert_relative_eq!(v, Vector::new(0.0, 1.0001, 0.0), max_relative = 1.0e-3);
+        approx::assert_ulps_eq!(rotated, Vector::new(0.0, angle.cos(), 1.0));
+    }
+
+    #[test]
+    fn vector_conversions() {
+        let v = Vector::from([1.0, 2.0, 3.0]);
+        assert_eq!(v, Vector::new(1.0, 2.0, 3.0));
+
+        let a: [f64; 4] = v.into();
+        assert_eq!(a, [1.0, 2.0, 3.0, 0.0]);
+
+        assert_eq!(v.to_point(), Point::new(1.0, 2.0, 3.0));
+        assert_eq!(Vector::<f64>::default(), Vector::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn vector_display() {
+        let v = Vector::new(0.5, 0.0, -1.0);
+        assert_eq!(v.to_string(), "vector(0.5, 0, -1)");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn vector_serde_array_form() {
+        let v = Vector::new(1.0, 2.0, 3.0);
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, "[1.0,2.0,3.0]");
+
+        let res: Vector = serde_json::from_str("[1, 2, 3]").unwrap();
+        assert_eq!(res, v);
+        assert!(serde_json::from_str::<Vector>("[1, 2]").is_err());
+    }
 }
@@ -15,7 +15,7 @@ pub fn run() {
 
     // println!("{:?}", p);
 
-    let mut c = Canvas::new(900, 550);
+    let mut c: Canvas = Canvas::new(900, 550);
 
     while p.position.y() > 0.0 {
         p.tick(&e);
@@ -34,7 +34,7 @@ pub fn run() {
     let mut data_file = File::create("images/projectile.ppm").expect("creation failed");
 
     // Write contents to the file
-    data_file.write(ppm.as_bytes()).expect("write failed");
+    data_file.write_all(ppm.as_bytes()).expect("write failed");
 
     println!("Write Operation Successful");
     // print!("{}", ppm);
@@ -0,0 +1,121 @@
+// ------------------------------------------------------
+/// Reconstruction filter used to turn image samples into pixel values.
+/// Offsets and radii are in pixels; all filters are separable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    Box { radius: f64 },
+    Tent { radius: f64 },
+    Gaussian { radius: f64, alpha: f64 },
+    MitchellNetravali { radius: f64, b: f64, c: f64 },
+}
+
+impl Filter {
+    /// Mitchell-Netravali with the recommended `b = c = 1/3`.
+    pub fn mitchell() -> Self {
+        Filter::MitchellNetravali {
+            radius: 2.0,
+            b: 1.0 / 3.0,
+            c: 1.0 / 3.0,
+        }
+    }
+
+    pub fn radius(&self) -> f64 {
+        match *self {
+            Filter::Box { radius }
+            | Filter::Tent { radius }
+            | Filter::Gaussian { radius, .. }
+            | Filter::MitchellNetravali { radius, .. } => radius,
+        }
+    }
+
+    /// Weight of a sample at offset `(dx, dy)` from the pixel center. May be
+    /// negative for Mitchell-Netravali.
+    pub fn weight(&self, dx: f64, dy: f64) -> f64 {
+        self.weight_1d(dx) * self.weight_1d(dy)
+    }
+
+    fn weight_1d(&self, d: f64) -> f64 {
+        let d = d.abs();
+        if d > self.radius() {
+            return 0.0;
+        }
+
+        match *self {
+            Filter::Box { .. } => 1.0,
+            Filter::Tent { radius } => 1.0 - d / radius,
+            Filter::Gaussian { radius, alpha } => {
+                ((-alpha * d * d).exp() - (-alpha * radius * radius).exp()).max(0.0)
+            }
+            Filter::MitchellNetravali { radius, b, c } => {
+                let x = 2.0 * d / radius;
+                let res = if x < 1.0 {
+                    (12.0 - 9.0 * b - 6.0 * c) * x * x * x
+                        + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+                        + (6.0 - 2.0 * b)
+                } else {
+                    (-b - 6.0 * c) * x * x * x
+                        + (6.0 * b + 30.0 * c) * x * x
+                        + (-12.0 * b - 48.0 * c) * x
+                        + (8.0 * b + 24.0 * c)
+                };
+                res / 6.0
+            }
+        }
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::Box { radius: 0.5 }
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn filter_box() {
+        let f = Filter::default();
+        assert_eq!(f.radius(), 0.5);
+        assert_eq!(f.weight(0.0, 0.0), 1.0);
+        assert_eq!(f.weight(0.4, -0.4), 1.0);
+        assert_eq!(f.weight(0.6, 0.0), 0.0);
+    }
+
+    #[test]
+    fn filter_tent() {
+        let f = Filter::Tent { radius: 1.0 };
+        assert_eq!(f.weight(0.0, 0.0), 1.0);
+        assert_eq!(f.weight(0.5, 0.0), 0.5);
+        assert_eq!(f.weight(0.5, -0.5), 0.25);
+        assert_eq!(f.weight(1.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn filter_gaussian() {
+        let f = Filter::Gaussian {
+            radius: 1.5,
+            alpha: 2.0,
+        };
+        assert!(f.weight(0.0, 0.0) > f.weight(0.5, 0.0));
+        assert!(f.weight(0.5, 0.0) > f.weight(1.0, 0.0));
+        assert_abs_diff_eq!(f.weight(1.5, 0.0), 0.0);
+        assert_eq!(f.weight(2.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn filter_mitchell() {
+        let f = Filter::mitchell();
+        assert_eq!(f.radius(), 2.0);
+        assert_abs_diff_eq!(f.weight_1d(0.0), 16.0 / 18.0, epsilon = 1e-12);
+        // Negative lobe between one and two pixels out.
+        assert!(f.weight_1d(1.5) < 0.0);
+        assert_abs_diff_eq!(f.weight_1d(2.0), 0.0, epsilon = 1e-12);
+        // Weights of integer offsets sum to one.
+        let sum: f64 = (-2..=2).map(|i| f.weight_1d(i as f64)).sum();
+        assert_abs_diff_eq!(sum, 1.0, epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,67 @@
+// ------------------------------------------------------
+/// How multiple importance sampling weighs two sampling strategies.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Heuristic {
+    Balance,
+    /// Power heuristic with exponent two, usually the better choice.
+    #[default]
+    Power,
+}
+
+impl Heuristic {
+    /// Weight of a sample drawn with strategy `f` (`nf` samples, density
+    /// `f_pdf`) when strategy `g` could also have produced it.
+    pub fn weight(&self, nf: usize, f_pdf: f64, ng: usize, g_pdf: f64) -> f64 {
+        match self {
+            Heuristic::Balance => balance_heuristic(nf, f_pdf, ng, g_pdf),
+            Heuristic::Power => power_heuristic(nf, f_pdf, ng, g_pdf),
+        }
+    }
+}
+
+pub fn balance_heuristic(nf: usize, f_pdf: f64, ng: usize, g_pdf: f64) -> f64 {
+    let f = nf as f64 * f_pdf;
+    let g = ng as f64 * g_pdf;
+    if f + g == 0.0 {
+        return 0.0;
+    }
+    f / (f + g)
+}
+
+pub fn power_heuristic(nf: usize, f_pdf: f64, ng: usize, g_pdf: f64) -> f64 {
+    let f = nf as f64 * f_pdf;
+    let g = ng as f64 * g_pdf;
+    if f * f + g * g == 0.0 {
+        return 0.0;
+    }
+    f * f / (f * f + g * g)
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn heuristics_partition_unity() {
+        for heuristic in [Heuristic::Balance, Heuristic::Power] {
+            for (nf, f, ng, g) in [(1, 0.5, 1, 2.0), (4, 0.1, 1, 3.0), (2, 7.0, 3, 0.0)] {
+                let sum = heuristic.weight(nf, f, ng, g) + heuristic.weight(ng, g, nf, f);
+                assert_abs_diff_eq!(sum, 1.0, epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn heuristics_values() {
+        assert_eq!(balance_heuristic(1, 1.0, 1, 3.0), 0.25);
+        assert_eq!(power_heuristic(1, 1.0, 1, 3.0), 0.1);
+        // Sample counts scale the densities.
+        assert_eq!(balance_heuristic(3, 1.0, 1, 3.0), 0.5);
+        // A strategy that cannot produce the sample leaves all the weight.
+        assert_eq!(power_heuristic(1, 2.0, 1, 0.0), 1.0);
+        assert_eq!(power_heuristic(1, 0.0, 1, 0.0), 0.0);
+        assert_eq!(Heuristic::default(), Heuristic::Power);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::primitives::{Canvas, Color};
+use crate::sampling::{Filter, Rng};
+
+// ------------------------------------------------------
+/// Where samples are placed inside each pixel. Grid sizes are per side, so
+/// `Regular(4)` takes 16 samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SamplePattern {
+    Regular(usize),
+    Jittered(usize),
+    /// Starts with a `min` x `min` jittered grid and re-samples pixels whose
+    /// color differs from a neighbour by more than `threshold` in any
+    /// channel with a `max` x `max` grid.
+    Adaptive {
+        min: usize,
+        max: usize,
+        threshold: f64,
+    },
+}
+
+impl Default for SamplePattern {
+    fn default() -> Self {
+        SamplePattern::Regular(1)
+    }
+}
+
+// ------------------------------------------------------
+/// Renders an image by supersampling a shading function and reconstructing
+/// pixels with a filter.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Supersampler {
+    pattern: SamplePattern,
+    filter: Filter,
+    seed: u64,
+}
+
+struct Sample {
+    x: f64,
+    y: f64,
+    color: Color,
+}
+
+impl Supersampler {
+    pub fn new(pattern: SamplePattern) -> Self {
+        let grid = match pattern {
+            SamplePattern::Regular(n) | SamplePattern::Jittered(n) => n,
+            SamplePattern::Adaptive { min, max, .. } => min.min(max),
+        };
+        if grid == 0 {
+            panic!("Supersampling needs at least one sample per pixel.");
+        }
+
+        Supersampler {
+            pattern,
+            filter: Filter::default(),
+            seed: 0,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        let radius = filter.radius();
+        if !radius.is_finite() || radius <= 0.0 {
+            panic!("A filter needs a positive radius.");
+        }
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn pattern(&self) -> SamplePattern {
+        self.pattern
+    }
+
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    /// Renders a `width` x `height` image. `shade(x, y)` returns the color
+    /// seen at continuous image coordinates, where pixel `(i, j)` covers
+    /// `[i, i + 1) x [j, j + 1)`.
+    pub fn render(&self, width: usize, height: usize, shade: impl Fn(f64, f64) -> Color) -> Canvas {
+        let mut rng = Rng::new(self.seed);
+        let mut pixels: Vec<Vec<Sample>> = Vec::with_capacity(width * height);
+
+        let (grid, jitter) = match self.pattern {
+            SamplePattern::Regular(n) => (n, false),
+            SamplePattern::Jittered(n) => (n, true),
+            SamplePattern::Adaptive { min, .. } => (min, true),
+        };
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(sample_pixel(x, y, grid, jitter, &mut rng, &shade));
+            }
+        }
+
+        if let SamplePattern::Adaptive { max, threshold, .. } = self.pattern {
+            let means: Vec<Color> = pixels.iter().map(|s| mean(s)).collect();
+            for y in 0..height {
+                for x in 0..width {
+                    if contrast(&means, width, height, x, y) > threshold {
+                        pixels[y * width + x] = sample_pixel(x, y, max, true, &mut rng, &shade);
+                    }
+                }
+            }
+        }
+
+        self.reconstruct(width, height, &pixels)
+    }
+
+    /// Splats every sample onto the pixels within the filter radius and
+    /// normalizes by the accumulated weights.
+    fn reconstruct(&self, width: usize, height: usize, pixels: &[Vec<Sample>]) -> Canvas {
+        let mut sums = vec![Color::black(); width * height];
+        let mut weights = vec![0.0; width * height];
+        let radius = self.filter.radius();
+
+        for sample in pixels.iter().flatten() {
+            let x0 = (sample.x - 0.5 - radius).ceil().max(0.0) as i64;
+            let x1 = ((sample.x - 0.5 + radius).floor() as i64).min(width as i64 - 1);
+            let y0 = (sample.y - 0.5 - radius).ceil().max(0.0) as i64;
+            let y1 = ((sample.y - 0.5 + radius).floor() as i64).min(height as i64 - 1);
+
+            for py in (y0..=y1).map(|v| v as usize) {
+                for px in (x0..=x1).map(|v| v as usize) {
+                    let w = self
+                        .filter
+                        .weight(sample.x - (px as f64 + 0.5), sample.y - (py as f64 + 0.5));
+                    sums[py * width + px] = sums[py * width + px] + sample.color * w;
+                    weights[py * width + px] += w;
+                }
+            }
+        }
+
+        let mut canvas = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                // Negative lobes can cancel out completely; fall back to the
+                // pixel's own samples.
+                canvas[x][y] = if weights[i] > 1.0e-9 {
+                    sums[i] * (1.0 / weights[i])
+                } else {
+                    mean(&pixels[i])
+                };
+            }
+        }
+        canvas
+    }
+}
+
+fn sample_pixel(
+    x: usize,
+    y: usize,
+    grid: usize,
+    jitter: bool,
+    rng: &mut Rng,
+    shade: &impl Fn(f64, f64) -> Color,
+) -> Vec<Sample> {
+    let mut samples = Vec::with_capacity(grid * grid);
+    for j in 0..grid {
+        for i in 0..grid {
+            let (ox, oy) = if jitter {
+                (rng.next_f64(), rng.next_f64())
+            } else {
+                (0.5, 0.5)
+            };
+            let sx = x as f64 + (i as f64 + ox) / grid as f64;
+            let sy = y as f64 + (j as f64 + oy) / grid as f64;
+            samples.push(Sample {
+                x: sx,
+                y: sy,
+                color: shade(sx, sy),
+            });
+        }
+    }
+    samples
+}
+
+fn mean(samples: &[Sample]) -> Color {
+    let sum = samples.iter().fold(Color::black(), |acc, s| acc + s.color);
+    sum * (1.0 / samples.len() as f64)
+}
+
+/// Largest per-channel difference between a pixel and its 4-neighbours.
+fn contrast(means: &[Color], width: usize, height: usize, x: usize, y: usize) -> f64 {
+    let c = means[y * width + x];
+    let neighbours = [
+        (x.wrapping_sub(1), y),
+        (x + 1, y),
+        (x, y.wrapping_sub(1)),
+        (x, y + 1),
+    ];
+    neighbours
+        .iter()
+        .filter(|&&(nx, ny)| nx < width && ny < height)
+        .map(|&(nx, ny)| {
+            let n = means[ny * width + nx];
+            (c.r() - n.r())
+                .abs()
+                .max((c.g() - n.g()).abs())
+                .max((c.b() - n.b()).abs())
+        })
+        .fold(0.0, f64::max)
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// White left of `x = 2.3`, black to the right.
+    fn edge(x: f64, _y: f64) -> Color {
+        if x < 2.3 {
+            Color::white()
+        } else {
+            Color::black()
+        }
+    }
+
+    #[test]
+    fn single_sample_hits_pixel_centers() {
+        let canvas = Supersampler::default().render(4, 3, |x, y| Color::new(x, y, 0.0));
+        for y in 0..3 {
+            for x in 0..4 {
+                assert_eq!(
+                    canvas[x][y],
+                    Color::new(x as f64 + 0.5, y as f64 + 0.5, 0.0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn regular_grid_antialiases_edge() {
+        let canvas = Supersampler::new(SamplePattern::Regular(4)).render(5, 2, edge);
+        assert_eq!(canvas[0][0], Color::white());
+        assert_eq!(canvas[1][0], Color::white());
+        assert_eq!(canvas[2][0], Color::new(0.25, 0.25, 0.25));
+        assert_eq!(canvas[3][1], Color::black());
+    }
+
+    #[test]
+    fn jittered_grid_is_seeded() {
+        let sampler = Supersampler::new(SamplePattern::Jittered(8)).with_seed(3);
+        let a = sampler.render(5, 2, edge);
+        let b = sampler.render(5, 2, edge);
+        assert_eq!(a[2][0], b[2][0]);
+        assert!((a[2][0].r() - 0.3).abs() < 0.1);
+    }
+
+    #[test]
+    fn filters_preserve_constant_images() {
+        let gray = Color::new(0.5, 0.25, 0.75);
+        for filter in [
+            Filter::default(),
+            Filter::Tent { radius: 1.0 },
+            Filter::Gaussian {
+                radius: 1.5,
+                alpha: 2.0,
+            },
+            Filter::mitchell(),
+        ] {
+            let canvas = Supersampler::new(SamplePattern::Jittered(3))
+                .with_filter(filter)
+                .render(4, 4, |_, _| gray);
+            for y in 0..4 {
+                for x in 0..4 {
+                    assert_eq!(canvas[x][y], gray);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "A filter needs a positive radius.")]
+    fn zero_radius_filter_is_rejected() {
+        Supersampler::new(SamplePattern::Regular(1)).with_filter(Filter::Tent { radius: 0.0 });
+    }
+
+    #[test]
+    fn narrow_filter_keeps_own_samples() {
+        let canvas = Supersampler::new(SamplePattern::Regular(4))
+            .with_filter(Filter::Box { radius: 0.3 })
+            .render(5, 1, edge);
+        assert_eq!(canvas[0][0], Color::white());
+        // Only the inner samples at 2.375 and 2.625 fall within the radius.
+        assert_eq!(canvas[2][0], Color::black());
+    }
+
+    #[test]
+    fn wide_filter_softens_edge() {
+        let boxed = Supersampler::new(SamplePattern::Regular(4)).render(5, 1, edge);
+        let tent = Supersampler::new(SamplePattern::Regular(4))
+            .with_filter(Filter::Tent { radius: 1.5 })
+            .render(5, 1, edge);
+        assert_eq!(boxed[1][0], Color::white());
+        assert!(tent[1][0].r() < 1.0);
+        assert!(tent[3][0].r() > 0.0);
+    }
+
+    #[test]
+    fn adaptive_refines_only_high_contrast_pixels() {
+        let calls = Cell::new(0);
+        let sampler = Supersampler::new(SamplePattern::Adaptive {
+            min: 1,
+            max: 4,
+            threshold: 0.1,
+        });
+
+        sampler.render(5, 2, |_, _| {
+            calls.set(calls.get() + 1);
+            Color::white()
+        });
+        assert_eq!(calls.get(), 10);
+
+        calls.set(0);
+        let canvas = sampler.render(5, 2, |x, y| {
+            calls.set(calls.get() + 1);
+            edge(x, y)
+        });
+        // Columns 1 and 2 straddle the edge, on both rows.
+        assert_eq!(calls.get(), 10 + 4 * 16);
+        assert!(canvas[2][0].r() > 0.0 && canvas[2][0].r() < 1.0);
+    }
+}
@@ -0,0 +1,155 @@
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+use crate::primitives::{Tuple, Vector};
+
+// ------------------------------------------------------
+/// Maps a point of the unit square onto the unit disk (Shirley-Chiu
+/// concentric mapping), preserving stratification.
+pub fn sample_disk(u: f64, v: f64) -> (f64, f64) {
+    let (a, b) = (2.0 * u - 1.0, 2.0 * v - 1.0);
+    if a == 0.0 && b == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let (r, theta) = if a.abs() > b.abs() {
+        (a, FRAC_PI_4 * (b / a))
+    } else {
+        (b, FRAC_PI_2 - FRAC_PI_4 * (a / b))
+    };
+    (r * theta.cos(), r * theta.sin())
+}
+
+/// Maps a point of the unit square uniformly onto a regular polygon with
+/// `blades` corners inscribed in the unit circle, the first corner at
+/// angle `rotation`.
+pub fn sample_polygon(blades: usize, rotation: f64, u: f64, v: f64) -> (f64, f64) {
+    if blades < 3 {
+        panic!("A polygon needs at least three blades.");
+    }
+
+    // Pick one of the triangles fanning out from the center, then reuse the
+    // remainder of `u` inside it.
+    let scaled = u * blades as f64;
+    let index = (scaled as usize).min(blades - 1);
+    let u = scaled - index as f64;
+
+    let step = 2.0 * PI / blades as f64;
+    let a0 = rotation + step * index as f64;
+    let a1 = a0 + step;
+
+    let su = u.sqrt();
+    let (b0, b1) = (su * (1.0 - v), su * v);
+    (b0 * a0.cos() + b1 * a1.cos(), b0 * a0.sin() + b1 * a1.sin())
+}
+
+/// Direction in the hemisphere around the unit vector `normal`, with density
+/// proportional to the cosine to the normal (`cos / PI`).
+pub fn cosine_hemisphere(normal: Vector, u: f64, v: f64) -> Vector {
+    let (x, y) = sample_disk(u, v);
+    let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+    let (tangent, bitangent) = orthonormal_basis(normal);
+    tangent * x + bitangent * y + normal * z
+}
+
+/// Two unit vectors completing `normal` to an orthonormal basis (Duff et al.,
+/// "Building an Orthonormal Basis, Revisited").
+pub fn orthonormal_basis(normal: Vector) -> (Vector, Vector) {
+    let sign = 1.0f64.copysign(normal.z());
+    let a = -1.0 / (sign + normal.z());
+    let b = normal.x() * normal.y() * a;
+    (
+        Vector::new(
+            1.0 + sign * normal.x() * normal.x() * a,
+            sign * b,
+            -sign * normal.x(),
+        ),
+        Vector::new(b, sign + normal.y() * normal.y() * a, -normal.y()),
+    )
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampling::Rng;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn disk_samples_inside_unit_disk() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            let (x, y) = sample_disk(rng.next_f64(), rng.next_f64());
+            assert!(x * x + y * y <= 1.0 + 1e-12);
+        }
+        assert_eq!(sample_disk(0.5, 0.5), (0.0, 0.0));
+        let (x, y) = sample_disk(1.0, 0.5);
+        assert_abs_diff_eq!(x, 1.0);
+        assert_abs_diff_eq!(y, 0.0);
+    }
+
+    #[test]
+    fn disk_samples_are_uniform() {
+        // Half the area of the unit disk lies within radius 1/sqrt(2).
+        let mut rng = Rng::new(2);
+        let n = 20_000;
+        let inner = (0..n)
+            .filter(|_| {
+                let (x, y) = sample_disk(rng.next_f64(), rng.next_f64());
+                x * x + y * y < 0.5
+            })
+            .count();
+        assert!((inner as f64 / n as f64 - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn polygon_samples_inside_polygon() {
+        let blades = 6;
+        let apothem = (PI / blades as f64).cos();
+        let mut rng = Rng::new(3);
+        for _ in 0..1000 {
+            let (x, y) = sample_polygon(blades, 0.0, rng.next_f64(), rng.next_f64());
+            // Inside every edge's half plane.
+            for i in 0..blades {
+                let normal = (2.0 * i as f64 + 1.0) * PI / blades as f64;
+                assert!(x * normal.cos() + y * normal.sin() <= apothem + 1e-12);
+            }
+        }
+        let (x, y) = sample_polygon(blades, 0.0, 0.0, 0.0);
+        assert_abs_diff_eq!(x, 0.0);
+        assert_abs_diff_eq!(y, 0.0);
+    }
+
+    #[test]
+    fn orthonormal_basis_is_orthonormal() {
+        for n in [
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(0.0, 0.0, -1.0),
+            Vector::new(1.0, 2.0, -3.0).normalize(),
+            Vector::new(0.0, 1.0, 0.0),
+        ] {
+            let (t, b) = orthonormal_basis(n);
+            assert_abs_diff_eq!(t.magnitude(), 1.0, epsilon = 1e-12);
+            assert_abs_diff_eq!(b.magnitude(), 1.0, epsilon = 1e-12);
+            assert_abs_diff_eq!(t.dot(b), 0.0, epsilon = 1e-12);
+            assert_abs_diff_eq!(t.dot(n), 0.0, epsilon = 1e-12);
+            assert_abs_diff_eq!(b.dot(n), 0.0, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn cosine_hemisphere_distribution() {
+        let normal = Vector::new(1.0, 1.0, 0.0).normalize();
+        let mut rng = Rng::new(6);
+        let n = 20_000;
+        let mut sum = 0.0;
+        for _ in 0..n {
+            let dir = cosine_hemisphere(normal, rng.next_f64(), rng.next_f64());
+            assert_abs_diff_eq!(dir.magnitude(), 1.0, epsilon = 1e-9);
+            let cos = dir.dot(normal);
+            assert!(cos >= 0.0);
+            sum += cos;
+        }
+        // E[cos] under a cosine-weighted density is 2/3.
+        assert!((sum / n as f64 - 2.0 / 3.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::OnceLock;
+
+use crate::primitives::{cie_xyz, Color, Xyz};
+
+/// Shortest wavelength, in nanometres, the spectral pipeline samples.
+pub const LAMBDA_MIN: f64 = 360.0;
+/// Longest wavelength, in nanometres, the spectral pipeline samples.
+pub const LAMBDA_MAX: f64 = 830.0;
+
+/// Spacing of the grid spectra are integrated over, in nanometres.
+const STEP: f64 = 1.0;
+
+// ------------------------------------------------------
+/// Integral of `f` over the sampled range.
+pub(crate) fn integrate(f: impl Fn(f64) -> f64) -> f64 {
+    let n = ((LAMBDA_MAX - LAMBDA_MIN) / STEP) as usize;
+    (0..n)
+        .map(|i| f(LAMBDA_MIN + (i as f64 + 0.5) * STEP))
+        .sum::<f64>()
+        * STEP
+}
+
+/// Integral of the `y` matching function, which normalizes luminance.
+pub(crate) fn cie_y_integral() -> f64 {
+    static INTEGRAL: OnceLock<f64> = OnceLock::new();
+    *INTEGRAL.get_or_init(|| integrate(|lambda| cie_xyz(lambda)[1]))
+}
+
+/// Tristimulus values of the equal energy spectrum `E` with unit luminance.
+pub(crate) fn equal_energy() -> Xyz {
+    let [x, y, z] = [0, 1, 2].map(|i| integrate(|lambda| cie_xyz(lambda)[i]) / cie_y_integral());
+    Xyz { x, y, z }
+}
+
+/// Linear sRGB `Color` of the tristimulus values `xyz`, white balanced so
+/// that the equal energy spectrum `E` is white.
+pub fn xyz_to_color(xyz: Xyz) -> Color {
+    static WHITE: OnceLock<Color> = OnceLock::new();
+    let white = WHITE.get_or_init(|| Color::from_xyz(equal_energy()));
+    let color: Color = Color::from_xyz(xyz);
+    Color::new(
+        color.r() / white.r(),
+        color.g() / white.g(),
+        color.b() / white.b(),
+    )
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn cie_matching_functions() {
+        // Peaks of the tabulated functions.
+        assert_abs_diff_eq!(cie_xyz(555.0)[1], 1.0, epsilon = 0.02);
+        assert_abs_diff_eq!(cie_xyz(600.0)[0], 1.06, epsilon = 0.02);
+        assert_abs_diff_eq!(cie_xyz(445.0)[2], 1.78, epsilon = 0.04);
+        // The CIE tables integrate y to 106.86 over 360 to 830 nm.
+        assert_abs_diff_eq!(cie_y_integral(), 106.86, epsilon = 0.5);
+    }
+
+    #[test]
+    fn xyz_equal_energy_is_white() {
+        assert_abs_diff_eq!(
+            xyz_to_color(equal_energy()),
+            Color::white(),
+            epsilon = 1.0e-12
+        );
+        let black = Xyz {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        assert_eq!(xyz_to_color(black), Color::black());
+    }
+}
@@ -0,0 +1,131 @@
+// ------------------------------------------------------
+/// Refractive index as a function of wavelength, in nanometres.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ior {
+    Constant(f64),
+    /// `n = a + b / lambda^2`, with `lambda` in micrometres.
+    Cauchy {
+        a: f64,
+        b: f64,
+    },
+    /// `n^2 = 1 + sum(b_i lambda^2 / (lambda^2 - c_i))`, with `lambda` in
+    /// micrometres.
+    Sellmeier {
+        b: [f64; 3],
+        c: [f64; 3],
+    },
+}
+
+impl Ior {
+    /// Schott N-BK7 crown glass.
+    pub fn bk7() -> Self {
+        Ior::Sellmeier {
+            b: [1.039_612_12, 0.231_792_344, 1.010_469_45],
+            c: [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+        }
+    }
+
+    /// Fused silica (Malitson 1965).
+    pub fn fused_silica() -> Self {
+        Ior::Sellmeier {
+            b: [0.696_166_3, 0.407_942_6, 0.897_479_4],
+            c: [0.004_679_148, 0.013_512_063, 97.934_003],
+        }
+    }
+
+    pub fn at(&self, lambda: f64) -> f64 {
+        let micro = lambda / 1000.0;
+        let l2 = micro * micro;
+        match *self {
+            Ior::Constant(n) => n,
+            Ior::Cauchy { a, b } => a + b / l2,
+            Ior::Sellmeier { b, c } => {
+                let sum: f64 = (0..3).map(|i| b[i] * l2 / (l2 - c[i])).sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+
+    /// Whether the index varies with wavelength, so refracted paths must
+    /// follow a single wavelength.
+    pub fn is_dispersive(&self) -> bool {
+        !matches!(self, Ior::Constant(_))
+    }
+
+    /// Abbe number `(n_d - 1) / (n_F - n_C)`; lower values disperse more.
+    pub fn abbe_number(&self) -> f64 {
+        (self.at(587.56) - 1.0) / (self.at(486.13) - self.at(656.27))
+    }
+}
+
+impl Default for Ior {
+    fn default() -> Self {
+        Ior::Constant(1.0)
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Color;
+    use crate::sampling::Rng;
+    use crate::spectral::{SampledSpectrum, SampledWavelengths};
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn ior_catalogue_glasses() {
+        assert_abs_diff_eq!(Ior::bk7().at(587.56), 1.5168, epsilon = 1.0e-4);
+        assert_abs_diff_eq!(Ior::bk7().abbe_number(), 64.17, epsilon = 0.05);
+        assert_abs_diff_eq!(Ior::fused_silica().at(587.56), 1.4585, epsilon = 1.0e-4);
+        assert_abs_diff_eq!(Ior::fused_silica().abbe_number(), 67.8, epsilon = 0.1);
+
+        let cauchy = Ior::Cauchy { a: 1.5, b: 0.004 };
+        assert_abs_diff_eq!(cauchy.at(500.0), 1.516, epsilon = 1.0e-12);
+        assert!(cauchy.is_dispersive());
+        assert!(!Ior::Constant(1.5).is_dispersive());
+        assert_eq!(Ior::default().at(400.0), 1.0);
+    }
+
+    #[test]
+    fn ior_normal_dispersion() {
+        for ior in [Ior::bk7(), Ior::fused_silica()] {
+            assert!(ior.at(400.0) > ior.at(550.0));
+            assert!(ior.at(550.0) > ior.at(700.0));
+        }
+    }
+
+    /// Deviation of a ray entering a prism with apex angle `apex` at
+    /// `incidence`, or `None` on total internal reflection.
+    fn deviation(n: f64, apex: f64, incidence: f64) -> Option<f64> {
+        let inside = (incidence.sin() / n).asin();
+        let exit = n * (apex - inside).sin();
+        (exit.abs() <= 1.0).then(|| incidence + exit.asin() - apex)
+    }
+
+    #[test]
+    fn ior_prism_splits_white_light() {
+        // White light through a 60 degree BK7 prism near minimum deviation:
+        // the rays bent more than green are blue, the others red.
+        let (apex, incidence) = (60f64.to_radians(), 50f64.to_radians());
+        let ior = Ior::bk7();
+        let split = deviation(ior.at(550.0), apex, incidence).unwrap();
+
+        let mut rng = Rng::new(3);
+        let (mut more, mut less) = (Color::black(), Color::black());
+        let n = 20_000;
+        for _ in 0..n {
+            let mut wavelengths = SampledWavelengths::sample(rng.next_f64());
+            let bent = deviation(ior.at(wavelengths.lambda(0)), apex, incidence).unwrap();
+            wavelengths.terminate_secondary();
+            let color = SampledSpectrum::constant(1.0).to_color(&wavelengths) * (1.0 / n as f64);
+            if bent > split {
+                more = more + color;
+            } else {
+                less = less + color;
+            }
+        }
+        assert!(more.b() > more.r() && less.r() > less.b());
+        assert_abs_diff_eq!(more + less, Color::white(), epsilon = 0.03);
+    }
+}
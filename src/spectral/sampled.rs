@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::ops::{Add, Mul};
+use std::sync::{Mutex, OnceLock};
+
+use crate::primitives::{Color, Xyz};
+use crate::spectral::{cie_xyz, cie_y_integral, xyz_to_color, Spectrum, LAMBDA_MAX, LAMBDA_MIN};
+
+/// Wavelengths carried by each path.
+pub const WAVELENGTH_SAMPLES: usize = 4;
+
+// ------------------------------------------------------
+/// Wavelengths a path carries: a hero wavelength and evenly spaced
+/// companions, each with its sampling density.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SampledWavelengths {
+    lambda: [f64; WAVELENGTH_SAMPLES],
+    pdf: [f64; WAVELENGTH_SAMPLES],
+}
+
+impl SampledWavelengths {
+    /// Stratified wavelengths over the visible range for the sample `u`.
+    pub fn sample(u: f64) -> Self {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let mut lambda = [0.0; WAVELENGTH_SAMPLES];
+        for (i, lambda) in lambda.iter_mut().enumerate() {
+            let offset = (u + i as f64 / WAVELENGTH_SAMPLES as f64).fract();
+            *lambda = LAMBDA_MIN + offset * range;
+        }
+        SampledWavelengths {
+            lambda,
+            pdf: [1.0 / range; WAVELENGTH_SAMPLES],
+        }
+    }
+
+    pub fn lambda(&self, i: usize) -> f64 {
+        self.lambda[i]
+    }
+
+    pub fn pdf(&self, i: usize) -> f64 {
+        self.pdf[i]
+    }
+
+    /// Keeps only the hero wavelength, for paths whose direction depends on
+    /// the wavelength, such as light refracted by a dispersive medium.
+    pub fn terminate_secondary(&mut self) {
+        if self.is_secondary_terminated() {
+            return;
+        }
+        self.pdf[1..].fill(0.0);
+        self.pdf[0] /= WAVELENGTH_SAMPLES as f64;
+    }
+
+    pub fn is_secondary_terminated(&self) -> bool {
+        self.pdf[1..].iter().all(|&pdf| pdf == 0.0)
+    }
+}
+
+// ------------------------------------------------------
+/// Spectral quantity at the wavelengths of a `SampledWavelengths`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SampledSpectrum {
+    values: [f64; WAVELENGTH_SAMPLES],
+}
+
+impl SampledSpectrum {
+    pub fn new(values: [f64; WAVELENGTH_SAMPLES]) -> Self {
+        SampledSpectrum { values }
+    }
+
+    pub fn constant(value: f64) -> Self {
+        SampledSpectrum::new([value; WAVELENGTH_SAMPLES])
+    }
+
+    /// Values at `wavelengths` of a smooth spectrum whose color is `color`,
+    /// at any brightness: `Spectrum::from_rgb` of its hue at half
+    /// brightness, scaled. Fitting is slow, so fits are cached by hue
+    /// rounded to 8 bits per channel.
+    pub fn from_rgb(color: Color, wavelengths: &SampledWavelengths) -> Self {
+        static FITS: OnceLock<Mutex<HashMap<[u8; 3], Spectrum>>> = OnceLock::new();
+
+        let rgb = [color.r(), color.g(), color.b()].map(|c| c.max(0.0));
+        let max = rgb[0].max(rgb[1]).max(rgb[2]);
+        if max == 0.0 {
+            return SampledSpectrum::constant(0.0);
+        }
+
+        let hue = rgb.map(|c| (c / max * 255.0).round() as u8);
+        let mut fits = FITS.get_or_init(Default::default).lock().unwrap();
+        let spectrum = fits.entry(hue).or_insert_with(|| {
+            let [r, g, b] = hue.map(|c| c as f64 / 510.0);
+            Spectrum::from_rgb(Color::new(r, g, b))
+        });
+        spectrum.sample(wavelengths) * (2.0 * max)
+    }
+
+    /// `f` evaluated at each of the `wavelengths`.
+    pub fn from_fn(wavelengths: &SampledWavelengths, f: impl Fn(f64) -> f64) -> Self {
+        SampledSpectrum::new(wavelengths.lambda.map(f))
+    }
+
+    pub fn values(&self) -> [f64; WAVELENGTH_SAMPLES] {
+        self.values
+    }
+
+    /// Monte Carlo estimate of the tristimulus values of the spectrum of
+    /// which this is a sample, normalized so that a unit spectrum has unit
+    /// luminance.
+    pub fn to_xyz(&self, wavelengths: &SampledWavelengths) -> Xyz {
+        let mut xyz = [0.0; 3];
+        for i in 0..WAVELENGTH_SAMPLES {
+            if wavelengths.pdf[i] == 0.0 {
+                continue;
+            }
+            let cmf = cie_xyz(wavelengths.lambda[i]);
+            for (c, cmf) in xyz.iter_mut().zip(cmf) {
+                *c += self.values[i] * cmf / wavelengths.pdf[i];
+            }
+        }
+        let [x, y, z] = xyz.map(|c| c / (WAVELENGTH_SAMPLES as f64 * cie_y_integral()));
+        Xyz { x, y, z }
+    }
+
+    pub fn to_color(&self, wavelengths: &SampledWavelengths) -> Color {
+        xyz_to_color(self.to_xyz(wavelengths))
+    }
+}
+
+impl Add for SampledSpectrum {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut values = self.values;
+        for (a, b) in values.iter_mut().zip(other.values) {
+            *a += b;
+        }
+        SampledSpectrum::new(values)
+    }
+}
+
+impl Mul for SampledSpectrum {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut values = self.values;
+        for (a, b) in values.iter_mut().zip(other.values) {
+            *a *= b;
+        }
+        SampledSpectrum::new(values)
+    }
+}
+
+impl Mul<f64> for SampledSpectrum {
+    type Output = Self;
+
+    fn mul(self, other: f64) -> Self {
+        SampledSpectrum::new(self.values.map(|a| a * other))
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampling::Rng;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn sampled_wavelengths_are_stratified() {
+        let wavelengths = SampledWavelengths::sample(0.9);
+        let step = (LAMBDA_MAX - LAMBDA_MIN) / 4.0;
+        assert_abs_diff_eq!(
+            wavelengths.lambda(0),
+            LAMBDA_MIN + 0.9 * 4.0 * step,
+            epsilon = 1.0e-9
+        );
+        assert_abs_diff_eq!(
+            wavelengths.lambda(1),
+            LAMBDA_MIN + 0.15 * 4.0 * step,
+            epsilon = 1.0e-9
+        );
+        for i in 0..WAVELENGTH_SAMPLES {
+            assert!(wavelengths.lambda(i) >= LAMBDA_MIN && wavelengths.lambda(i) < LAMBDA_MAX);
+        }
+    }
+
+    #[test]
+    fn sampled_spectrum_arithmetic() {
+        let a = SampledSpectrum::new([1.0, 2.0, 3.0, 4.0]);
+        let b = SampledSpectrum::constant(2.0);
+        assert_eq!((a + b).values(), [3.0, 4.0, 5.0, 6.0]);
+        assert_eq!((a * b).values(), [2.0, 4.0, 6.0, 8.0]);
+        assert_eq!((a * 0.5).values(), [0.5, 1.0, 1.5, 2.0]);
+    }
+
+    #[test]
+    fn sampled_spectrum_unit_is_white() {
+        let mut rng = Rng::new(1);
+        let mut full = Color::black();
+        let mut hero = Color::black();
+        let n = 20_000;
+        for _ in 0..n {
+            let mut wavelengths = SampledWavelengths::sample(rng.next_f64());
+            full = full + SampledSpectrum::constant(1.0).to_color(&wavelengths);
+            wavelengths.terminate_secondary();
+            assert!(wavelengths.is_secondary_terminated());
+            hero = hero + SampledSpectrum::constant(1.0).to_color(&wavelengths);
+        }
+        assert_abs_diff_eq!(full * (1.0 / n as f64), Color::white(), epsilon = 0.01);
+        assert_abs_diff_eq!(hero * (1.0 / n as f64), Color::white(), epsilon = 0.03);
+    }
+
+    #[test]
+    fn sampled_spectrum_from_rgb_keeps_color() {
+        let mut rng = Rng::new(2);
+        for color in [
+            Color::new(0.8, 0.3, 0.1),
+            Color::new(0.2, 0.4, 0.9) * 5.0,
+            Color::new(0.5, 0.5, 0.5),
+        ] {
+            let n = 20_000;
+            let sum = (0..n).fold(Color::black(), |acc, _| {
+                let wavelengths = SampledWavelengths::sample(rng.next_f64());
+                acc + SampledSpectrum::from_rgb(color, &wavelengths).to_color(&wavelengths)
+            });
+            let mean = sum * (1.0 / n as f64);
+            let tolerance = 0.02 * color.r().max(color.g()).max(color.b());
+            assert_abs_diff_eq!(mean, color, epsilon = tolerance);
+        }
+
+        let wavelengths = SampledWavelengths::sample(0.3);
+        let dim = SampledSpectrum::from_rgb(Color::new(0.4, 0.2, 0.1), &wavelengths);
+        let bright = SampledSpectrum::from_rgb(Color::new(4.0, 2.0, 1.0), &wavelengths);
+        for (dim, bright) in dim.values().into_iter().zip(bright.values()) {
+            assert_abs_diff_eq!(bright, 10.0 * dim, epsilon = 1.0e-9);
+        }
+        let black = SampledSpectrum::from_rgb(Color::black(), &wavelengths);
+        assert_eq!(black.values(), [0.0; WAVELENGTH_SAMPLES]);
+    }
+}
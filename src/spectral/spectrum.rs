@@ -0,0 +1,311 @@
+use crate::primitives::{Color, Xyz};
+use crate::spectral::{
+    cie_xyz, cie_y_integral, integrate, xyz_to_color, SampledSpectrum, SampledWavelengths,
+    LAMBDA_MAX, LAMBDA_MIN,
+};
+
+// ------------------------------------------------------
+/// Continuous spectral distribution over wavelengths in nanometres.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Spectrum {
+    Constant(f64),
+    /// Linearly interpolated `values` spaced `step` apart from `start`, held
+    /// constant beyond both ends.
+    Sampled {
+        start: f64,
+        step: f64,
+        values: Vec<f64>,
+    },
+    /// `scale` times a sigmoid of a quadratic in the normalized wavelength,
+    /// as fitted by `from_rgb` (Jakob and Hanika 2019).
+    Sigmoid {
+        coefficients: [f64; 3],
+        scale: f64,
+    },
+}
+
+impl Spectrum {
+    pub fn sampled(start: f64, step: f64, values: Vec<f64>) -> Self {
+        if values.is_empty() || step.is_nan() || step <= 0.0 {
+            panic!("Invalid spectrum samples.");
+        }
+        Spectrum::Sampled {
+            start,
+            step,
+            values,
+        }
+    }
+
+    /// Smooth spectrum whose color is `color`. Colors with channels in
+    /// `[0, 1]` give reflectances within the same bounds; brighter ones are
+    /// scaled for use as emission.
+    pub fn from_rgb(color: Color) -> Self {
+        let rgb = [color.r(), color.g(), color.b()];
+        if rgb.iter().any(|&c| c.is_nan() || c < 0.0) {
+            panic!("Invalid spectrum color.");
+        }
+        if rgb[0] == rgb[1] && rgb[1] == rgb[2] {
+            return Spectrum::Constant(rgb[0]);
+        }
+
+        let max = rgb[0].max(rgb[1]).max(rgb[2]);
+        let scale = if max > 1.0 { 2.0 * max } else { 1.0 };
+        Spectrum::Sigmoid {
+            coefficients: fit(rgb.map(|c| c / scale)),
+            scale,
+        }
+    }
+
+    pub fn eval(&self, lambda: f64) -> f64 {
+        match self {
+            Spectrum::Constant(value) => *value,
+            Spectrum::Sampled {
+                start,
+                step,
+                values,
+            } => {
+                let x = ((lambda - start) / step).clamp(0.0, (values.len() - 1) as f64);
+                let i = (x as usize).min(values.len().saturating_sub(2));
+                match values.get(i + 1) {
+                    Some(next) => values[i] + (next - values[i]) * (x - i as f64),
+                    None => values[i],
+                }
+            }
+            Spectrum::Sigmoid {
+                coefficients,
+                scale,
+            } => scale * sigmoid(*coefficients, lambda),
+        }
+    }
+
+    /// Values at the `wavelengths`.
+    pub fn sample(&self, wavelengths: &SampledWavelengths) -> SampledSpectrum {
+        SampledSpectrum::from_fn(wavelengths, |lambda| self.eval(lambda))
+    }
+
+    /// Color of the spectrum as emitted light, or as a reflectance under
+    /// equal energy light.
+    pub fn to_color(&self) -> Color {
+        self.to_color_under(&Spectrum::Constant(1.0))
+    }
+
+    /// Color of the spectrum as a reflectance lit by `illuminant`, relative
+    /// to a white reflector under the same light.
+    pub fn to_color_under(&self, illuminant: &Spectrum) -> Color {
+        let white = integrate(|lambda| illuminant.eval(lambda) * cie_xyz(lambda)[1]);
+        let [x, y, z] = [0, 1, 2].map(|i| {
+            integrate(|lambda| self.eval(lambda) * illuminant.eval(lambda) * cie_xyz(lambda)[i])
+                / white
+        });
+        xyz_to_color(Xyz { x, y, z })
+    }
+
+    /// Luminance relative to the unit spectrum.
+    pub fn luminance(&self) -> f64 {
+        integrate(|lambda| self.eval(lambda) * cie_xyz(lambda)[1]) / cie_y_integral()
+    }
+}
+
+fn sigmoid([c0, c1, c2]: [f64; 3], lambda: f64) -> f64 {
+    let x = (lambda - LAMBDA_MIN) / (LAMBDA_MAX - LAMBDA_MIN);
+    let t = c0 * x * x + c1 * x + c2;
+    0.5 + t / (2.0 * (1.0 + t * t).sqrt())
+}
+
+/// Sigmoid coefficients whose spectrum has the color `rgb`, found by
+/// damped Gauss-Newton iteration.
+fn fit(rgb: [f64; 3]) -> [f64; 3] {
+    let residual = |c: [f64; 3]| {
+        let color = Spectrum::Sigmoid {
+            coefficients: c,
+            scale: 1.0,
+        }
+        .to_color();
+        [color.r() - rgb[0], color.g() - rgb[1], color.b() - rgb[2]]
+    };
+    let norm = |r: [f64; 3]| r.iter().map(|r| r * r).sum::<f64>();
+
+    let mut c = [0.0; 3];
+    let mut r = residual(c);
+    let mut damping = 1.0e-3;
+    for _ in 0..100 {
+        if norm(r) < 1.0e-14 {
+            break;
+        }
+
+        // Jacobian by forward differences, one column per coefficient.
+        let mut jacobian = [[0.0; 3]; 3];
+        for (j, column) in jacobian.iter_mut().enumerate() {
+            let mut shifted = c;
+            shifted[j] += 1.0e-6;
+            let rs = residual(shifted);
+            *column = [0, 1, 2].map(|i| (rs[i] - r[i]) / 1.0e-6);
+        }
+
+        // Normal equations (J^T J + damping I) step = -J^T r.
+        let mut a = [[0.0; 3]; 3];
+        let mut b = [0.0; 3];
+        for i in 0..3 {
+            for k in 0..3 {
+                a[i][k] = (0..3).map(|m| jacobian[i][m] * jacobian[k][m]).sum();
+            }
+            a[i][i] += damping;
+            b[i] = -(0..3).map(|m| jacobian[i][m] * r[m]).sum::<f64>();
+        }
+        let Some(step) = solve3(a, b) else {
+            break;
+        };
+
+        let next = [c[0] + step[0], c[1] + step[1], c[2] + step[2]];
+        let rn = residual(next);
+        if norm(rn) < norm(r) {
+            (c, r) = (next, rn);
+            damping = (damping / 3.0).max(1.0e-12);
+        } else {
+            damping *= 4.0;
+        }
+    }
+    c
+}
+
+/// Solves `a x = b` by Cramer's rule.
+fn solve3(a: [[f64; 3]; 3], b: [f64; 3]) -> Option<[f64; 3]> {
+    let det = |m: [[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(a);
+    if d.abs() < 1.0e-300 {
+        return None;
+    }
+    Some([0, 1, 2].map(|j| {
+        let mut m = a;
+        for i in 0..3 {
+            m[i][j] = b[i];
+        }
+        det(m) / d
+    }))
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampling::Rng;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn spectrum_sampled_interpolates() {
+        let s = Spectrum::sampled(400.0, 100.0, vec![0.0, 1.0, 0.5]);
+        assert_eq!(s.eval(350.0), 0.0);
+        assert_eq!(s.eval(450.0), 0.5);
+        assert_eq!(s.eval(550.0), 0.75);
+        assert_eq!(s.eval(700.0), 0.5);
+        assert_eq!(Spectrum::sampled(0.0, 1.0, vec![2.0]).eval(500.0), 2.0);
+    }
+
+    #[test]
+    fn spectrum_from_rgb_round_trip() {
+        let colors = [
+            Color::new(0.8, 0.3, 0.1),
+            Color::new(0.1, 0.6, 0.2),
+            Color::new(0.2, 0.3, 0.9),
+            Color::new(0.5, 0.5, 0.5),
+            Color::new(4.0, 2.0, 1.0),
+        ];
+        for color in colors {
+            let spectrum = Spectrum::from_rgb(color);
+            assert_abs_diff_eq!(
+                spectrum.to_color(),
+                color,
+                epsilon = 1.0e-3 * color.r().max(1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn spectrum_reflectances_stay_bounded() {
+        for color in [Color::new(0.95, 0.05, 0.05), Color::new(0.02, 0.02, 0.9)] {
+            let spectrum = Spectrum::from_rgb(color);
+            for lambda in (360..=830).step_by(10) {
+                let value = spectrum.eval(lambda as f64);
+                assert!((0.0..=1.0).contains(&value));
+            }
+        }
+    }
+
+    #[test]
+    fn spectrum_sampling_converges_to_color() {
+        let spectrum = Spectrum::from_rgb(Color::new(0.7, 0.4, 0.2));
+        let mut rng = Rng::new(2);
+        let n = 20_000;
+        let sum = (0..n).fold(Color::black(), |acc, _| {
+            let wavelengths = SampledWavelengths::sample(rng.next_f64());
+            acc + spectrum.sample(&wavelengths).to_color(&wavelengths)
+        });
+        assert_abs_diff_eq!(sum * (1.0 / n as f64), spectrum.to_color(), epsilon = 0.01);
+    }
+
+    #[test]
+    fn spectrum_metamerism() {
+        // A flat grey and the same grey plus a "metameric black", a
+        // spectrum the eye cannot see, match under equal energy light but
+        // not under a fluorescent-like light with a few strong lines.
+        let grey = Spectrum::Constant(0.5);
+        let lambdas: Vec<f64> = (0..=470).map(|i| 360.0 + i as f64).collect();
+        let cmfs: Vec<[f64; 3]> = lambdas.iter().map(|&l| cie_xyz(l)).collect();
+        let mut black: Vec<f64> = lambdas
+            .iter()
+            .map(|l| 0.2 * (2.0 * std::f64::consts::PI * (l - 360.0) / 90.0).sin())
+            .collect();
+        // Remove the parts of the wiggle the matching functions respond to.
+        let mut basis: Vec<Vec<f64>> = Vec::new();
+        for i in 0..3 {
+            let mut v: Vec<f64> = cmfs.iter().map(|c| c[i]).collect();
+            for u in &basis {
+                let d: f64 = v.iter().zip(u).map(|(a, b)| a * b).sum();
+                v.iter_mut().zip(u).for_each(|(a, b)| *a -= d * b);
+            }
+            let len = v.iter().map(|a| a * a).sum::<f64>().sqrt();
+            v.iter_mut().for_each(|a| *a /= len);
+            basis.push(v);
+        }
+        for u in &basis {
+            let d: f64 = black.iter().zip(u).map(|(a, b)| a * b).sum();
+            black.iter_mut().zip(u).for_each(|(a, b)| *a -= d * b);
+        }
+        let metamer = Spectrum::sampled(360.0, 1.0, black.iter().map(|b| 0.5 + b).collect());
+
+        assert_abs_diff_eq!(metamer.to_color(), grey.to_color(), epsilon = 2.0e-3);
+        let lines = Spectrum::sampled(
+            360.0,
+            10.0,
+            (0..=47)
+                .map(|i| if [8, 18, 25].contains(&i) { 10.0 } else { 0.1 })
+                .collect(),
+        );
+        let difference = metamer.to_color_under(&lines) - grey.to_color_under(&lines);
+        assert!(
+            difference
+                .r()
+                .abs()
+                .max(difference.g().abs())
+                .max(difference.b().abs())
+                > 0.01,
+            "{:?}",
+            difference
+        );
+    }
+
+    #[test]
+    fn spectrum_luminance() {
+        assert_abs_diff_eq!(Spectrum::Constant(0.5).luminance(), 0.5, epsilon = 1.0e-12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn spectrum_invalid_samples() {
+        Spectrum::sampled(400.0, 0.0, vec![1.0]);
+    }
+}
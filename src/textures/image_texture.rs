@@ -0,0 +1,223 @@
+use crate::primitives::{Canvas, Color};
+
+// ------------------------------------------------------
+/// How a texture is reconstructed between pixel centers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TextureFilter {
+    Nearest,
+    #[default]
+    Bilinear,
+    /// Catmull-Rom over the surrounding 4x4 pixels.
+    Bicubic,
+}
+
+/// What happens to texture coordinates outside `[0, 1]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    #[default]
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+impl WrapMode {
+    /// Maps a possibly out-of-range pixel index into `0..size`.
+    pub fn apply(&self, index: i64, size: usize) -> usize {
+        let size = size as i64;
+        let index = match self {
+            WrapMode::Repeat => index.rem_euclid(size),
+            WrapMode::Clamp => index.clamp(0, size - 1),
+            WrapMode::Mirror => {
+                let m = index.rem_euclid(2 * size);
+                if m < size {
+                    m
+                } else {
+                    2 * size - 1 - m
+                }
+            }
+        };
+        index as usize
+    }
+}
+
+// ------------------------------------------------------
+/// A `Canvas` sampled by texture coordinates, `(0, 0)` at the bottom left.
+#[derive(Debug)]
+pub struct ImageTexture {
+    canvas: Canvas,
+    filter: TextureFilter,
+    wrap: WrapMode,
+}
+
+impl ImageTexture {
+    pub fn new(canvas: Canvas) -> Self {
+        ImageTexture {
+            canvas,
+            filter: TextureFilter::default(),
+            wrap: WrapMode::default(),
+        }
+    }
+
+    pub fn with_filter(self, filter: TextureFilter) -> Self {
+        ImageTexture { filter, ..self }
+    }
+
+    pub fn with_wrap(self, wrap: WrapMode) -> Self {
+        ImageTexture { wrap, ..self }
+    }
+
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    pub fn sample(&self, u: f64, v: f64) -> Color {
+        // Continuous pixel coordinates, with pixel centers at integers.
+        let x = u * self.canvas.width() as f64 - 0.5;
+        let y = (1.0 - v) * self.canvas.height() as f64 - 0.5;
+
+        match self.filter {
+            TextureFilter::Nearest => self.texel(x.round() as i64, y.round() as i64),
+            TextureFilter::Bilinear => self.bilinear(x, y),
+            TextureFilter::Bicubic => self.bicubic(x, y),
+        }
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let x = self.wrap.apply(x, self.canvas.width());
+        let y = self.wrap.apply(y, self.canvas.height());
+        self.canvas.pixel_at(x, y)
+    }
+
+    fn bilinear(&self, x: f64, y: f64) -> Color {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = self.texel(x0, y0) * (1.0 - tx) + self.texel(x0 + 1, y0) * tx;
+        let bottom = self.texel(x0, y0 + 1) * (1.0 - tx) + self.texel(x0 + 1, y0 + 1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+
+    fn bicubic(&self, x: f64, y: f64) -> Color {
+        let (x0, y0) = (x.floor(), y.floor());
+        let wx = catmull_rom_weights(x - x0);
+        let wy = catmull_rom_weights(y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let mut color = Color::black();
+        for (j, wy) in wy.iter().enumerate() {
+            for (i, wx) in wx.iter().enumerate() {
+                let texel = self.texel(x0 + i as i64 - 1, y0 + j as i64 - 1);
+                color = color + texel * (wx * wy);
+            }
+        }
+        color
+    }
+}
+
+/// Weights of the four taps around a sample at fraction `t` past the
+/// second one.
+fn catmull_rom_weights(t: f64) -> [f64; 4] {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    [
+        (-t3 + 2.0 * t2 - t) / 2.0,
+        (3.0 * t3 - 5.0 * t2 + 2.0) / 2.0,
+        (-3.0 * t3 + 4.0 * t2 + t) / 2.0,
+        (t3 - t2) / 2.0,
+    ]
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2x2 texture: red, green on top; blue, white at the bottom.
+    fn quad() -> Canvas {
+        let mut canvas = Canvas::new(2, 2);
+        canvas[0][0] = Color::red();
+        canvas[1][0] = Color::green();
+        canvas[0][1] = Color::blue();
+        canvas[1][1] = Color::white();
+        canvas
+    }
+
+    /// 4x1 texture ramping 0, 1, 2, 3.
+    fn ramp() -> Canvas {
+        let mut canvas = Canvas::new(4, 1);
+        for x in 0..4 {
+            let c = x as f64;
+            canvas[x][0] = Color::new(c, c, c);
+        }
+        canvas
+    }
+
+    #[test]
+    fn wrap_modes() {
+        assert_eq!(WrapMode::Repeat.apply(-1, 4), 3);
+        assert_eq!(WrapMode::Repeat.apply(5, 4), 1);
+        assert_eq!(WrapMode::Clamp.apply(-1, 4), 0);
+        assert_eq!(WrapMode::Clamp.apply(5, 4), 3);
+        assert_eq!(WrapMode::Mirror.apply(-1, 4), 0);
+        assert_eq!(WrapMode::Mirror.apply(4, 4), 3);
+        assert_eq!(WrapMode::Mirror.apply(5, 4), 2);
+        assert_eq!(WrapMode::Mirror.apply(8, 4), 0);
+    }
+
+    #[test]
+    fn texture_nearest() {
+        let texture = ImageTexture::new(quad()).with_filter(TextureFilter::Nearest);
+        assert_eq!(texture.sample(0.25, 0.75), Color::red());
+        assert_eq!(texture.sample(0.75, 0.75), Color::green());
+        assert_eq!(texture.sample(0.25, 0.25), Color::blue());
+        assert_eq!(texture.sample(0.9, 0.1), Color::white());
+    }
+
+    #[test]
+    fn texture_bilinear() {
+        let texture = ImageTexture::new(quad()).with_wrap(WrapMode::Clamp);
+        assert_eq!(texture.sample(0.25, 0.75), Color::red());
+        assert_eq!(texture.sample(0.5, 0.75), Color::new(0.5, 0.5, 0.0));
+        assert_eq!(texture.sample(0.5, 0.5), Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn texture_wrap_addressing() {
+        let repeat = ImageTexture::new(ramp())
+            .with_filter(TextureFilter::Nearest)
+            .with_wrap(WrapMode::Repeat);
+        let clamp = ImageTexture::new(ramp())
+            .with_filter(TextureFilter::Nearest)
+            .with_wrap(WrapMode::Clamp);
+        let mirror = ImageTexture::new(ramp())
+            .with_filter(TextureFilter::Nearest)
+            .with_wrap(WrapMode::Mirror);
+
+        // One texel past the right edge.
+        let u = 1.125;
+        assert_eq!(repeat.sample(u, 0.5), Color::new(0.0, 0.0, 0.0));
+        assert_eq!(clamp.sample(u, 0.5), Color::new(3.0, 3.0, 3.0));
+        assert_eq!(mirror.sample(u, 0.5), Color::new(3.0, 3.0, 3.0));
+
+        let u = 1.375;
+        assert_eq!(repeat.sample(u, 0.5), Color::new(1.0, 1.0, 1.0));
+        assert_eq!(mirror.sample(u, 0.5), Color::new(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn texture_bicubic() {
+        // Catmull-Rom reproduces a linear ramp exactly between its samples.
+        let texture = ImageTexture::new(ramp())
+            .with_filter(TextureFilter::Bicubic)
+            .with_wrap(WrapMode::Clamp);
+        assert_eq!(texture.sample(0.375, 0.5), Color::new(1.0, 1.0, 1.0));
+        assert_eq!(texture.sample(0.5, 0.5), Color::new(1.5, 1.5, 1.5));
+        assert_eq!(texture.sample(0.4375, 0.5), Color::new(1.25, 1.25, 1.25));
+
+        // And a flat texture stays flat.
+        let flat = ImageTexture::new(Canvas::new_with_bgcolor(3, 3, Color::red()))
+            .with_filter(TextureFilter::Bicubic);
+        assert_eq!(flat.sample(0.123, 0.77), Color::red());
+    }
+}
@@ -0,0 +1,129 @@
+use crate::patterns::{Pattern, PatternTransform};
+use crate::primitives::{Color, Matrix, Point};
+use crate::textures::{cube_uv, ImageTexture, UvMapping};
+
+// ------------------------------------------------------
+/// An image texture wrapped onto an object through a UV mapping, usable
+/// anywhere a pattern is.
+pub struct TextureMap {
+    texture: ImageTexture,
+    mapping: UvMapping,
+    transform: PatternTransform,
+}
+
+impl TextureMap {
+    pub fn new(texture: ImageTexture, mapping: UvMapping) -> Self {
+        TextureMap {
+            texture,
+            mapping,
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        TextureMap {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for TextureMap {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let (u, v) = self.mapping.map(point);
+        self.texture.sample(u, v)
+    }
+}
+
+// ------------------------------------------------------
+/// Six image textures, one per face of the cube `[-1, 1]³`, in
+/// `CubeFace::ALL` order.
+pub struct CubeMap {
+    faces: [ImageTexture; 6],
+    transform: PatternTransform,
+}
+
+impl CubeMap {
+    pub fn new(faces: [ImageTexture; 6]) -> Self {
+        CubeMap {
+            faces,
+            transform: PatternTransform::default(),
+        }
+    }
+
+    pub fn with_transform(self, matrix: Matrix) -> Self {
+        CubeMap {
+            transform: PatternTransform::new(matrix),
+            ..self
+        }
+    }
+}
+
+impl Pattern for CubeMap {
+    fn transform(&self) -> &PatternTransform {
+        &self.transform
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let (face, u, v) = cube_uv(point);
+        self.faces[face.index()].sample(u, v)
+    }
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{Canvas, Tuple};
+    use crate::textures::{CubeFace, TextureFilter};
+
+    fn solid(color: Color) -> ImageTexture {
+        ImageTexture::new(Canvas::new_with_bgcolor(2, 2, color))
+    }
+
+    #[test]
+    fn texture_map_on_sphere() {
+        // Left half of the texture red, right half blue.
+        let mut canvas = Canvas::new(2, 1);
+        canvas[0][0] = Color::red();
+        canvas[1][0] = Color::blue();
+        let texture = ImageTexture::new(canvas).with_filter(TextureFilter::Nearest);
+        let pattern = TextureMap::new(texture, UvMapping::Spherical);
+
+        // u = 0.25 at +x, u = 0.75 at -x.
+        assert_eq!(pattern.pattern_at(Point::new(1.0, 0.0, 0.0)), Color::red());
+        assert_eq!(
+            pattern.pattern_at(Point::new(-1.0, 0.0, 0.0)),
+            Color::blue()
+        );
+    }
+
+    #[test]
+    fn cube_map_faces() {
+        let colors = [
+            Color::new(1.0, 1.0, 0.0),
+            Color::new(0.0, 1.0, 1.0),
+            Color::red(),
+            Color::green(),
+            Color::new(1.0, 0.5, 0.0),
+            Color::new(0.5, 0.0, 1.0),
+        ];
+        let pattern = CubeMap::new(colors.map(solid));
+
+        let points = [
+            Point::new(-1.0, 0.0, 0.0),
+            Point::new(0.0, 0.0, 1.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(0.0, 0.0, -1.0),
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(0.0, -1.0, 0.0),
+        ];
+        for (face, point) in CubeFace::ALL.iter().zip(points) {
+            assert_eq!(pattern.pattern_at(point), colors[face.index()]);
+        }
+    }
+}
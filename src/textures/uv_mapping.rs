@@ -0,0 +1,219 @@
+use std::f64::consts::PI;
+
+use crate::primitives::{Point, Tuple, Vector};
+
+// ------------------------------------------------------
+/// Projects an object-space point onto `(u, v)` texture coordinates in
+/// `[0, 1]`, with `v` running bottom to top.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UvMapping {
+    /// Unit sphere around the origin; `u` wraps around the y axis.
+    Spherical,
+    /// The xz plane, repeating every unit.
+    Planar,
+    /// Unit cylinder around the y axis, repeating every unit of height.
+    Cylindrical,
+}
+
+impl UvMapping {
+    pub fn map(&self, point: Point) -> (f64, f64) {
+        match self {
+            UvMapping::Spherical => spherical(point),
+            UvMapping::Planar => planar(point),
+            UvMapping::Cylindrical => cylindrical(point),
+        }
+    }
+}
+
+fn azimuth_u(point: Point) -> f64 {
+    let theta = point.x().atan2(point.z());
+    let raw_u = theta / (2.0 * PI);
+    // Flip so u increases counterclockwise when viewed from above.
+    1.0 - (raw_u + 0.5)
+}
+
+fn spherical(point: Point) -> (f64, f64) {
+    let radius = Vector::new(point.x(), point.y(), point.z()).magnitude();
+    let phi = (point.y() / radius).acos();
+    (azimuth_u(point), 1.0 - phi / PI)
+}
+
+fn planar(point: Point) -> (f64, f64) {
+    (point.x().rem_euclid(1.0), point.z().rem_euclid(1.0))
+}
+
+fn cylindrical(point: Point) -> (f64, f64) {
+    (azimuth_u(point), point.y().rem_euclid(1.0))
+}
+
+// ------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CubeFace {
+    Left,
+    Front,
+    Right,
+    Back,
+    Up,
+    Down,
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::Left,
+        CubeFace::Front,
+        CubeFace::Right,
+        CubeFace::Back,
+        CubeFace::Up,
+        CubeFace::Down,
+    ];
+
+    /// Face of the axis-aligned cube `[-1, 1]³` that `point` lies on (or
+    /// whose direction it points toward).
+    pub fn from_point(point: Point) -> CubeFace {
+        let (x, y, z) = (point.x(), point.y(), point.z());
+        let coord = x.abs().max(y.abs()).max(z.abs());
+
+        if coord == x {
+            CubeFace::Right
+        } else if coord == -x {
+            CubeFace::Left
+        } else if coord == y {
+            CubeFace::Up
+        } else if coord == -y {
+            CubeFace::Down
+        } else if coord == z {
+            CubeFace::Front
+        } else {
+            CubeFace::Back
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Face and `(u, v)` on that face for a point on the cube `[-1, 1]³`.
+pub fn cube_uv(point: Point) -> (CubeFace, f64, f64) {
+    let (x, y, z) = (point.x(), point.y(), point.z());
+    let wrap = |value: f64| value.rem_euclid(2.0) / 2.0;
+    let face = CubeFace::from_point(point);
+
+    let (u, v) = match face {
+        CubeFace::Front => (wrap(x + 1.0), wrap(y + 1.0)),
+        CubeFace::Back => (wrap(1.0 - x), wrap(y + 1.0)),
+        CubeFace::Left => (wrap(z + 1.0), wrap(y + 1.0)),
+        CubeFace::Right => (wrap(1.0 - z), wrap(y + 1.0)),
+        CubeFace::Up => (wrap(x + 1.0), wrap(1.0 - z)),
+        CubeFace::Down => (wrap(x + 1.0), wrap(z + 1.0)),
+    };
+    (face, u, v)
+}
+
+// ------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn assert_uv(res: (f64, f64), expected: (f64, f64)) {
+        assert_abs_diff_eq!(res.0, expected.0, epsilon = 1.0e-5);
+        assert_abs_diff_eq!(res.1, expected.1, epsilon = 1.0e-5);
+    }
+
+    #[test]
+    fn spherical_mapping() {
+        // Scenario Outline: Using a spherical mapping on a 3D point
+        let s2 = 2.0f64.sqrt() / 2.0;
+        let cases = [
+            (Point::new(0.0, 0.0, -1.0), (0.0, 0.5)),
+            (Point::new(1.0, 0.0, 0.0), (0.25, 0.5)),
+            (Point::new(0.0, 0.0, 1.0), (0.5, 0.5)),
+            (Point::new(-1.0, 0.0, 0.0), (0.75, 0.5)),
+            (Point::new(0.0, 1.0, 0.0), (0.5, 1.0)),
+            (Point::new(0.0, -1.0, 0.0), (0.5, 0.0)),
+            (Point::new(s2, s2, 0.0), (0.25, 0.75)),
+        ];
+        for (p, uv) in cases {
+            assert_uv(UvMapping::Spherical.map(p), uv);
+        }
+    }
+
+    #[test]
+    fn planar_mapping() {
+        // Scenario Outline: Using a planar mapping on a 3D point
+        let cases = [
+            (Point::new(0.25, 0.0, 0.5), (0.25, 0.5)),
+            (Point::new(0.25, 0.0, -0.25), (0.25, 0.75)),
+            (Point::new(0.25, 0.5, -0.25), (0.25, 0.75)),
+            (Point::new(1.25, 0.0, 0.5), (0.25, 0.5)),
+            (Point::new(0.25, 0.0, -1.75), (0.25, 0.25)),
+            (Point::new(1.0, 0.0, -1.0), (0.0, 0.0)),
+            (Point::new(0.0, 0.0, 0.0), (0.0, 0.0)),
+        ];
+        for (p, uv) in cases {
+            assert_uv(UvMapping::Planar.map(p), uv);
+        }
+    }
+
+    #[test]
+    fn cylindrical_mapping() {
+        // Scenario Outline: Using a cylindrical mapping on a 3D point
+        let s2 = 2.0f64.sqrt() / 2.0;
+        let cases = [
+            (Point::new(0.0, 0.0, -1.0), (0.0, 0.0)),
+            (Point::new(0.0, 0.5, -1.0), (0.0, 0.5)),
+            (Point::new(0.0, 1.0, -1.0), (0.0, 0.0)),
+            (Point::new(s2, 0.5, -s2), (0.125, 0.5)),
+            (Point::new(1.0, 0.5, 0.0), (0.25, 0.5)),
+            (Point::new(s2, 0.5, s2), (0.375, 0.5)),
+            (Point::new(0.0, -0.25, 1.0), (0.5, 0.75)),
+            (Point::new(-s2, 0.5, s2), (0.625, 0.5)),
+            (Point::new(-1.0, 1.25, 0.0), (0.75, 0.25)),
+            (Point::new(-s2, 0.5, -s2), (0.875, 0.5)),
+        ];
+        for (p, uv) in cases {
+            assert_uv(UvMapping::Cylindrical.map(p), uv);
+        }
+    }
+
+    #[test]
+    fn cube_face_from_point() {
+        // Scenario Outline: Identifying the face of a cube from a point
+        let cases = [
+            (Point::new(-1.0, 0.5, -0.25), CubeFace::Left),
+            (Point::new(1.1, -0.75, 0.8), CubeFace::Right),
+            (Point::new(0.1, 0.6, 0.9), CubeFace::Front),
+            (Point::new(-0.7, 0.0, -2.0), CubeFace::Back),
+            (Point::new(0.5, 1.0, 0.9), CubeFace::Up),
+            (Point::new(-0.2, -1.3, 1.1), CubeFace::Down),
+        ];
+        for (p, face) in cases {
+            assert_eq!(CubeFace::from_point(p), face);
+        }
+    }
+
+    #[test]
+    fn cube_uv_per_face() {
+        // Scenarios: UV mapping the front/back/left/right/upper/lower face
+        let cases = [
+            (Point::new(-0.5, 0.5, 1.0), CubeFace::Front, (0.25, 0.75)),
+            (Point::new(0.5, -0.5, 1.0), CubeFace::Front, (0.75, 0.25)),
+            (Point::new(0.5, 0.5, -1.0), CubeFace::Back, (0.25, 0.75)),
+            (Point::new(-0.5, -0.5, -1.0), CubeFace::Back, (0.75, 0.25)),
+            (Point::new(-1.0, 0.5, -0.5), CubeFace::Left, (0.25, 0.75)),
+            (Point::new(-1.0, -0.5, 0.5), CubeFace::Left, (0.75, 0.25)),
+            (Point::new(1.0, 0.5, 0.5), CubeFace::Right, (0.25, 0.75)),
+            (Point::new(1.0, -0.5, -0.5), CubeFace::Right, (0.75, 0.25)),
+            (Point::new(-0.5, 1.0, -0.5), CubeFace::Up, (0.25, 0.75)),
+            (Point::new(0.5, 1.0, 0.5), CubeFace::Up, (0.75, 0.25)),
+            (Point::new(-0.5, -1.0, 0.5), CubeFace::Down, (0.25, 0.75)),
+            (Point::new(0.5, -1.0, -0.5), CubeFace::Down, (0.75, 0.25)),
+        ];
+        for (p, face, uv) in cases {
+            let (f, u, v) = cube_uv(p);
+            assert_eq!(f, face);
+            assert_uv((u, v), uv);
+        }
+    }
+}